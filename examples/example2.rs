@@ -1,8 +1,5 @@
 use syntree_layout::{Layouter, Visualize};
 
-#[derive(Debug)]
-struct MyNodeData(i32);
-
 enum Ast {
     Calc,
     CalcLst1,
@@ -0,0 +1,75 @@
+//! The module with a drawer that exports the embedding's coordinates as CSV.
+
+use crate::{Drawer, Embedding, Result};
+use std::io::Write;
+
+/// The header row naming the columns
+const HEADER: &str = "ord,parent,y_order,x_center,x_extent,emphasized,text";
+
+///
+/// The `CsvDrawer` type provides the transformation of the embedding information into a table of
+/// comma separated values, e.g. for the analysis in spreadsheets or with pandas. It is only
+/// available with the `csv` feature.
+///
+/// The first row is the header `ord,parent,y_order,x_center,x_extent,emphasized,text`, followed
+/// by one row per node, where `parent` is empty for roots. The coordinates are the logical ones
+/// of the embedding. Texts are quoted as described in RFC 4180 if necessary.
+///
+#[derive(Debug, Default)]
+pub struct CsvDrawer;
+
+impl CsvDrawer {
+    /// Method to create a fresh instance of the `CsvDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Escapes the given text to be used as CSV field.
+    /// Fields containing separators, quotes or line breaks are quoted, quotes are doubled.
+    fn escape(text: &str) -> String {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `CsvDrawer`.
+///
+impl Drawer for CsvDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        fn build_csv(out: &mut dyn Write, embedding: &Embedding) -> std::io::Result<()> {
+            writeln!(out, "{}", HEADER)?;
+            for data in embedding {
+                let parent = data
+                    .parent
+                    .map(|parent| parent.to_string())
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    data.ord,
+                    parent,
+                    data.y_order,
+                    data.x_center,
+                    data.x_extent,
+                    data.is_emphasized,
+                    CsvDrawer::escape(&data.text)
+                )?;
+            }
+
+            out.flush()
+        }
+
+        Ok(build_csv(writer, embedding)?)
+    }
+}
@@ -0,0 +1,103 @@
+//! The module with a drawer that emits the Graphviz DOT format.
+
+use crate::{Drawer, Embedding, LayoutDirection, Result};
+use std::io::Write;
+
+const X_SCALE: f32 = 10.0;
+const Y_SCALE: f32 = 35.0;
+
+///
+/// The `DotDrawer` type provides the transformation of the embedding information into the
+/// [Graphviz](https://graphviz.org) DOT format.
+///
+/// Each node of the embedding becomes a DOT node with its `ord` as node id and its text as label.
+/// The edges are reconstructed from the parent relationship.
+/// Since Graphviz does its own layout the computed coordinates are only emitted as `pos`
+/// attributes. To respect them use a layout engine that honors given positions, e.g.
+/// `neato -n -Gsplines=true`.
+///
+#[derive(Debug, Default)]
+pub struct DotDrawer;
+
+impl DotDrawer {
+    /// Method to create a fresh instance of the `DotDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Escapes the given text to be used inside a quoted DOT string.
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `DotDrawer`.
+///
+impl Drawer for DotDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        fn build_dot(out: &mut dyn Write, embedding: &Embedding) -> std::io::Result<()> {
+            let tree_depth =
+                embedding
+                    .iter()
+                    .fold(0, |acc, e| if e.y_order > acc { e.y_order } else { acc });
+
+            writeln!(out, "digraph {{")?;
+            writeln!(out, "    graph [splines=true];")?;
+            writeln!(out, "    node [shape=box, fontname=\"Courier\"];")?;
+
+            for data in embedding {
+                // Graphviz' y axis points upwards
+                let (x, y) = match embedding.direction() {
+                    LayoutDirection::TopToBottom => (
+                        data.x_center as f32 * X_SCALE,
+                        (tree_depth - data.y_order) as f32 * Y_SCALE,
+                    ),
+                    LayoutDirection::LeftToRight => (
+                        data.y_order as f32 * Y_SCALE * 3.0,
+                        data.x_center as f32 * -X_SCALE,
+                    ),
+                };
+                write!(
+                    out,
+                    "    {} [label=\"{}\", pos=\"{},{}!\"",
+                    data.ord,
+                    DotDrawer::escape(&data.text),
+                    x,
+                    y
+                )?;
+                if data.is_emphasized {
+                    write!(out, ", style=bold")?;
+                }
+                writeln!(out, "];")?;
+            }
+
+            for data in embedding {
+                if let Some(parent) = data.parent {
+                    writeln!(out, "    {} -> {};", parent, data.ord)?;
+                }
+            }
+
+            writeln!(out, "}}")?;
+            out.flush()
+        }
+
+        Ok(build_dot(writer, embedding)?)
+    }
+}
@@ -1,10 +1,85 @@
-//! The module with the `Drawer` trait.
-use crate::{EmbeddedNode, Result};
+//! The module with the `Drawer` and the `TypedDrawer` traits.
+use std::{fs::File, io::Write, path::Path};
+
+use crate::{EmbeddedNode, Embedding, LayouterError, Result};
 
 ///
 /// By implementing this trait anyone can provide his own drawer, for instance one that draws onto
 /// a bitmap, if he don't want to use the `SvgDrawer` used by the crate by default.
 ///
+/// A drawer should implement at least one of the two methods. Drawers that implement
+/// `draw_to_writer` get the file based `draw` for free, whereas drawers that only implement `draw`
+/// can't be used with [Layouter::write_to_string][crate::Layouter::write_to_string].
+///
 pub trait Drawer {
-    fn draw(&self, file_name: &std::path::Path, embedding: &[EmbeddedNode]) -> Result<()>;
+    /// Draws the embedding into the file with the given name.
+    /// The default implementation creates the file and delegates to `draw_to_writer`.
+    fn draw(&self, file_name: &Path, embedding: &Embedding) -> Result<()> {
+        let mut file = File::create(file_name)?;
+        self.draw_to_writer(&mut file, embedding)
+    }
+
+    /// Draws the embedding into the given writer, e.g. a `Vec<u8>`, a socket or stdout, without
+    /// the need for a temporary file.
+    /// The default implementation returns an error.
+    ///
+    /// ```
+    /// use syntree_layout::{Drawer, Layouter, SvgDrawer};
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("leaf", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// let mut buffer = Vec::new();
+    /// SvgDrawer::new()
+    ///     .draw_to_writer(&mut buffer, layouter.embedding())
+    ///     .unwrap();
+    /// assert!(String::from_utf8(buffer).unwrap().contains("<svg"));
+    ///
+    /// SvgDrawer::new()
+    ///     .draw_to_writer(&mut std::io::stdout(), layouter.embedding())
+    ///     .unwrap();
+    /// ```
+    ///
+    fn draw_to_writer(&self, _writer: &mut dyn Write, _embedding: &Embedding) -> Result<()> {
+        Err(LayouterError::WriterNotSupported)
+    }
+}
+
+///
+/// Like [Drawer] but the drawer additionally gets the nodes' original values of type T, e.g. to
+/// choose an icon or a shape by an enum variant instead of parsing the stringified labels.
+/// Typed drawers are used with [Layouter::write_typed][crate::Layouter::write_typed], or with
+/// the values of [Layouter::typed_nodes][crate::Layouter::typed_nodes].
+///
+/// A drawer should implement at least one of the two methods, see [Drawer].
+///
+pub trait TypedDrawer<T> {
+    /// Draws the embedding into the file with the given name. `nodes` holds each embedded node
+    /// together with its value, in the order of the embedding, i.e. by `ord`.
+    /// The default implementation creates the file and delegates to `draw_typed_to_writer`.
+    fn draw_typed(
+        &self,
+        file_name: &Path,
+        embedding: &Embedding,
+        nodes: &[(&T, &EmbeddedNode)],
+    ) -> Result<()> {
+        let mut file = File::create(file_name)?;
+        self.draw_typed_to_writer(&mut file, embedding, nodes)
+    }
+
+    /// Draws the embedding into the given writer, see
+    /// [draw_typed][TypedDrawer::draw_typed].
+    /// The default implementation returns an error.
+    fn draw_typed_to_writer(
+        &self,
+        _writer: &mut dyn Write,
+        _embedding: &Embedding,
+        _nodes: &[(&T, &EmbeddedNode)],
+    ) -> Result<()> {
+        Err(LayouterError::WriterNotSupported)
+    }
 }
@@ -1,41 +1,621 @@
 //! The module with the data structures used in the **Public API**.
 
-use syntree::pointer::Width;
+use std::ops::Deref;
 
-use crate::internal::node::InternalNode;
+use syntree::pointer::{Pointer, Width};
+
+use crate::{
+    internal::{node::InternalNode, options::EmbedderOptions},
+    NodeShape,
+};
+
+///
+/// The [LayoutDirection] determines in which direction the layers of the tree grow.
+/// It can be set with [Layouter::with_direction][crate::Layouter::with_direction].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutDirection {
+    /// The root is placed at the top, the children fan out downwards.
+    /// The layers are stacked vertically and siblings are placed side by side horizontally.
+    #[default]
+    TopToBottom,
+    /// The root is placed at the left, the children fan out rightwards.
+    /// The layers are stacked horizontally and siblings are placed on top of each other.
+    LeftToRight,
+}
+
+///
+/// The [LayoutStyle] determines how the layers of the tree are arranged.
+/// It can be set with [Layouter::with_layout_style][crate::Layouter::with_layout_style].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutStyle {
+    /// The layers are parallel lines, see [LayoutDirection].
+    #[default]
+    Layered,
+    /// The root is placed at the center and the layers are concentric rings around it, which is
+    /// space-efficient for balanced trees. The logical x coordinate maps to the angle, the layer
+    /// to the radius. Each subtree gets an angular span proportional to its number of leaves.
+    Radial,
+}
+
+///
+/// The [ParentAlignment] determines where a parent is placed relative to its children.
+/// It can be set with [Layouter::with_parent_alignment][crate::Layouter::with_parent_alignment].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParentAlignment {
+    /// The parent is centered over all of its children.
+    #[default]
+    Center,
+    /// The parent is placed over its first child, which suits list-like trees.
+    OverFirstChild,
+    /// The parent is placed over its last child.
+    OverLastChild,
+}
+
+///
+/// The [Rounding] determines how the half width of the children is rounded when a parent is
+/// centered over several children whose width is odd.
+/// It can be set with [Layouter::with_rounding][crate::Layouter::with_rounding].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// The half width is rounded down, which places such parents half a column left of the
+    /// middle.
+    #[default]
+    Floor,
+    /// The half width is rounded up, which places such parents half a column right of the
+    /// middle.
+    Ceil,
+    /// The half width is rounded to the nearest even number, so parents are alternately placed
+    /// left and right of the middle and the bias evens out over the tree.
+    Nearest,
+}
+
+impl Rounding {
+    /// Half of the given width, rounded
+    pub(crate) fn half(self, width: usize) -> usize {
+        let half = width / 2;
+        match self {
+            Rounding::Floor => half,
+            Rounding::Ceil => width - half,
+            Rounding::Nearest if width % 2 == 1 && half % 2 == 1 => half + 1,
+            Rounding::Nearest => half,
+        }
+    }
+}
 
 ///
 /// The Embedding is the interface to drawers that need the embedding for the purpose
 /// to transform it to their own output format.
 /// It is accessible at the [Layouter][crate::Layouter::embedding] after calling an embed method
 ///
-pub type Embedding = Vec<EmbeddedNode>;
+/// The embedding dereferences to a slice of [EmbeddedNode]s and additionally provides the
+/// properties that apply to the embedding as a whole. The embedding of an empty tree is empty,
+/// the drawers nevertheless produce valid output for it.
+///
+/// # Coordinate conventions
+///
+/// All coordinates are logical and unit-less, it is up to the drawer to scale them.
+/// * The origin is the upper left corner of the layout. No node extends to negative coordinates.
+/// * The x axis is measured in columns. A node's text occupies the columns of its widest line,
+///   which are measured with the character widths of
+///   [Layouter::with_char_width][crate::Layouter::with_char_width] resp. the text measure of
+///   [Layouter::with_text_measure][crate::Layouter::with_text_measure], see
+///   [EmbeddedNode::line_widths], or with
+///   [Layouter::with_span_layout][crate::Layouter::with_span_layout] the length of its span
+///   instead. The node's [EmbeddedNode::x_extent] widens it to the minimum
+///   node width, adds the horizontal padding on both sides and the spacing to the siblings, which
+///   is one column by default, see
+///   [Layouter::with_sibling_spacing][crate::Layouter::with_sibling_spacing].
+/// * A node horizontally spans `x_center - x_extent / 2` to `x_center + x_extent / 2`. Its
+///   subtree spans `x_center - x_extent_children / 2` to `x_center + x_extent_children / 2`,
+///   except for the compact layout, see
+///   [Layouter::with_compaction][crate::Layouter::with_compaction], and for parents that aren't
+///   centered, see
+///   [Layouter::with_parent_alignment][crate::Layouter::with_parent_alignment].
+/// * The y axis is measured in layers, see [EmbeddedNode::y_order]. The root is in layer 0 and
+///   each child is one layer below its parent, except for leaves placed in the last layer, see
+///   [Layouter::with_leaves_at_bottom][crate::Layouter::with_leaves_at_bottom].
+/// * On each layer the nodes of sibling subtrees don't overlap and keep the order of the
+///   siblings. Thus straight edges between parents and their children never cross, and the order
+///   of the children is always the one of the tree.
+///
+/// With [LayoutDirection::LeftToRight] the roles of the axes are swapped.
+///
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Embedding {
+    nodes: Vec<EmbeddedNode>,
+    direction: LayoutDirection,
+    layer_spacing: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    layout_style: LayoutStyle,
+    #[cfg_attr(feature = "serde", serde(default))]
+    min_node_width: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    horizontal_padding: usize,
+    /// The positions of the nodes indexed by their ids, see [Embedding::by_node_id]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    positions: Vec<Option<usize>>,
+}
+
+impl Embedding {
+    pub(crate) fn new<T, W: Width>(
+        nodes: Vec<EmbeddedNode>,
+        options: &EmbedderOptions<T, W>,
+    ) -> Self {
+        let positions = Self::positions(&nodes);
+        Self {
+            nodes,
+            positions,
+            direction: options.direction(),
+            layer_spacing: options.layer_spacing,
+            layout_style: options.layout_style,
+            min_node_width: options.min_node_width,
+            horizontal_padding: options.horizontal_padding,
+        }
+    }
+
+    /// Lets the layouter's post embed hook update the nodes, which may change their ids
+    pub(crate) fn update_nodes(&mut self, update: impl FnOnce(&mut [EmbeddedNode])) {
+        update(&mut self.nodes);
+        self.positions = Self::positions(&self.nodes);
+    }
+
+    fn positions(nodes: &[EmbeddedNode]) -> Vec<Option<usize>> {
+        let len = nodes
+            .iter()
+            .map(|e| e.node_id + 1)
+            .max()
+            .unwrap_or_default();
+        let mut positions = vec![None; len];
+        for (position, e) in nodes.iter().enumerate() {
+            positions[e.node_id] = Some(position);
+        }
+        positions
+    }
+
+    /// The arrangement of the layers, drawers that don't support [LayoutStyle::Radial] can use
+    /// the coordinates like the ones of a layered embedding
+    pub fn layout_style(&self) -> LayoutStyle {
+        self.layout_style
+    }
+
+    /// The direction in which the layers of the embedding grow
+    pub fn direction(&self) -> LayoutDirection {
+        self.direction
+    }
+
+    /// The spacing between two adjacent layers, in empty text lines for
+    /// [LayoutDirection::TopToBottom] and in empty text columns for [LayoutDirection::LeftToRight].
+    /// Drawers should respect this value when they map layers to their own coordinates.
+    pub fn layer_spacing(&self) -> usize {
+        self.layer_spacing
+    }
+
+    /// The minimum width of the nodes in text columns, see
+    /// [Layouter::with_min_node_width][crate::Layouter::with_min_node_width]. Drawers should
+    /// draw the boxes of nodes with narrower texts this wide.
+    pub fn min_node_width(&self) -> usize {
+        self.min_node_width
+    }
+
+    /// The padding of the nodes in text columns on both sides of their labels, see
+    /// [Layouter::with_horizontal_padding][crate::Layouter::with_horizontal_padding]. Drawers
+    /// should widen the boxes by it and keep the labels centered.
+    pub fn horizontal_padding(&self) -> usize {
+        self.horizontal_padding
+    }
+
+    ///
+    /// The width and height of the embedding in logical units, see the coordinate conventions
+    /// above.
+    /// The width is the right border of the rightmost node, i.e. the maximum of
+    /// `x_center + x_extent / 2`, which is also the right border of the rightmost subtree and
+    /// thus spans all roots of a forest.
+    /// The height is the number of layers.
+    /// An empty embedding has the dimensions `(0, 0)`.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    ///
+    /// #[derive(Debug)]
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> String {
+    ///         self.0.to_string()
+    ///     }
+    /// }
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// tree.token(MyNodeData(2), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    /// assert_eq!((4, 2), layouter.embedding().dimensions());
+    /// ```
+    ///
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.nodes.iter().fold((0, 0), |(width, height), e| {
+            let right = e.x_center + e.x_extent - e.x_extent / 2;
+            (width.max(right), height.max(e.y_order + 1))
+        })
+    }
+
+    ///
+    /// Looks up the embedded node of the tree's node with the given id, e.g. to find the
+    /// coordinates of a node obtained from an own traversal of the tree.
+    /// Returns `None` if the node isn't part of the embedding, e.g. because it is deeper than the
+    /// maximum depth.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("first", 1).unwrap();
+    /// let second = tree.token("second", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// let node = layouter.embedding().by_node_id(second).unwrap();
+    /// assert_eq!("second", node.text);
+    /// ```
+    ///
+    /// # Complexity
+    ///
+    /// The lookup is of time complexity class O(1), the index of the node ids is built once
+    /// while embedding. The index isn't serialized, so for a deserialized embedding the lookup
+    /// falls back to a linear search of time complexity class O(n).
+    ///
+    pub fn by_node_id<P: Pointer>(&self, id: P) -> Option<&EmbeddedNode> {
+        let node_id = id.get();
+        match self.positions.is_empty() {
+            true => self.nodes.iter().find(|e| e.node_id == node_id),
+            false => self
+                .positions
+                .get(node_id)
+                .copied()
+                .flatten()
+                .map(|position| &self.nodes[position]),
+        }
+    }
+
+    ///
+    /// The edges of the embedding as pairs of a parent and one of its children, in the order of
+    /// the children. Roots have no edge to a parent, so there are as many edges as nodes minus
+    /// roots.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("first", 1).unwrap();
+    /// tree.token("second", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// let edges = layouter
+    ///     .embedding()
+    ///     .edges()
+    ///     .map(|(parent, child)| (parent.text.as_str(), child.text.as_str()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(vec![("root", "first"), ("root", "second")], edges);
+    /// ```
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n) for embeddings created by the
+    /// [Layouter][crate::Layouter].
+    ///
+    pub fn edges(&self) -> impl Iterator<Item = (&EmbeddedNode, &EmbeddedNode)> {
+        self.nodes.iter().filter_map(move |child| {
+            let ord = child.parent?;
+            // The nodes are sorted by their dense ord, so the parent is usually found at its
+            // index
+            let parent = match self.nodes.get(ord) {
+                Some(parent) if parent.ord == ord => parent,
+                _ => self.nodes.iter().find(|e| e.ord == ord)?,
+            };
+            Some((parent, child))
+        })
+    }
+
+    ///
+    /// Checks the placement of the nodes and returns the `ord`s of two nodes on the same layer
+    /// whose horizontal spans overlap, see the coordinate conventions above. `None` is the
+    /// result for all embeddings created by the [Layouter][crate::Layouter], which verifies
+    /// this in debug builds.
+    /// Nodes without extent occupy no space and thus never overlap. Nodes that were moved beyond
+    /// the origin, e.g. by [Layouter::with_post_embed][crate::Layouter::with_post_embed], are
+    /// checked with their negative borders.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n log n).
+    ///
+    pub fn find_overlap(&self) -> Option<(usize, usize)> {
+        let mut spans = self
+            .nodes
+            .iter()
+            .filter(|e| e.x_extent > 0)
+            .map(|e| {
+                let left = e.x_center as isize - (e.x_extent / 2) as isize;
+                (e.y_order, left, left + e.x_extent as isize, e.ord)
+            })
+            .collect::<Vec<_>>();
+        spans.sort_unstable();
+        spans
+            .windows(2)
+            .find(|w| w[0].0 == w[1].0 && w[1].1 < w[0].2)
+            .map(|w| (w[0].3, w[1].3))
+    }
+
+    ///
+    /// The height of the embedding, i.e. the maximum `y_order` of all nodes, which is the
+    /// depth of the deepest node unless the layout is inverted. An empty embedding has the
+    /// height 0, like an embedding of a single node.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("leaf", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// assert_eq!(1, layouter.embedding().height());
+    /// ```
+    ///
+    pub fn height(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|e| e.y_order)
+            .max()
+            .unwrap_or_default()
+    }
+
+    ///
+    /// The number of nodes in each layer, indexed by the layer's `y_order`.
+    /// This helps e.g. to choose the spacing or to decide whether to collapse deep subtrees with
+    /// [Layouter::with_max_depth][crate::Layouter::with_max_depth] before drawing.
+    /// An empty embedding has no layers.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("first", 1).unwrap();
+    /// tree.token("second", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// assert_eq!(vec![1, 2], layouter.embedding().layer_widths());
+    /// ```
+    ///
+    pub fn layer_widths(&self) -> Vec<usize> {
+        let mut layer_widths = vec![0; self.dimensions().1];
+        for e in &self.nodes {
+            layer_widths[e.y_order] += 1;
+        }
+        layer_widths
+    }
+
+    ///
+    /// The number of text lines of each layer, i.e. the maximum
+    /// [line_count][EmbeddedNode::line_count] of the layer's nodes, indexed by the layer's
+    /// `y_order`. Drawers should make each layer this high, so that labels with several lines
+    /// don't collide with the next layer.
+    /// An empty embedding has no layers.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("first\nline", 1).unwrap();
+    /// tree.token("second", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// assert_eq!(vec![1, 2], layouter.embedding().layer_heights());
+    /// ```
+    ///
+    pub fn layer_heights(&self) -> Vec<usize> {
+        let mut layer_heights = vec![1; self.dimensions().1];
+        for e in &self.nodes {
+            layer_heights[e.y_order] = layer_heights[e.y_order].max(e.line_count);
+        }
+        layer_heights
+    }
+}
+
+impl Deref for Embedding {
+    type Target = [EmbeddedNode];
+
+    fn deref(&self) -> &Self::Target {
+        &self.nodes
+    }
+}
+
+impl<'a> IntoIterator for &'a Embedding {
+    type Item = &'a EmbeddedNode;
+    type IntoIter = std::slice::Iter<'a, EmbeddedNode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.iter()
+    }
+}
 
 ///
 /// The [EmbeddedNode] is the embedding information for one single tree node.
 /// It is used only in a collection type `Embedding`.
 ///
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EmbeddedNode {
-    /// The nodes level, root has level 0. Can be used to calculate an y coordinate for the node
+    /// The nodes level, root has level 0. Can be used to calculate an y coordinate for the node.
+    /// In [LayoutDirection::LeftToRight] it is used to calculate the x coordinate instead.
     pub y_order: usize,
-    /// The logical x coordinate of the node's center
+    /// The logical x coordinate of the node's center.
+    /// In [LayoutDirection::LeftToRight] it is the logical y coordinate of the node's center.
     pub x_center: usize,
-    /// The x-extent of the nodes text representation in logical coordinate units
+    /// The x-extent of the nodes text representation in logical coordinate units.
+    /// In [LayoutDirection::LeftToRight] it is the extent along the y axis, i.e. the height of the
+    /// node's text representation.
     pub x_extent: usize,
-    /// The maximum extent over the nodes text representation and the sum of all children's x-extent
+    /// The maximum extent over the nodes text representation and the sum of all children's
+    /// x-extent.
+    /// In the compact layout and for parents that aren't centered it is the width of the
+    /// subtree, which isn't necessarily centered below the node.
     pub x_extent_children: usize,
     /// The text representation of the nodes data - created e.g. by the `Visualize` trait's
     /// implementation, by the node type's Display or Debug implementation or by custom methods
     pub text: String,
+    /// The complete text representation if `text` was truncated because it exceeded the maximum
+    /// label width, see [Layouter::with_max_label_width][crate::Layouter::with_max_label_width]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub full_text: Option<String>,
+    /// The number of lines of the text representation, which is at least one. Drawers should
+    /// grow the node's box to fit all lines.
+    #[cfg_attr(feature = "serde", serde(default = "default_line_count"))]
+    pub line_count: usize,
+    /// The width of each line of the text representation in columns, as the layouter measured it
+    /// with the character widths of
+    /// [Layouter::with_char_width][crate::Layouter::with_char_width] resp. with the text measure
+    /// of [Layouter::with_text_measure][crate::Layouter::with_text_measure]. Drawers should fit
+    /// the node's box to it. Nodes without measured widths, e.g. deserialized from an older
+    /// version, count one column per character.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line_widths: Vec<usize>,
     /// The *emphasize* property obtained from the `Visualize` trait or via a custom method
     pub is_emphasized: bool,
+    /// The optional background color obtained from the `Visualize` trait. It can be any color
+    /// value the drawer understands, e.g. `"#ffcc00"` or `"yellow"` for the `SvgDrawer`.
+    pub background_color: Option<String>,
+    /// The optional tooltip obtained from the `Visualize` trait, the `SvgDrawer` shows it when
+    /// hovering the node
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tooltip: Option<String>,
+    /// The optional shape obtained from the `Visualize` trait, which overrides the drawer's
+    /// default shape
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shape: Option<NodeShape>,
+    /// The optional URL obtained from the `Visualize` trait, the `SvgDrawer` makes the node a
+    /// hyperlink to it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub link: Option<String>,
+    /// The optional label of the edge to the parent obtained from the `Visualize` trait, the
+    /// `SvgDrawer` draws it at the edge's midpoint
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub edge_label: Option<String>,
+    /// The reduced opacity of a node outside the focused subtree, see
+    /// [Layouter::with_focus][crate::Layouter::with_focus]. Drawers should draw the node and the
+    /// edge to its parent with it, `None` stands for full opacity.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub opacity: Option<f32>,
     /// The parent's `ord`, if there is one
     pub parent: Option<usize>,
-    /// A unique number reflecting the topological post-ordering of the nodes in the tree
+    /// The number of the node's children in the embedding, i.e. a leaf has none. Children
+    /// beyond the [maximum depth][crate::Layouter::with_max_depth] aren't counted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub child_count: usize,
+    /// The dense pre-order index of the node, i.e. the nodes are numbered from `0` in the order of
+    /// a depth-first traversal that visits each parent before its children and the children in
+    /// their order in the tree, resp. in the order of
+    /// [Layouter::with_sibling_sort][crate::Layouter::with_sibling_sort]. The embedding's nodes
+    /// are sorted by it.
     pub ord: usize,
+    /// The value of the node's id in the tree, see [Embedding::by_node_id]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub node_id: usize,
 }
 
+/// The line count of nodes deserialized from before the field existed, which had single lines
+#[cfg(feature = "serde")]
+fn default_line_count() -> usize {
+    1
+}
+
+impl EmbeddedNode {
+    /// The column width of the text's line with the given index, drawers use it to fit the
+    /// node's box to the embedder's measurement
+    pub(crate) fn line_width(&self, index: usize, line: &str) -> usize {
+        self.line_widths
+            .get(index)
+            .copied()
+            .unwrap_or_else(|| line.chars().count())
+    }
+
+    /// The column width of the text's widest line
+    pub(crate) fn text_width(&self) -> usize {
+        self.text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| self.line_width(index, line))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+///
+/// The nodes of an embedding as an iterator, which converts the layouter's internal nodes one at
+/// a time instead of collecting them into an [Embedding]. Drawers that need only one pass over
+/// the nodes in the order of their `ord`, like
+/// [JsonDrawer::draw_nodes_to_writer][crate::JsonDrawer::draw_nodes_to_writer], can this way
+/// draw huge trees without holding a second copy of the nodes.
+/// It is created by [Layouter::embed_streaming_with][crate::Layouter::embed_streaming_with] and
+/// yields the same nodes in the same order as the embedding.
+///
+pub struct EmbeddedNodes<W: Width> {
+    items: std::vec::IntoIter<InternalNode<W>>,
+    /// The height of the tree if the layers are inverted
+    inverted_height: Option<usize>,
+}
+
+impl<W: Width> EmbeddedNodes<W> {
+    pub(crate) fn new(items: Vec<InternalNode<W>>, inverted_height: Option<usize>) -> Self {
+        Self {
+            items: items.into_iter(),
+            inverted_height,
+        }
+    }
+}
+
+impl<W: Width> Iterator for EmbeddedNodes<W> {
+    type Item = EmbeddedNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut item = self.items.next()?;
+        if let Some(height) = self.inverted_height {
+            item.y_order = height - item.y_order;
+        }
+        Some(item.into())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<W: Width> ExactSizeIterator for EmbeddedNodes<W> {}
+
 ///
 /// Conversion form internal to external (i.e. public) representation of the embedding structure.
 ///
@@ -43,13 +623,30 @@ impl<P: Width> From<InternalNode<P>> for EmbeddedNode {
     fn from(e: InternalNode<P>) -> Self {
         Self {
             y_order: e.y_order,
-            x_center: e.x_center,
+            x_center: {
+                debug_assert!(
+                    e.x_center >= 0,
+                    "Nodes should have been shifted to the origin"
+                );
+                e.x_center.max(0) as usize
+            },
             x_extent: e.x_extent,
             x_extent_children: e.x_extent_children,
             text: e.text,
+            full_text: e.full_text,
+            line_count: e.line_count,
+            line_widths: e.line_widths,
             is_emphasized: e.is_emphasized,
+            background_color: e.background_color,
+            tooltip: e.tooltip,
+            shape: e.shape,
+            link: e.link,
+            edge_label: e.edge_label,
+            opacity: e.opacity,
             parent: e.parent,
+            child_count: e.child_count,
             ord: e.ord,
+            node_id: e.node_id.get(),
         }
     }
 }
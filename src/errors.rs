@@ -6,22 +6,53 @@ use thiserror::Error;
 ///
 #[derive(Error, Debug)]
 pub enum LayouterError {
-    #[error("IoError: {source}")]
-    IoError { source: std::io::Error },
-    #[error("Error from tree implementation: {source}")]
-    TreeError { source: syntree::Error },
-    #[error("Error occurred: {msg}")]
-    OtherError { msg: String },
+    /// An I/O error occurred, e.g. while writing the output file. It converts from
+    /// [std::io::Error] and is the error's [source][std::error::Error::source].
+    #[error("IoError: {0}")]
+    Io(#[from] std::io::Error),
+    /// An error reported by the tree implementation
+    #[error("Error from tree implementation: {0}")]
+    Tree(#[source] syntree::Error),
+    /// [Layouter::write][crate::Layouter::write] was called without an output file
+    #[error("No output file name given - use Layouter::with_file_path.")]
+    MissingOutputPath,
+    /// The drawer doesn't support [Drawer::draw_to_writer][crate::Drawer::draw_to_writer]
+    #[error("This drawer doesn't support drawing to a writer")]
+    WriterNotSupported,
+    /// The width of the layout exceeds the maximum, see
+    /// [Layouter::with_max_layout_width][crate::Layouter::with_max_layout_width]
+    #[error("The layout is wider than the maximum of {0} units")]
+    LayoutTooLarge(usize),
+    /// The parent of the node with the given `ord` wasn't embedded before the node, which only
+    /// happens for malformed trees. The node would otherwise be laid out as an additional root.
+    #[error("The parent of the node {0} is missing in the embedding")]
+    MissingParent(usize),
+    /// The tree has the given number of roots, but a single root is required, see
+    /// [Layouter::with_single_root][crate::Layouter::with_single_root]
+    #[error("The tree has {found} roots, but a single root is required")]
+    MultipleRoots {
+        /// The number of roots of the tree
+        found: usize,
+    },
+    /// The subtree root isn't a node of the tree, see
+    /// [Layouter::with_subtree_root][crate::Layouter::with_subtree_root]
+    #[error("The subtree root is not a node of the tree")]
+    UnknownSubtreeRoot,
+    /// The focused node isn't embedded, e.g. because it's outside of the subtree root or deeper
+    /// than the maximum depth, see [Layouter::with_focus][crate::Layouter::with_focus]
+    #[error("The focused node is not an embedded node of the tree")]
+    UnknownFocus,
+    /// Any other error, described by a message
+    #[error("Error occurred: {0}")]
+    Description(String),
 }
 
 impl LayouterError {
     pub fn from_description(description: &str) -> Self {
-        LayouterError::OtherError {
-            msg: description.to_string(),
-        }
+        LayouterError::Description(description.to_string())
     }
     pub fn from_io_error(io_error: std::io::Error) -> Self {
-        LayouterError::IoError { source: io_error }
+        LayouterError::Io(io_error)
     }
 }
 
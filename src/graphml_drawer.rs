@@ -0,0 +1,109 @@
+//! The module with a drawer that exports the embedding as GraphML document.
+
+use crate::{Drawer, Embedding, LayoutDirection, Result};
+use std::io::Write;
+use xml_writer::XmlWriter;
+
+/// The id of the key of the nodes' labels
+const LABEL_KEY: &str = "label";
+/// The id of the key of the nodes' x coordinates
+const X_KEY: &str = "x";
+/// The id of the key of the nodes' y coordinates
+const Y_KEY: &str = "y";
+
+///
+/// The `GraphMlDrawer` type provides the transformation of the embedding information into a
+/// [GraphML](http://graphml.graphdrawing.org) document, which can be imported by graph tools like
+/// yEd or Gephi.
+///
+/// Each node of the embedding becomes a `<node>` with the id `n{ord}` and the data keys `label`,
+/// `x` and `y`. The coordinates are the logical ones of the embedding, i.e. `x` is the node's
+/// `x_center` and `y` is its `y_order`, in [LayoutDirection::LeftToRight] the other way round.
+/// The edges are reconstructed from the parent relationship and are directed from the parent to
+/// the child.
+///
+#[derive(Debug, Default)]
+pub struct GraphMlDrawer;
+
+impl GraphMlDrawer {
+    /// Method to create a fresh instance of the `GraphMlDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `GraphMlDrawer`.
+///
+impl Drawer for GraphMlDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        let xml = XmlWriter::new(writer);
+
+        fn build_xml(
+            mut xml: XmlWriter<&mut dyn Write>,
+            embedding: &Embedding,
+        ) -> std::io::Result<()> {
+            xml.dtd("UTF-8")?;
+            xml.begin_elem("graphml")?;
+            xml.attr("xmlns", "http://graphml.graphdrawing.org/xmlns")?;
+
+            for (id, ty) in [(LABEL_KEY, "string"), (X_KEY, "double"), (Y_KEY, "double")] {
+                xml.begin_elem("key")?;
+                xml.attr("id", id)?;
+                xml.attr("for", "node")?;
+                xml.attr("attr.name", id)?;
+                xml.attr("attr.type", ty)?;
+                xml.end_elem()?;
+            }
+
+            xml.begin_elem("graph")?;
+            xml.attr("id", "tree")?;
+            xml.attr("edgedefault", "directed")?;
+
+            for data in embedding {
+                let (x, y) = match embedding.direction() {
+                    LayoutDirection::TopToBottom => (data.x_center, data.y_order),
+                    LayoutDirection::LeftToRight => (data.y_order, data.x_center),
+                };
+                xml.begin_elem("node")?;
+                xml.attr("id", format!("n{}", data.ord).as_str())?;
+                for (key, value) in [
+                    (LABEL_KEY, data.text.clone()),
+                    (X_KEY, x.to_string()),
+                    (Y_KEY, y.to_string()),
+                ] {
+                    xml.begin_elem("data")?;
+                    xml.attr("key", key)?;
+                    xml.text(&value)?;
+                    xml.end_elem()?;
+                }
+                xml.end_elem()?;
+            }
+
+            for data in embedding {
+                if let Some(parent) = data.parent {
+                    xml.begin_elem("edge")?;
+                    xml.attr("source", format!("n{}", parent).as_str())?;
+                    xml.attr("target", format!("n{}", data.ord).as_str())?;
+                    xml.end_elem()?;
+                }
+            }
+
+            xml.end_elem()?;
+            xml.end_elem()?;
+            xml.close()?;
+            xml.flush()?;
+
+            Ok(())
+        }
+
+        Ok(build_xml(xml, embedding)?)
+    }
+}
@@ -0,0 +1,172 @@
+//! The module with a drawer that embeds the svg output into an HTML page for exploring it in a
+//! browser.
+
+use crate::{Drawer, Embedding, LayouterError, Result, SvgDrawer};
+use std::io::Write;
+
+/// The script that pans the image by dragging and zooms it with the mouse wheel around the
+/// pointer. It changes the `viewBox` of the svg, a double click restores the initial one.
+const PAN_ZOOM_SCRIPT: &str = r##"(function () {
+  const svg = document.querySelector("#viewport > svg");
+  const box = svg.viewBox.baseVal;
+  const initial = [box.x, box.y, box.width, box.height];
+  const point = (event) =>
+    new DOMPoint(event.clientX, event.clientY).matrixTransform(svg.getScreenCTM().inverse());
+  let start = null;
+  svg.addEventListener("wheel", (event) => {
+    event.preventDefault();
+    const factor = Math.exp(event.deltaY * 0.001);
+    const p = point(event);
+    box.x = p.x - (p.x - box.x) * factor;
+    box.y = p.y - (p.y - box.y) * factor;
+    box.width *= factor;
+    box.height *= factor;
+  }, { passive: false });
+  svg.addEventListener("pointerdown", (event) => {
+    start = point(event);
+    svg.setPointerCapture(event.pointerId);
+  });
+  svg.addEventListener("pointermove", (event) => {
+    if (start) {
+      const p = point(event);
+      box.x -= p.x - start.x;
+      box.y -= p.y - start.y;
+    }
+  });
+  svg.addEventListener("pointerup", () => { start = null; });
+  svg.addEventListener("dblclick", () => {
+    [box.x, box.y, box.width, box.height] = initial;
+  });
+})();"##;
+
+///
+/// The `HtmlDrawer` type provides the transformation of the embedding information into a
+/// self-contained HTML page, which is suitable to explore large trees in a browser.
+///
+/// The page shows the output of the [SvgDrawer] in a viewport that fills the browser window.
+/// The image can be panned by dragging and zoomed with the mouse wheel, a double click restores
+/// the initial view. The script and the styles are part of the page, so it has no external
+/// dependencies.
+///
+/// ```
+/// use syntree_layout::{HtmlDrawer, SvgDrawer};
+///
+/// let drawer = HtmlDrawer::new()
+///     .with_title("Syntax tree")
+///     .with_svg_drawer(SvgDrawer::new().with_arrowheads(true));
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct HtmlDrawer {
+    svg_drawer: SvgDrawer,
+    title: String,
+}
+
+impl Default for HtmlDrawer {
+    fn default() -> Self {
+        Self {
+            svg_drawer: SvgDrawer::default(),
+            title: "Tree".to_string(),
+        }
+    }
+}
+
+impl HtmlDrawer {
+    /// Method to create a fresh instance of the `HtmlDrawer` type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Sets the title of the page. The default is "Tree".
+    ///
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    ///
+    /// Sets the `SvgDrawer` that creates the image shown on the page. Its fragment mode is
+    /// switched off, because the viewport needs the image's `viewBox`.
+    /// By default an `SvgDrawer` with default settings is used.
+    ///
+    pub fn with_svg_drawer(mut self, svg_drawer: SvgDrawer) -> Self {
+        self.svg_drawer = svg_drawer.with_fragment_mode(false);
+        self
+    }
+
+    /// Escapes the characters with a special meaning in HTML text
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `HtmlDrawer`.
+///
+impl Drawer for HtmlDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        let mut svg = Vec::new();
+        self.svg_drawer.draw_to_writer(&mut svg, embedding)?;
+        let svg = String::from_utf8(svg)
+            .map_err(|_| LayouterError::from_description("The svg output is not valid UTF-8"))?;
+        // The XML prolog isn't allowed within HTML
+        let svg = svg.find("<svg").map_or(svg.as_str(), |start| &svg[start..]);
+
+        fn build_html(
+            out: &mut dyn Write,
+            title: &str,
+            svg: &str,
+            background: &str,
+        ) -> std::io::Result<()> {
+            writeln!(out, "<!DOCTYPE html>")?;
+            writeln!(out, "<html lang=\"en\">")?;
+            writeln!(out, "<head>")?;
+            writeln!(out, "<meta charset=\"utf-8\">")?;
+            writeln!(
+                out,
+                "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+            )?;
+            writeln!(out, "<title>{}</title>", title)?;
+            writeln!(out, "<style>")?;
+            writeln!(
+                out,
+                "html, body {{ margin: 0; height: 100%; overflow: hidden; background: {}; }}",
+                background
+            )?;
+            writeln!(out, "#viewport {{ width: 100vw; height: 100vh; }}")?;
+            writeln!(
+                out,
+                "#viewport > svg {{ width: 100%; height: 100%; cursor: grab; touch-action: none; }}"
+            )?;
+            writeln!(out, "</style>")?;
+            writeln!(out, "</head>")?;
+            writeln!(out, "<body>")?;
+            writeln!(out, "<div id=\"viewport\">")?;
+            writeln!(out, "{}", svg)?;
+            writeln!(out, "</div>")?;
+            writeln!(out, "<script>")?;
+            writeln!(out, "{}", PAN_ZOOM_SCRIPT)?;
+            writeln!(out, "</script>")?;
+            writeln!(out, "</body>")?;
+            writeln!(out, "</html>")?;
+            out.flush()
+        }
+
+        Ok(build_html(
+            writer,
+            &Self::escape(&self.title),
+            svg,
+            &self.svg_drawer.theme().background,
+        )?)
+    }
+}
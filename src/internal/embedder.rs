@@ -3,11 +3,66 @@
 use syntree::{index::Index, node::Event, pointer::Width, Node, Tree};
 
 use crate::{
-    layouter::{EmphasizeFunction, StringifyFunction},
-    Embedding, LayouterError, Result,
+    measure::{line_count, line_widths, measured_line_widths, truncate, wrap},
+    EmbeddedNodes, Embedding, LayoutDirection, LayoutStyle, LayouterError, NodeShape,
+    ParentAlignment, Result,
 };
 
-use super::node::{EmbeddingHelperData, InternalNode};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::{
+    node::{EmbeddingHelperData, InternalNode, NodeId},
+    options::EmbedderOptions,
+    visualizer::NodeVisualizer,
+};
+
+/// The opacity of the nodes outside the focused subtree
+const CONTEXT_OPACITY: f32 = 0.3;
+
+/// A node of the tree together with its depth
+type NodeAtDepth<'a, T, I, W> = (usize, Node<'a, T, I, W>);
+
+///
+/// The data that places siblings side by side, indexed by the nodes' ords.
+/// It doesn't change while the nodes are centered and is free of the tree's types, so it can be
+/// shared between threads.
+///
+struct SiblingMetrics<'a> {
+    /// The x center of each node relative to the left border of its children
+    offsets: Vec<isize>,
+    /// The x center of each node relative to the left border of its subtree
+    shifts: Vec<isize>,
+    /// The `x_extent_children` of each node
+    extents: Vec<usize>,
+    /// The ords of each node's children
+    children: &'a [Vec<usize>],
+    /// The gap between sibling subtrees
+    gutter: usize,
+    /// Places the siblings from right to left
+    right_to_left: bool,
+    /// Places the sibling groups of a layer concurrently
+    #[cfg(feature = "rayon")]
+    parallel: bool,
+}
+
+impl SiblingMetrics<'_> {
+    /// The x centers of the given siblings placed side by side, starting at the given x
+    /// coordinate. For right-to-left placement the first sibling is placed rightmost.
+    fn place(&self, ords: &[usize], start: isize, gap: usize) -> Vec<(usize, isize)> {
+        let mut moving_x_center = start;
+        let mut place = |ord: &usize| {
+            let x_center = moving_x_center + self.shifts[*ord];
+            moving_x_center += (self.extents[*ord] + gap) as isize;
+            (*ord, x_center)
+        };
+        if self.right_to_left {
+            ords.iter().rev().map(&mut place).collect()
+        } else {
+            ords.iter().map(&mut place).collect()
+        }
+    }
+}
 
 ///
 /// The Embedder type provides a single (accessible) method `embed` to arrange nodes of a tree into
@@ -39,27 +94,60 @@ where
     ///
     pub(crate) fn embed(
         tree: &Tree<T, I, W>,
-        stringify: StringifyFunction<T>,
-        emphasize: EmphasizeFunction<T>,
+        visualizer: &dyn NodeVisualizer<T>,
+        options: &EmbedderOptions<T, W>,
     ) -> Result<Embedding> {
+        let nodes = Self::embed_streaming(tree, visualizer, options)?;
+        let embedding = Embedding::new(nodes.collect(), options);
+        // A safety net against layout bugs, which release builds don't pay for
+        debug_assert_eq!(
+            None,
+            embedding.find_overlap(),
+            "The nodes with these ords overlap"
+        );
+        Ok(embedding)
+    }
+
+    ///
+    /// Like [Embedder::embed] but the nodes are converted one at a time while they are iterated.
+    ///
+    pub(crate) fn embed_streaming(
+        tree: &Tree<T, I, W>,
+        visualizer: &dyn NodeVisualizer<T>,
+        options: &EmbedderOptions<T, W>,
+    ) -> Result<EmbeddedNodes<W>> {
         // Insert all tree items with their indices
         // After this step each item has following properties set:
         // 'y_order', 'x_extent', 'text', 'is_emphasized', 'ord'
-        let mut items = Self::create_initial_embedding_data(tree, &stringify, &emphasize)?;
+        let mut items = Self::create_initial_embedding_data(tree, visualizer, options)?;
         debug_assert_eq!(items.0.len(), items.1.len());
 
         // Set widths (x_extent_children, x_extent_of_children) on each InternalNode structure
         // After this step each item has following properties set:
         // 'y_order', 'x_extent', 'text', 'is_emphasized', 'ord', 'x_extent_children',
         // 'x_extent_of_children', 'parent'
-        Self::apply_children_x_extents(tree, &mut items);
+        Self::apply_children_x_extents(tree, &mut items, options)?;
 
         // Finally set the property 'x_center' from leafs to root
         // After this step each item has all necessary properties set
-        Self::apply_x_center(&mut items)?;
+        let height = if options.compact {
+            Self::apply_compact_x_center(&mut items, options)
+        } else {
+            Self::apply_x_center(&mut items, options)
+        };
+        if options.leaves_at_bottom {
+            Self::move_leaves_to_bottom(&mut items, height);
+        }
+        let height = match options.max_width {
+            Some(max_width) => Self::wrap_into_bands(&mut items, height, max_width, options),
+            None => height,
+        };
+        if options.layout_style == LayoutStyle::Radial {
+            Self::apply_radial_x_center(&mut items, options);
+        }
 
         // Transfer result
-        Ok(Self::transfer_result(items))
+        Ok(Self::transfer_result(items, height, options))
     }
 
     fn create_from_node(
@@ -67,170 +155,632 @@ where
         depth: usize,
         node: Node<T, I, W>,
         items: &EmbeddingHelperData<W>,
-        stringify: &StringifyFunction<T>,
-        emphasize: &EmphasizeFunction<T>,
-    ) -> InternalNode<W> {
-        let text = stringify(node.value());
+        visualizer: &dyn NodeVisualizer<T>,
+        options: &EmbedderOptions<T, W>,
+    ) -> Result<InternalNode<W>> {
+        let mut text = visualizer.stringify(node.value());
+        if let Some(wrapped) = options
+            .label_wrap
+            .and_then(|max_width| wrap(&text, max_width, options.char_width))
+        {
+            text = wrapped;
+        }
+        let full_text = options
+            .max_label_width
+            .and_then(|max_width| truncate(&text, max_width, options.char_width))
+            .map(|truncated| std::mem::replace(&mut text, truncated));
+        if options.max_depth == Some(depth) && node.first().is_some() {
+            // Mark the node whose children are hidden
+            text.push_str(" …");
+        }
+        let line_count = line_count(&text);
+        let line_widths = match &options.text_measure {
+            Some(measure) => measured_line_widths(&text, measure),
+            None => line_widths(&text, options.char_width),
+        };
         let y_order = depth;
         let x_center = 0;
-        let x_extent = text.len() + 1;
+        let shape = visualizer.shape(node.value());
+        let text_extent = match options.direction() {
+            // In the span layout the node is as wide as the source text it covers, in either
+            // direction
+            _ if options.span_layout => {
+                node.range().len().max(options.min_node_width) + 2 * options.horizontal_padding
+            }
+            LayoutDirection::TopToBottom => {
+                let width = line_widths.iter().copied().max().unwrap_or_default();
+                width.max(options.min_node_width) + 2 * options.horizontal_padding
+            }
+            // Siblings are stacked vertically, so the extent is the height of the text's lines
+            LayoutDirection::LeftToRight => line_count,
+        };
+        // An ellipse around the text is wider than the text's box
+        let shape_extent = match shape {
+            Some(NodeShape::Ellipse) => text_extent.div_ceil(2),
+            _ => 0,
+        };
+        let x_extent = text_extent + shape_extent + options.sibling_spacing;
         let x_extent_of_children = x_extent;
         let x_extent_children = x_extent;
-        let is_emphasized = emphasize(node.value());
+        let is_emphasized =
+            visualizer.emphasize(node.value()) || options.emphasized_ids.contains(&node.id());
+        let background_color = visualizer.background_color(node.value());
+        let tooltip = visualizer.tooltip(node.value());
+        let link = visualizer.link(node.value());
+        let edge_label = visualizer.edge_label(node.value());
+        let opacity = None;
+        // The children are counted once all nodes are embedded
+        let child_count = 0;
+        // The pre-order guarantees that the parent was inserted before the node, nodes at depth 0
+        // are roots even if they have a parent outside of the embedded subtree
         let parent = node
             .parent()
-            .and_then(|p| items.get_by_node_id(&p.id()).map(|n| n.ord));
+            .filter(|_| depth > 0)
+            .map(|p| {
+                items
+                    .get_by_node_id(&p.id())
+                    .map(|n| n.ord)
+                    .ok_or(LayouterError::MissingParent(ord))
+            })
+            .transpose()?;
         let node_id = node.id();
 
-        InternalNode {
+        Ok(InternalNode {
             y_order,
             x_center,
             x_extent,
             x_extent_of_children,
             x_extent_children,
             text,
+            full_text,
+            line_count,
+            line_widths,
             is_emphasized,
+            background_color,
+            tooltip,
+            shape,
+            link,
+            edge_label,
+            opacity,
             parent,
+            child_count,
             ord,
             node_id,
-        }
+        })
     }
 
     fn create_initial_embedding_data(
         tree: &Tree<T, I, W>,
-        stringify: &StringifyFunction<T>,
-        emphasize: &EmphasizeFunction<T>,
+        visualizer: &dyn NodeVisualizer<T>,
+        options: &EmbedderOptions<T, W>,
     ) -> Result<EmbeddingHelperData<W>> {
         let mut items = EmbeddingHelperData::with_capacity(tree.len());
-        if tree.children().count() > 1 {
-            return Err(LayouterError::from_description(
-                "Currently we support only one root",
-            ));
-        }
 
-        tree.walk()
-            .with_depths()
+        Self::pre_order(tree, options)?
+            .into_iter()
             .enumerate()
-            .for_each(|(ord, (depth, node))| {
+            .try_for_each(|(ord, (depth, node))| -> Result<()> {
                 let new_item =
-                    Self::create_from_node(ord, depth, node, &items, stringify, emphasize);
+                    Self::create_from_node(ord, depth, node, &items, visualizer, options)?;
                 items.insert(ord, new_item);
-            });
+                Ok(())
+            })?;
 
+        if let Some(focus) = &options.focus {
+            Self::dim_outside_of_focus(&mut items, focus)?;
+        }
         Ok(items)
     }
 
-    fn apply_children_x_extents(tree: &Tree<T, I, W>, items: &mut EmbeddingHelperData<W>) {
-        tree.walk_events().for_each(|(event, node)| {
+    /// Sets the reduced opacity of all nodes outside of the focused node's subtree.
+    /// The subtree is a contiguous range of ords, since the ords are in pre-order.
+    /// Fails if the focused node isn't embedded.
+    fn dim_outside_of_focus(items: &mut EmbeddingHelperData<W>, focus: &NodeId<W>) -> Result<()> {
+        let (first, depth) = items
+            .get_by_node_id(focus)
+            .map(|item| (item.ord, item.y_order))
+            .ok_or(LayouterError::UnknownFocus)?;
+        let end = items.0[first + 1..]
+            .iter()
+            .position(|item| item.y_order <= depth)
+            .map_or(items.0.len(), |len| first + 1 + len);
+        for (ord, item) in items.0.iter_mut().enumerate() {
+            if !(first..end).contains(&ord) {
+                item.opacity = Some(CONTEXT_OPACITY);
+            }
+        }
+        Ok(())
+    }
+
+    /// The nodes with their depths in pre-order, i.e. each parent precedes its children and the
+    /// siblings follow their order in the tree, resp. the order of their spans' starts in the span
+    /// layout and then the sibling order of the options, which makes the position a node's 'ord'.
+    /// The roots follow the root order of the options, if set.
+    /// The order is derived from the child and sibling links instead of relying on the order of
+    /// the tree's walker. Nodes deeper than the maximum depth are omitted.
+    /// If a subtree root is set, only its subtree is traversed and it is the only node at depth 0.
+    /// Otherwise a forest is rejected if a single root is required.
+    fn pre_order<'a>(
+        tree: &'a Tree<T, I, W>,
+        options: &EmbedderOptions<T, W>,
+    ) -> Result<Vec<NodeAtDepth<'a, T, I, W>>> {
+        let siblings = |first: Option<Node<'a, T, I, W>>| {
+            let mut siblings = Vec::from_iter(std::iter::successors(first, |node| node.next()));
+            if options.span_layout {
+                siblings.sort_by_key(|node| node.range().start);
+            }
+            if let Some(sibling_order) = &options.sibling_order {
+                siblings.sort_by(|a, b| sibling_order(a.value(), b.value()));
+            }
+            siblings
+        };
+        let roots = match options.subtree_root {
+            Some(id) => vec![tree
+                .walk()
+                .find(|node| node.id() == id)
+                .ok_or(LayouterError::UnknownSubtreeRoot)?],
+            None => {
+                let mut roots = siblings(tree.first());
+                if options.single_root && roots.len() > 1 {
+                    return Err(LayouterError::MultipleRoots { found: roots.len() });
+                }
+                if let Some(root_order) = &options.root_order {
+                    roots.sort_by(|a, b| root_order(a.value(), b.value()));
+                }
+                roots
+            }
+        };
+        let mut nodes = Vec::with_capacity(tree.len());
+        // The siblings are pushed in reverse order to visit the first one next
+        let mut pending = Vec::from_iter(roots.into_iter().rev().map(|n| (0, n)));
+        while let Some((depth, node)) = pending.pop() {
+            if options.max_depth.is_none_or(|max| depth < max) {
+                let children = siblings(node.first()).into_iter().rev();
+                pending.extend(children.map(|child| (depth + 1, child)));
+            }
+            nodes.push((depth, node));
+        }
+        Ok(nodes)
+    }
+
+    /// Nodes omitted because of the maximum depth aren't found in the items and thus don't
+    /// contribute to their parent's extent, collapsed nodes are laid out like leaves.
+    /// Since 'x_extent_children' spans the whole subtree and not only the node's children, it is
+    /// an upper bound of the subtree's contour on every layer, so sibling subtrees placed side by
+    /// side never intersect, however deep one of them is.
+    /// Fails with [LayouterError::LayoutTooLarge] if the width of a subtree or of the whole
+    /// forest exceeds the maximum layout width, which also prevents the sums from overflowing.
+    fn apply_children_x_extents(
+        tree: &Tree<T, I, W>,
+        items: &mut EmbeddingHelperData<W>,
+        options: &EmbedderOptions<T, W>,
+    ) -> Result<()> {
+        let max_width = options.max_layout_width;
+        let add = |acc: usize, width: usize| {
+            acc.checked_add(width)
+                .filter(|sum| *sum <= max_width)
+                .ok_or(LayouterError::LayoutTooLarge(max_width))
+        };
+        for (event, node) in tree.walk_events() {
             if let Event::Up = event {
-                let x_extent_of_children = node.children().fold(0, |acc, child| {
+                // The embedded children are separated by the subtree gutter
+                let mut embedded = 0;
+                let x_extent_of_children = node.children().try_fold(0, |acc, child| {
                     if let Some(internal_child) = items.get_by_node_id(&child.id()) {
-                        acc + internal_child.x_extent_children
+                        let gap = if embedded > 0 {
+                            options.subtree_gutter
+                        } else {
+                            0
+                        };
+                        embedded += 1;
+                        add(add(acc, gap)?, internal_child.x_extent_children)
                     } else {
-                        acc
+                        Ok(acc)
                     }
-                });
+                })?;
                 if let Some(internal_node) = items.get_mut_by_node_id(&node.id()) {
                     internal_node.x_extent_of_children = x_extent_of_children;
-                    internal_node.x_extent_children =
-                        std::cmp::max(internal_node.x_extent, x_extent_of_children);
+                    // The subtree spans the children and the parent placed over them
+                    let ord = internal_node.ord;
+                    let offset = Self::parent_offset(ord, items, options);
+                    let (left, right) = Self::parent_borders(ord, offset, items);
+                    items.0[ord].x_extent_children = add(
+                        0,
+                        (right.max(x_extent_of_children as isize) - left.min(0)) as usize,
+                    )?;
                 }
             }
-        });
+        }
+        // The roots of a forest are placed side by side, separated by the root gap
+        items
+            .ords_in_layer(0)
+            .iter()
+            .enumerate()
+            .try_fold(0, |acc, (i, ord)| {
+                let gap = if i > 0 { options.root_gap } else { 0 };
+                add(add(acc, gap)?, items.0[*ord].x_extent_children)
+            })?;
+        Ok(())
+    }
+
+    /// The x center of the parent with the given ord relative to the left border of its
+    /// children, depending on the parent alignment. A parent without children is centered over
+    /// an empty range.
+    /// The integer division rounds the same way for a parent and its single child, whose subtree
+    /// spans the same range, so both get the same x center. Only the half width of several
+    /// children follows the rounding of the options.
+    /// The children's 'x_extent_children' must have been set.
+    fn parent_offset(
+        ord: usize,
+        items: &EmbeddingHelperData<W>,
+        options: &EmbedderOptions<T, W>,
+    ) -> isize {
+        let children = items.child_ords(ord);
+        let target = match options.parent_alignment {
+            ParentAlignment::Center => None,
+            ParentAlignment::OverFirstChild => children.first(),
+            ParentAlignment::OverLastChild => children.last(),
+        };
+        let Some(target) = target else {
+            let x_extent_of_children = items.0[ord].x_extent_of_children;
+            return match children.len() {
+                // A single child is centered the same way, so both keep the same x center
+                0 | 1 => x_extent_of_children / 2,
+                _ => options.rounding.half(x_extent_of_children),
+            } as isize;
+        };
+        // The children preceding the target one from left to right
+        let mut offset = 0;
+        for child in Self::ordered(children, options) {
+            let width = items.0[child].x_extent_children;
+            if child == *target {
+                return (offset + width / 2) as isize;
+            }
+            offset += width + options.subtree_gutter;
+        }
+        unreachable!("The target is one of the children")
     }
 
-    fn x_center_layer(layer: usize, items: &mut EmbeddingHelperData<W>) -> Result<()> {
-        let node_ids_in_layer =
-            items
-                .0
-                .iter()
-                .enumerate()
-                .fold(Vec::new(), |mut acc, (ord, item)| {
-                    if item.y_order == layer {
-                        acc.push(ord)
-                    }
-                    acc
-                });
+    /// The left and right border of the parent's box relative to the left border of its
+    /// children, given the offset of its x center
+    fn parent_borders(ord: usize, offset: isize, items: &EmbeddingHelperData<W>) -> (isize, isize) {
+        let x_extent = items.0[ord].x_extent;
+        (
+            offset - (x_extent / 2) as isize,
+            offset + (x_extent - x_extent / 2) as isize,
+        )
+    }
+
+    /// Places the children of the given parents side by side below their parents.
+    /// The sibling groups of different parents are independent of each other, with the `rayon`
+    /// feature they are placed concurrently unless disabled by the options. The result is the
+    /// same in both cases.
+    fn x_center_layer(parents: &[usize], metrics: &SiblingMetrics, x_centers: &mut [isize]) {
+        let place = |parent: &usize| {
+            // We start left of the parents x center by the parent's offset, i.e. half way for
+            // centered parents.
+            // This may become negative, which is corrected in `apply_x_center`.
+            let start = x_centers[*parent] - metrics.offsets[*parent];
+            metrics.place(&metrics.children[*parent], start, metrics.gutter)
+        };
+        #[cfg(feature = "rayon")]
+        let groups: Vec<_> = if metrics.parallel {
+            parents.par_iter().map(place).collect()
+        } else {
+            parents.iter().map(place).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let groups: Vec<_> = parents.iter().map(place).collect();
+        for (ord, x_center) in groups.into_iter().flatten() {
+            x_centers[ord] = x_center;
+        }
+    }
 
-        let parents_in_layer = node_ids_in_layer
+    /// The placement data of all nodes, see [SiblingMetrics]
+    fn sibling_metrics<'a>(
+        items: &'a EmbeddingHelperData<W>,
+        options: &EmbedderOptions<T, W>,
+    ) -> SiblingMetrics<'a> {
+        let offsets = (0..items.0.len())
+            .map(|ord| Self::parent_offset(ord, items, options))
+            .collect::<Vec<_>>();
+        // The node is placed at its offset from the left border of its subtree
+        let shifts = offsets
             .iter()
-            .map(|ord| {
-                Ok(items
-                    .get_by_ord(*ord)
-                    .ok_or(LayouterError::from_description("Expecting existing node"))?
-                    .parent)
-            })
-            .collect::<Result<Vec<Option<usize>>>>()?;
+            .enumerate()
+            .map(|(ord, offset)| offset - Self::parent_borders(ord, *offset, items).0.min(0))
+            .collect();
+        SiblingMetrics {
+            offsets,
+            shifts,
+            extents: items.0.iter().map(|item| item.x_extent_children).collect(),
+            children: &items.3,
+            gutter: options.subtree_gutter,
+            right_to_left: options.right_to_left,
+            #[cfg(feature = "rayon")]
+            parallel: options.parallel,
+        }
+    }
 
-        for p in parents_in_layer {
-            let nodes_in_layer_per_parent = node_ids_in_layer
-                .iter()
-                .filter_map(|ord| {
-                    if let Some(node) = items.get_by_ord(*ord) {
-                        if node.parent == p {
-                            Some(*ord)
-                        } else {
-                            None
-                        }
-                    } else {
-                        debug_assert!(false, "Expecting existing node");
-                        None
-                    }
-                })
-                .collect::<Vec<usize>>();
-
-            let mut moving_x_center = {
-                if let Some(parent_ord) = p {
-                    if let Some(placed_parent_item) = items.get_by_ord(parent_ord) {
-                        // We start half way left from the parents x center
-                        placed_parent_item.x_center - placed_parent_item.x_extent_of_children / 2
-                    } else {
-                        // This really should not happen
-                        return Err(LayouterError::from_description("Some item expected here!"));
-                    }
-                } else {
-                    // `None` means we are in layer 0
-                    debug_assert_eq!(layer, 0);
-                    // and we should have only one root
-                    debug_assert_eq!(node_ids_in_layer.len(), 1);
-                    // We start all the way left
-                    0
-                }
-            };
-            for ord in nodes_in_layer_per_parent {
-                if let Some(placed_item) = items.get_mut_by_ord(ord) {
-                    placed_item.x_center = moving_x_center + placed_item.x_extent_children / 2;
-                    moving_x_center += placed_item.x_extent_children;
-                }
+    /// Returns the height of the tree, i.e. the maximum `y_order` of all items
+    fn apply_x_center(
+        items: &mut EmbeddingHelperData<W>,
+        options: &EmbedderOptions<T, W>,
+    ) -> usize {
+        let height = items.2.len().saturating_sub(1);
+        let mut x_centers = vec![0; items.0.len()];
+        {
+            let metrics = Self::sibling_metrics(items, options);
+            // The roots are placed from left to right, starting all the way left.
+            // Roots of a forest are separated by an additional gap.
+            for (ord, x_center) in metrics.place(items.ords_in_layer(0), 0, options.root_gap) {
+                x_centers[ord] = x_center;
+            }
+            for layer in 1..height + 1 {
+                let parents = items.ords_in_layer(layer - 1);
+                Self::x_center_layer(parents, &metrics, &mut x_centers);
             }
         }
-
-        Ok(())
+        for (item, x_center) in items.0.iter_mut().zip(x_centers) {
+            item.x_center = x_center;
+        }
+        Self::shift_to_origin(items);
+        height
     }
 
-    fn apply_x_center(items: &mut EmbeddingHelperData<W>) -> Result<()> {
-        let height = items
+    /// Shifts all items horizontally so that the leftmost border of all nodes is at x = 0.
+    /// Since each subtree's border is the border of one of its nodes, this is also the leftmost
+    /// border of all subtrees.
+    /// After this step no item has a negative `x_center`.
+    fn shift_to_origin(items: &mut EmbeddingHelperData<W>) {
+        let left = items
             .0
             .iter()
-            .max_by(|x, y| x.y_order.cmp(&y.y_order))
-            .map(|i| i.y_order)
+            .map(|i| i.x_center - (i.x_extent / 2) as isize)
+            .min()
             .unwrap_or_default();
-        for l in 0..height + 1 {
-            Self::x_center_layer(l, items)?;
+        if left != 0 {
+            items.0.iter_mut().for_each(|i| i.x_center -= left);
         }
-        Ok(())
     }
 
-    /// Transforming the internal `EmbeddingHelperMap` to the external representation `Embedding`.
-    /// The `items` parameter is hereby consumed.
-    fn transfer_result(items: EmbeddingHelperData<W>) -> Embedding {
+    /// Sets the property 'x_center' of all items with a contour based compaction in the style of
+    /// Reingold and Tilford: Bottom up, each subtree is described by its contour, i.e. the left
+    /// and right border on each of its layers. Sibling subtrees are then placed as close as their
+    /// contours allow, which may move them below each other's wider parts, and the parent is
+    /// centered above its children.
+    /// 'x_extent_children' is set to the width of each subtree's contour.
+    /// Returns the height of the tree, i.e. the maximum `y_order` of all items.
+    fn apply_compact_x_center(
+        items: &mut EmbeddingHelperData<W>,
+        options: &EmbedderOptions<T, W>,
+    ) -> usize {
         let len = items.0.len();
-        items
-            .0
-            .into_iter()
-            .fold(Embedding::with_capacity(len), |mut acc, e| {
-                acc.push(e.into());
-                acc
-            })
+        // ord => contour relative to the node's x center, one (left, right) pair per layer
+        let mut contours: Vec<Vec<(isize, isize)>> = vec![Vec::new(); len];
+        // ord => x center relative to the parent's x center
+        let mut offsets = vec![0isize; len];
+
+        // Parents are inserted before their children, so the children are handled first
+        for ord in (0..len).rev() {
+            let children = Self::ordered(items.child_ords(ord), options);
+            let item = &items.0[ord];
+            let own = (
+                -((item.x_extent / 2) as isize),
+                (item.x_extent - item.x_extent / 2) as isize,
+            );
+            let mut contour = vec![own];
+            if options.leaves_at_bottom && children.is_empty() {
+                // The leaf and its edge occupy all layers down to the last one
+                contour.resize(items.2.len() - item.y_order, own);
+            }
+            if !children.is_empty() {
+                let (block, centers) =
+                    Self::pack(&children, &contours, options.subtree_gutter as isize);
+                // Center the parent above its children resp. place it above the target child
+                let target = match options.parent_alignment {
+                    ParentAlignment::Center => None,
+                    ParentAlignment::OverFirstChild => items.child_ords(ord).first(),
+                    ParentAlignment::OverLastChild => items.child_ords(ord).last(),
+                };
+                let parent_center = target
+                    .and_then(|target| children.iter().position(|child| child == target))
+                    .map_or((block[0].0 + block[0].1).div_euclid(2), |i| centers[i]);
+                for (child, center) in children.iter().zip(centers) {
+                    offsets[*child] = center - parent_center;
+                    contours[*child] = Vec::new();
+                }
+                contour.extend(
+                    block
+                        .iter()
+                        .map(|(left, right)| (left - parent_center, right - parent_center)),
+                );
+            }
+            let left = contour.iter().map(|c| c.0).min().unwrap_or_default();
+            let right = contour.iter().map(|c| c.1).max().unwrap_or_default();
+            items.0[ord].x_extent_children = (right - left) as usize;
+            contours[ord] = contour;
+        }
+
+        // The roots of a forest are packed like siblings, separated by the additional gap
+        let roots = Self::ordered(items.ords_in_layer(0), options);
+        let (_, centers) = Self::pack(&roots, &contours, options.root_gap as isize);
+        for (root, center) in roots.iter().zip(centers) {
+            offsets[*root] = center;
+        }
+
+        // Top down, the parents' x centers are known before their children's
+        for (ord, offset) in offsets.into_iter().enumerate() {
+            let parent_x_center = items.0[ord]
+                .parent
+                .map(|parent| items.0[parent].x_center)
+                .unwrap_or_default();
+            items.0[ord].x_center = parent_x_center + offset;
+        }
+
+        Self::shift_to_origin(items);
+        items.2.len().saturating_sub(1)
+    }
+
+    /// Moves all leaves to the last layer, i.e. sets their `y_order` to the given tree height.
+    /// Their x centers are kept, which is possible since each leaf's subtree area, resp. its
+    /// contour in the compact layout, already spans all layers below it.
+    fn move_leaves_to_bottom(items: &mut EmbeddingHelperData<W>, height: usize) {
+        for ord in 0..items.0.len() {
+            if items.child_ords(ord).is_empty() {
+                items.0[ord].y_order = height;
+            }
+        }
+    }
+
+    /// Distributes the trees of a forest over bands that are stacked along the layer axis.
+    /// The trees are placed from left to right as before, separated by the root gap, until the
+    /// next tree would exceed the maximum width. It then starts the next band below the deepest
+    /// layer of the current band. A tree that is wider than the maximum gets a band of its own.
+    /// Returns the new height, i.e. the maximum `y_order` of all items.
+    fn wrap_into_bands(
+        items: &mut EmbeddingHelperData<W>,
+        height: usize,
+        max_width: usize,
+        options: &EmbedderOptions<T, W>,
+    ) -> usize {
+        let mut roots = items.ords_in_layer(0).to_vec();
+        if roots.len() < 2 {
+            return height;
+        }
+        roots.sort_by_key(|root| items.0[*root].x_center);
+        // The roots' subtrees are contiguous ranges of ords, since the ords are in pre-order
+        let mut starts = items.ords_in_layer(0).to_vec();
+        starts.sort_unstable();
+        let len = items.0.len();
+        let subtree = |root: usize| {
+            let i = starts.binary_search(&root).unwrap();
+            root..starts.get(i + 1).copied().unwrap_or(len)
+        };
+
+        let (mut band_x, mut band_y, mut band_height) = (0, 0, 0);
+        for root in roots {
+            let range = subtree(root);
+            let nodes = &items.0[range.clone()];
+            let left = nodes
+                .iter()
+                .map(|i| i.x_center - (i.x_extent / 2) as isize)
+                .min()
+                .unwrap_or_default();
+            let right = nodes
+                .iter()
+                .map(|i| i.x_center + (i.x_extent - i.x_extent / 2) as isize)
+                .max()
+                .unwrap_or_default();
+            let depth = nodes.iter().map(|i| i.y_order).max().unwrap_or_default() + 1;
+            let width = (right - left) as usize;
+            if band_x > 0 && band_x + width > max_width {
+                band_y += band_height;
+                (band_x, band_height) = (0, 0);
+            }
+            for item in &mut items.0[range] {
+                item.x_center += band_x as isize - left;
+                item.y_order += band_y;
+            }
+            band_x += width + options.root_gap;
+            band_height = band_height.max(depth);
+        }
+        band_y + band_height - 1
+    }
+
+    /// Sets the property 'x_center' of all items for the radial layout, where it maps to the
+    /// angle: Each leaf gets a slot as wide as the widest node, and each parent is centered over
+    /// the slots of its subtree's leaves. So the span of each subtree is proportional to its
+    /// number of leaves and nodes of the same layer never overlap.
+    fn apply_radial_x_center(items: &mut EmbeddingHelperData<W>, options: &EmbedderOptions<T, W>) {
+        let len = items.0.len();
+        let slot = items.0.iter().map(|i| i.x_extent).max().unwrap_or_default();
+        // Bottom up, the number of leaves of each subtree
+        let mut leaf_counts = vec![0; len];
+        for ord in (0..len).rev() {
+            let children = items.child_ords(ord);
+            leaf_counts[ord] = if children.is_empty() {
+                1
+            } else {
+                children.iter().map(|child| leaf_counts[*child]).sum()
+            };
+        }
+        // In pre-order, each subtree's leaves follow the leaves of the subtrees before it
+        let mut first_leaf = vec![0; len];
+        let mut next_leaf = 0;
+        for ord in 0..len {
+            let children = Self::ordered(items.child_ords(ord), options);
+            let start = if items.0[ord].parent.is_some() {
+                first_leaf[ord]
+            } else {
+                let start = next_leaf;
+                next_leaf += leaf_counts[ord];
+                start
+            };
+            let mut child_start = start;
+            for child in children {
+                first_leaf[child] = child_start;
+                child_start += leaf_counts[child];
+            }
+            items.0[ord].x_center = ((2 * start + leaf_counts[ord]) * slot / 2) as isize;
+            items.0[ord].x_extent_children = leaf_counts[ord] * slot;
+        }
+    }
+
+    /// The given siblings in the order they are placed from left to right
+    fn ordered(ords: &[usize], options: &EmbedderOptions<T, W>) -> Vec<usize> {
+        if options.right_to_left {
+            ords.iter().rev().copied().collect()
+        } else {
+            ords.to_vec()
+        }
+    }
+
+    /// Places the subtrees with the given contours from left to right as close as possible, with
+    /// at least the given gap between them.
+    /// Returns the combined contour and the x centers of the subtrees' roots, both relative to
+    /// the first root's x center.
+    fn pack(
+        ords: &[usize],
+        contours: &[Vec<(isize, isize)>],
+        gap: isize,
+    ) -> (Vec<(isize, isize)>, Vec<isize>) {
+        let mut block: Vec<(isize, isize)> = Vec::new();
+        let mut centers = Vec::with_capacity(ords.len());
+        for ord in ords {
+            let contour = &contours[*ord];
+            // The closest position where the subtree doesn't overlap on any common layer
+            let center = block
+                .iter()
+                .zip(contour)
+                .map(|((_, right), (left, _))| right - left + gap)
+                .max()
+                .unwrap_or_default();
+            for (layer, (left, right)) in contour.iter().enumerate() {
+                if let Some(borders) = block.get_mut(layer) {
+                    borders.1 = center + right;
+                } else {
+                    block.push((center + left, center + right));
+                }
+            }
+            centers.push(center);
+        }
+        (block, centers)
+    }
+
+    /// Transforming the internal `EmbeddingHelperMap` to the external representation, which
+    /// converts the nodes while they are iterated.
+    /// The `items` parameter is hereby consumed, only the nodes are kept.
+    /// If the layers are inverted the `y_order` is flipped against the given tree height.
+    fn transfer_result(
+        items: EmbeddingHelperData<W>,
+        height: usize,
+        options: &EmbedderOptions<T, W>,
+    ) -> EmbeddedNodes<W> {
+        let EmbeddingHelperData(mut nodes, _, _, children) = items;
+        for (node, children) in nodes.iter_mut().zip(&children) {
+            node.child_count = children.len();
+        }
+        EmbeddedNodes::new(nodes, options.inverted.then_some(height))
     }
 }
@@ -1,3 +1,5 @@
 //! Internal module with implementation details
 pub(crate) mod embedder;
 pub(crate) mod node;
+pub(crate) mod options;
+pub(crate) mod visualizer;
@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use syntree::pointer::Width;
 
+use crate::NodeShape;
+
 pub(crate) type NodeId<W> = <W as Width>::Pointer;
 
 ///
@@ -14,8 +16,9 @@ where
 {
     /// The nodes level, root has level 0. Can be used to calculate an y coordinate for the node
     pub(crate) y_order: usize,
-    /// The logical x coordinate of the node's center
-    pub(crate) x_center: usize,
+    /// The logical x coordinate of the node's center. It is signed to allow intermediate values
+    /// left of the origin during the embedding.
+    pub(crate) x_center: isize,
     /// The x-extent of the nodes text representation in logical coordinate units
     pub(crate) x_extent: usize,
     /// Internal value used to sum up the x-extent of all children of the node
@@ -24,10 +27,30 @@ where
     pub(crate) x_extent_children: usize,
     /// The text representation of the nodes data - created by the `Visualize` trait's implementation
     pub(crate) text: String,
+    /// The complete text representation if `text` was truncated
+    pub(crate) full_text: Option<String>,
+    /// The number of lines of the nodes text representation
+    pub(crate) line_count: usize,
+    /// The measured column width of each line of the nodes text representation
+    pub(crate) line_widths: Vec<usize>,
     /// The *emphasize* property possibly obtained from the `Visualize` trait
     pub(crate) is_emphasized: bool,
+    /// The optional background color possibly obtained from the `Visualize` trait
+    pub(crate) background_color: Option<String>,
+    /// The optional tooltip possibly obtained from the `Visualize` trait
+    pub(crate) tooltip: Option<String>,
+    /// The optional shape possibly obtained from the `Visualize` trait
+    pub(crate) shape: Option<NodeShape>,
+    /// The optional hyperlink possibly obtained from the `Visualize` trait
+    pub(crate) link: Option<String>,
+    /// The optional label of the edge to the parent possibly obtained from the `Visualize` trait
+    pub(crate) edge_label: Option<String>,
+    /// The reduced opacity of a node outside the focused subtree
+    pub(crate) opacity: Option<f32>,
     /// The parent's `ord`, if there is one
     pub(crate) parent: Option<usize>,
+    /// The number of embedded children
+    pub(crate) child_count: usize,
     /// A unique number reflecting the depth first walk order of the nodes in the tree
     /// It is assumed that parents are inserted before their child nodes
     pub(crate) ord: usize,
@@ -47,8 +70,18 @@ where
             x_extent_of_children: Default::default(),
             x_extent_children: Default::default(),
             text: Default::default(),
+            full_text: Default::default(),
+            line_count: Default::default(),
+            line_widths: Default::default(),
             is_emphasized: Default::default(),
+            background_color: Default::default(),
+            tooltip: Default::default(),
+            shape: Default::default(),
+            link: Default::default(),
+            edge_label: Default::default(),
+            opacity: Default::default(),
             parent: Default::default(),
+            child_count: Default::default(),
             ord: Default::default(),
             node_id: <W as Width>::EMPTY,
         }
@@ -56,13 +89,19 @@ where
 }
 
 ///
-/// Internal helper data
+/// Internal helper data.
+/// The nodes are stored by their `ord` and indexed by their syntree node id, so that all lookups
+/// are done in constant time.
 ///
 pub(crate) struct EmbeddingHelperData<W: Width>(
     /// ord => InternalNode
     pub(crate) Vec<InternalNode<W>>,
     /// NodeId => ord
     pub(crate) HashMap<NodeId<W>, usize>,
+    /// layer => ords of the nodes in this layer, in ascending order
+    pub(crate) Vec<Vec<usize>>,
+    /// ord => ords of the node's children, in ascending order
+    pub(crate) Vec<Vec<usize>>,
 );
 
 impl<W> EmbeddingHelperData<W>
@@ -73,17 +112,11 @@ where
         Self(
             Vec::with_capacity(capacity),
             HashMap::with_capacity(capacity),
+            Vec::new(),
+            Vec::with_capacity(capacity),
         )
     }
 
-    pub(crate) fn get_by_ord(&self, ord: usize) -> Option<&InternalNode<W>> {
-        self.0.get(ord)
-    }
-
-    pub(crate) fn get_mut_by_ord(&mut self, ord: usize) -> Option<&mut InternalNode<W>> {
-        self.0.get_mut(ord)
-    }
-
     pub(crate) fn get_by_node_id(&self, node_id: &NodeId<W>) -> Option<&InternalNode<W>> {
         self.1.get(node_id).and_then(|n| self.0.get(*n))
     }
@@ -95,8 +128,32 @@ where
         self.1.get(node_id).and_then(|n| self.0.get_mut(*n))
     }
 
+    /// The ords of all nodes in the given layer
+    pub(crate) fn ords_in_layer(&self, layer: usize) -> &[usize] {
+        self.2.get(layer).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The ords of all children of the node with the given ord
+    pub(crate) fn child_ords(&self, ord: usize) -> &[usize] {
+        self.3.get(ord).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Inserts the item and updates the indices.
+    /// It is assumed that parents are inserted before their children and that the items are
+    /// inserted in ascending order of their `ord`.
     pub(crate) fn insert(&mut self, ord: usize, item: InternalNode<W>) {
         self.1.insert(item.node_id, ord);
+        if self.2.len() <= item.y_order {
+            self.2.resize(item.y_order + 1, Vec::new());
+        }
+        self.2[item.y_order].push(ord);
+        if self.3.len() <= ord {
+            self.3.resize(ord + 1, Vec::new());
+        }
+        if let Some(parent) = item.parent {
+            debug_assert!(parent < ord, "Parents are expected to be inserted first");
+            self.3[parent].push(ord);
+        }
         self.0.insert(ord, item);
     }
 }
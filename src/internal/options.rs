@@ -0,0 +1,137 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use syntree::pointer::Width;
+
+use super::node::NodeId;
+use crate::{
+    single_column_char_width, CharWidthFunction, LayoutDirection, LayoutStyle, ParentAlignment,
+    Rounding,
+};
+
+///
+/// The [EmbedderOptions] collect the settings made on the [crate::Layouter] that influence the
+/// embedding process.
+///
+pub(crate) struct EmbedderOptions<T, W>
+where
+    W: Width,
+{
+    /// Horizontal gap in logical coordinate units between adjacent roots of a forest
+    pub(crate) root_gap: usize,
+    /// Function to measure the number of columns a character occupies
+    pub(crate) char_width: CharWidthFunction,
+    /// The measure of the width of a text line, which replaces the character widths
+    pub(crate) text_measure: Option<TextMeasure>,
+    /// Direction in which the layers grow
+    pub(crate) direction: LayoutDirection,
+    /// Arrangement of the layers
+    pub(crate) layout_style: LayoutStyle,
+    /// Places the root in the last layer instead of the first one
+    pub(crate) inverted: bool,
+    /// Places sibling subtrees as close as their contours allow
+    pub(crate) compact: bool,
+    /// Places the siblings from right to left
+    pub(crate) right_to_left: bool,
+    /// Where parents are placed relative to their children
+    pub(crate) parent_alignment: ParentAlignment,
+    /// The rounding of the half width when a parent is centered over several children
+    pub(crate) rounding: Rounding,
+    /// Spacing between two adjacent layers, see [crate::Embedding::layer_spacing]
+    pub(crate) layer_spacing: usize,
+    /// Padding in logical coordinate units that is added to the extent of each node
+    pub(crate) sibling_spacing: usize,
+    /// The minimum gap between sibling subtrees, in addition to the sibling spacing
+    pub(crate) subtree_gutter: usize,
+    /// The deepest layer that is embedded, deeper nodes are omitted
+    pub(crate) max_depth: Option<usize>,
+    /// The maximum column width of a label, wider labels are truncated
+    pub(crate) max_label_width: Option<usize>,
+    /// The maximum column width of a label's lines, longer lines wrap at word boundaries
+    pub(crate) label_wrap: Option<usize>,
+    /// The minimum column width of a node, which keeps nodes with empty labels visible
+    pub(crate) min_node_width: usize,
+    /// The padding in text columns on both sides of each label, within the node's box
+    pub(crate) horizontal_padding: usize,
+    /// Sizes and orders the nodes by their spans instead of their labels
+    pub(crate) span_layout: bool,
+    /// Places all leaves in the last layer
+    pub(crate) leaves_at_bottom: bool,
+    /// The maximum width of the whole layout in logical coordinate units
+    pub(crate) max_layout_width: usize,
+    /// The width at which the trees of a forest wrap into another band
+    pub(crate) max_width: Option<usize>,
+    /// The ids of the nodes that are emphasized in addition to the visualizer's choice
+    pub(crate) emphasized_ids: HashSet<NodeId<W>>,
+    /// The node whose subtree is embedded instead of the whole tree
+    pub(crate) subtree_root: Option<NodeId<W>>,
+    /// The node whose subtree is shown in focus, all other nodes are dimmed
+    pub(crate) focus: Option<NodeId<W>>,
+    /// The order in which siblings are placed instead of their order in the tree
+    pub(crate) sibling_order: Option<SiblingOrder<T>>,
+    /// Rejects forests instead of placing their roots side by side
+    pub(crate) single_root: bool,
+    /// The order in which the roots of a forest are placed, which precedes the sibling order
+    pub(crate) root_order: Option<SiblingOrder<T>>,
+    /// Centers the sibling groups of a layer concurrently
+    #[cfg(feature = "rayon")]
+    pub(crate) parallel: bool,
+}
+
+/// A function that measures the width of a text line in columns
+pub(crate) type TextMeasure = Box<dyn Fn(&str) -> f32>;
+
+/// A comparator of the nodes' values that determines the order of siblings
+pub(crate) type SiblingOrder<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+impl<T, W> EmbedderOptions<T, W>
+where
+    W: Width,
+{
+    /// The direction in which the layers grow, the radial layout ignores the configured one
+    pub(crate) fn direction(&self) -> LayoutDirection {
+        match self.layout_style {
+            LayoutStyle::Layered => self.direction,
+            LayoutStyle::Radial => LayoutDirection::TopToBottom,
+        }
+    }
+}
+
+impl<T, W> Default for EmbedderOptions<T, W>
+where
+    W: Width,
+{
+    fn default() -> Self {
+        Self {
+            root_gap: 2,
+            char_width: single_column_char_width,
+            text_measure: None,
+            direction: LayoutDirection::default(),
+            layout_style: LayoutStyle::default(),
+            inverted: false,
+            right_to_left: false,
+            parent_alignment: ParentAlignment::default(),
+            rounding: Rounding::default(),
+            compact: false,
+            layer_spacing: 2,
+            sibling_spacing: 1,
+            subtree_gutter: 0,
+            max_depth: None,
+            max_label_width: None,
+            label_wrap: None,
+            min_node_width: 0,
+            horizontal_padding: 0,
+            span_layout: false,
+            leaves_at_bottom: false,
+            max_layout_width: isize::MAX as usize,
+            max_width: None,
+            emphasized_ids: HashSet::new(),
+            subtree_root: None,
+            focus: None,
+            sibling_order: None,
+            single_root: false,
+            root_order: None,
+            #[cfg(feature = "rayon")]
+            parallel: true,
+        }
+    }
+}
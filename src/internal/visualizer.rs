@@ -0,0 +1,92 @@
+use crate::{NodeShape, Visualize};
+
+///
+/// The [NodeVisualizer] derives the visual properties of a node from the node's data.
+/// It abstracts over the different ways the [crate::Layouter] offers to represent the nodes.
+///
+pub(crate) trait NodeVisualizer<T> {
+    /// The text representation of the node's data
+    fn stringify(&self, value: &T) -> String;
+
+    /// Whether the node should be emphasized
+    fn emphasize(&self, value: &T) -> bool;
+
+    /// The optional background color of the node
+    fn background_color(&self, _value: &T) -> Option<String> {
+        None
+    }
+
+    /// The optional tooltip of the node
+    fn tooltip(&self, _value: &T) -> Option<String> {
+        None
+    }
+
+    /// The optional shape of the node
+    fn shape(&self, _value: &T) -> Option<NodeShape> {
+        None
+    }
+
+    /// The optional hyperlink of the node
+    fn link(&self, _value: &T) -> Option<String> {
+        None
+    }
+
+    /// The optional label of the edge to the node's parent
+    fn edge_label(&self, _value: &T) -> Option<String> {
+        None
+    }
+}
+
+///
+/// Visualizes nodes via the [Visualize] implementation of their data type.
+///
+pub(crate) struct TraitVisualizer;
+
+impl<T: Visualize> NodeVisualizer<T> for TraitVisualizer {
+    fn stringify(&self, value: &T) -> String {
+        value.visualize()
+    }
+
+    fn emphasize(&self, value: &T) -> bool {
+        value.emphasize()
+    }
+
+    fn background_color(&self, value: &T) -> Option<String> {
+        value.background_color()
+    }
+
+    fn tooltip(&self, value: &T) -> Option<String> {
+        value.tooltip()
+    }
+
+    fn shape(&self, value: &T) -> Option<NodeShape> {
+        value.shape()
+    }
+
+    fn link(&self, value: &T) -> Option<String> {
+        value.link()
+    }
+
+    fn edge_label(&self, value: &T) -> Option<String> {
+        value.edge_label()
+    }
+}
+
+///
+/// Visualizes nodes via the two given functions `stringify` and `emphasize`.
+///
+pub(crate) struct FnVisualizer<S, E>(pub(crate) S, pub(crate) E);
+
+impl<T, S, E> NodeVisualizer<T> for FnVisualizer<S, E>
+where
+    S: Fn(&T) -> String,
+    E: Fn(&T) -> bool,
+{
+    fn stringify(&self, value: &T) -> String {
+        (self.0)(value)
+    }
+
+    fn emphasize(&self, value: &T) -> bool {
+        (self.1)(value)
+    }
+}
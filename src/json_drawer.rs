@@ -0,0 +1,107 @@
+//! The module with a drawer that exports the embedding as JSON.
+
+use crate::{Drawer, EmbeddedNode, Embedding, Result};
+use std::{borrow::Borrow, io::Write};
+
+///
+/// The `JsonDrawer` type provides the transformation of the embedding information into a JSON
+/// array, which can be consumed by renderers outside of Rust, e.g. with D3 or on a canvas.
+///
+/// Each node of the embedding becomes an object with the fields `ord`, `parent`, `text`,
+/// `x_center`, `x_extent`, `y_order` and `emphasized`, where `parent` is `null` for roots.
+/// The coordinates are the logical ones of the embedding.
+/// Only the already computed embedding is written, so neither the node type nor this crate need
+/// the `serde` feature.
+///
+#[derive(Debug, Default)]
+pub struct JsonDrawer;
+
+impl JsonDrawer {
+    /// Method to create a fresh instance of the `JsonDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Escapes the given text to be used inside a JSON string.
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    ///
+    /// Writes the given nodes like [Drawer::draw_to_writer], but consumes them one at a time,
+    /// e.g. from the iterator of
+    /// [Layouter::embed_streaming_with][crate::Layouter::embed_streaming_with], so the nodes are
+    /// never held in memory all at once.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    pub fn draw_nodes_to_writer(
+        &self,
+        writer: &mut dyn Write,
+        nodes: impl IntoIterator<Item = EmbeddedNode>,
+    ) -> Result<()> {
+        Ok(build_json(writer, nodes.into_iter())?)
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `JsonDrawer`.
+///
+impl Drawer for JsonDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        Ok(build_json(writer, embedding.iter())?)
+    }
+}
+
+/// Writes the nodes as JSON array, one object per line
+fn build_json<N: Borrow<EmbeddedNode>>(
+    out: &mut dyn Write,
+    nodes: impl Iterator<Item = N>,
+) -> std::io::Result<()> {
+    write!(out, "[")?;
+    for (i, data) in nodes.enumerate() {
+        let data = data.borrow();
+        let parent = data
+            .parent
+            .map_or_else(|| "null".to_string(), |parent| parent.to_string());
+        // The separator is written before the next node, which needn't be known in advance
+        let separator = if i > 0 { "," } else { "" };
+        write!(
+            out,
+            "{}\n  {{ \"ord\": {}, \"parent\": {}, \"text\": \"{}\", \"x_center\": {}, \
+             \"x_extent\": {}, \"y_order\": {}, \"emphasized\": {} }}",
+            separator,
+            data.ord,
+            parent,
+            JsonDrawer::escape(&data.text),
+            data.x_center,
+            data.x_extent,
+            data.y_order,
+            data.is_emphasized,
+        )?;
+    }
+    writeln!(out, "\n]")?;
+
+    out.flush()
+}
@@ -0,0 +1,111 @@
+//! The module with free functions that compute embeddings without the [Layouter][crate::Layouter]
+//! builder, e.g. for libraries that wrap this crate and manage the output themselves.
+//!
+//! The functions lay out the tree with the default settings of the `Layouter`, i.e. they return
+//! the same embedding as `Layouter::new(tree).embed_with_visualize()` resp.
+//! `Layouter::new(tree).embed_with(..)`.
+
+use syntree::{index::Index, pointer::Width, Tree};
+
+use crate::{
+    internal::{
+        embedder::Embedder,
+        options::EmbedderOptions,
+        visualizer::{FnVisualizer, NodeVisualizer, TraitVisualizer},
+    },
+    EmbeddedNodes, Embedding, Result, Visualize,
+};
+
+///
+/// Creates an embedding of the nodes of the given tree in the plane.
+/// The nodes representation is taken from the [Visualize] implementation of type T.
+///
+/// ```
+/// use syntree_layout::{layout, Visualize};
+///
+/// struct Label(&'static str);
+///
+/// impl Visualize for Label {
+///     fn visualize(&self) -> String {
+///         self.0.to_string()
+///     }
+/// }
+///
+/// let mut tree = syntree::Builder::new();
+/// tree.open(Label("root")).unwrap();
+/// tree.token(Label("leaf"), 1).unwrap();
+/// tree.close().unwrap();
+/// let tree = tree.build().unwrap();
+///
+/// let embedding = layout::layout(&tree).unwrap();
+/// assert_eq!(2, embedding.len());
+/// ```
+///
+pub fn layout<T, I, W>(tree: &Tree<T, I, W>) -> Result<Embedding>
+where
+    T: Visualize,
+    I: Index,
+    W: Width,
+{
+    embed(tree, &TraitVisualizer, &EmbedderOptions::default())
+}
+
+///
+/// Creates an embedding of the nodes of the given tree in the plane.
+/// The nodes representation is taken from the two given functions, so no trait implementation
+/// is necessary for the tree's node type T.
+///
+/// ```
+/// use syntree_layout::layout;
+///
+/// let mut tree = syntree::Builder::new();
+/// tree.open(1).unwrap();
+/// tree.token(2, 1).unwrap();
+/// tree.close().unwrap();
+/// let tree = tree.build().unwrap();
+///
+/// let embedding = layout::layout_with(&tree, |n| n.to_string(), |n| *n == 2).unwrap();
+/// assert!(embedding[1].is_emphasized);
+/// ```
+///
+pub fn layout_with<T, I, W>(
+    tree: &Tree<T, I, W>,
+    stringify: impl Fn(&T) -> String,
+    emphasize: impl Fn(&T) -> bool,
+) -> Result<Embedding>
+where
+    I: Index,
+    W: Width,
+{
+    embed(
+        tree,
+        &FnVisualizer(stringify, emphasize),
+        &EmbedderOptions::default(),
+    )
+}
+
+/// The common entry point of the free functions and the `Layouter`, which passes its options
+pub(crate) fn embed<T, I, W>(
+    tree: &Tree<T, I, W>,
+    visualizer: &dyn NodeVisualizer<T>,
+    options: &EmbedderOptions<T, W>,
+) -> Result<Embedding>
+where
+    I: Index,
+    W: Width,
+{
+    Embedder::embed(tree, visualizer, options)
+}
+
+/// Like [embed] but the nodes are converted one at a time while they are iterated
+pub(crate) fn embed_streaming<T, I, W>(
+    tree: &Tree<T, I, W>,
+    visualizer: &dyn NodeVisualizer<T>,
+    options: &EmbedderOptions<T, W>,
+) -> Result<EmbeddedNodes<W>>
+where
+    I: Index,
+    W: Width,
+{
+    Embedder::embed_streaming(tree, visualizer, options)
+}
@@ -1,37 +1,1126 @@
 //! The module with the **Public API**.
 
-use std::fmt::{Debug, Display};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display},
+};
 
-use syntree::{index::Index, pointer::Width, Tree};
+use syntree::{
+    index::Index,
+    pointer::{Pointer, Width},
+    Tree,
+};
 
 use crate::{
-    internal::embedder::Embedder, Drawer, Embedding, LayouterError, Result, SvgDrawer, Visualize,
+    internal::{
+        options::EmbedderOptions,
+        visualizer::{FnVisualizer, TraitVisualizer},
+    },
+    layout, CharWidthFunction, DotDrawer, Drawer, EmbeddedNode, EmbeddedNodes, Embedding,
+    GraphMlDrawer, HtmlDrawer, JsonDrawer, LayoutDirection, LayoutStyle, LayouterError,
+    MermaidDrawer, ParentAlignment, PlantUmlDrawer, Result, Rounding, SvgDrawer, TextDrawer,
+    TikzDrawer, TypedDrawer, Visualize,
 };
 
-pub type StringifyFunction<T> = Box<dyn Fn(&T) -> String>;
-pub type EmphasizeFunction<T> = Box<dyn Fn(&T) -> bool>;
+///
+/// The drawer of a [Layouter], which is either borrowed from the caller or owned by the layouter.
+///
+enum LayouterDrawer<'d> {
+    Borrowed(&'d dyn Drawer),
+    Owned(Box<dyn Drawer>),
+}
+
+impl LayouterDrawer<'_> {
+    fn get(&self) -> &dyn Drawer {
+        match self {
+            LayouterDrawer::Borrowed(drawer) => *drawer,
+            LayouterDrawer::Owned(drawer) => drawer.as_ref(),
+        }
+    }
+}
+
+/// The crate's drawer for the format the given file's extension stands for, see
+/// [Layouter::write]
+fn drawer_for_extension(file_name: &std::path::Path) -> Box<dyn Drawer> {
+    let extension = file_name
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("dot" | "gv") => Box::new(DotDrawer::new()),
+        Some("json") => Box::new(JsonDrawer::new()),
+        Some("graphml") => Box::new(GraphMlDrawer::new()),
+        Some("mmd" | "mermaid") => Box::new(MermaidDrawer::new()),
+        Some("puml" | "plantuml") => Box::new(PlantUmlDrawer::new()),
+        Some("tex") => Box::new(TikzDrawer::new()),
+        Some("txt") => Box::new(TextDrawer::new()),
+        Some("html" | "htm") => Box::new(HtmlDrawer::new()),
+        #[cfg(feature = "csv")]
+        Some("csv") => Box::new(crate::CsvDrawer::new()),
+        #[cfg(feature = "png")]
+        Some("png") => Box::new(crate::PngDrawer::new()),
+        _ => Box::new(SvgDrawer::new()),
+    }
+}
+
+///
+/// The Layouter type provides a simple builder mechanism with a fluent API.
+///
+pub struct Layouter<'t, 'd, 'p, T, I, W>
+where
+    I: Index,
+    W: Width,
+{
+    tree: &'t Tree<T, I, W>,
+    drawer: Option<LayouterDrawer<'d>>,
+    file_name: Option<&'p std::path::Path>,
+    embedding: Embedding,
+    options: EmbedderOptions<T, W>,
+    post_embed: Option<PostEmbedFunction>,
+}
+
+/// A function that adjusts the nodes after the embedding, see [Layouter::with_post_embed]
+type PostEmbedFunction = Box<dyn FnMut(&mut [EmbeddedNode])>;
+
+impl<'t, 'd, 'p, T, I, W> Layouter<'t, 'd, 'p, T, I, W>
+where
+    I: Index,
+    W: Width,
+{
+    ///
+    /// Creates a new Layouter with the required tree.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree);
+    /// ```
+    ///
+    pub fn new(tree: &'t Tree<T, I, W>) -> Self {
+        Self {
+            tree,
+            drawer: None,
+            file_name: None,
+            embedding: Embedding::default(),
+            options: EmbedderOptions::default(),
+            post_embed: None,
+        }
+    }
+
+    ///
+    /// Sets the path of the output file on the layouter.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    /// use std::path::Path;
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_file_path(Path::new("target/tmp/test.svg"));
+    /// ```
+    ///
+    pub fn with_file_path(self, path: &'p std::path::Path) -> Self {
+        Self {
+            tree: self.tree,
+            file_name: Some(path),
+            drawer: self.drawer,
+            embedding: self.embedding,
+            options: self.options,
+            post_embed: self.post_embed,
+        }
+    }
+
+    ///
+    /// Sets the path of the output file on an existing layouter.
+    /// In contrast to [Layouter::with_file_path] the layouter is not consumed, so an embedding
+    /// can be written to several files without computing it again.
+    ///
+    /// ```no_run
+    /// use syntree_layout::{DotDrawer, Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    /// use std::path::Path;
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let dot_drawer = DotDrawer::new();
+    /// let mut layouter = Layouter::new(&tree)
+    ///     .with_file_path(Path::new("target/tmp/a.svg"))
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// layouter.write().unwrap();
+    ///
+    /// layouter.set_file_path(Path::new("target/tmp/b.dot"));
+    /// layouter.set_drawer(&dot_drawer);
+    /// layouter.write().unwrap();
+    /// ```
+    ///
+    pub fn set_file_path(&mut self, path: &'p std::path::Path) -> &mut Self {
+        self.file_name = Some(path);
+        self
+    }
+
+    ///
+    /// Sets a different drawer when you don't want to use the default svg-drawer.
+    /// If this method is not called the crate's own svg-drawer is used.
+    ///
+    /// ```
+    /// use syntree_layout::{Drawer, Layouter, Embedding, Result, Visualize};
+    /// use syntree::{Tree, Builder};
+    /// use std::path::Path;
+    ///
+    /// struct NilDrawer;
+    /// impl Drawer for NilDrawer {
+    ///     fn draw(&self, _file_name: &Path, _embedding: &Embedding) -> Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let drawer = NilDrawer;
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_drawer(&drawer)
+    ///     .with_file_path(Path::new("target/tmp/test.svg"));
+    /// ```
+    ///
+    pub fn with_drawer(self, drawer: &'d dyn Drawer) -> Self {
+        Self {
+            tree: self.tree,
+            file_name: self.file_name,
+            drawer: Some(LayouterDrawer::Borrowed(drawer)),
+            embedding: self.embedding,
+            options: self.options,
+            post_embed: self.post_embed,
+        }
+    }
+
+    ///
+    /// Like [Layouter::with_drawer] but the layouter takes ownership of the drawer, so the drawer
+    /// can be chosen at runtime, e.g. depending on the output file's extension.
+    ///
+    /// ```
+    /// use syntree_layout::{DotDrawer, Drawer, Layouter, SvgDrawer, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.token(MyNodeData(0), 1).unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let drawer: Box<dyn Drawer> = match "dot" {
+    ///     "dot" => Box::new(DotDrawer::new()),
+    ///     _ => Box::new(SvgDrawer::new()),
+    /// };
+    /// let output = Layouter::new(&tree)
+    ///     .with_boxed_drawer(drawer)
+    ///     .embed_with_visualize()
+    ///     .unwrap()
+    ///     .write_to_string()
+    ///     .unwrap();
+    /// assert!(output.starts_with("digraph"));
+    /// ```
+    ///
+    pub fn with_boxed_drawer(self, drawer: Box<dyn Drawer>) -> Self {
+        Self {
+            tree: self.tree,
+            file_name: self.file_name,
+            drawer: Some(LayouterDrawer::Owned(drawer)),
+            embedding: self.embedding,
+            options: self.options,
+            post_embed: self.post_embed,
+        }
+    }
+
+    ///
+    /// Sets the drawer on an existing layouter.
+    /// In contrast to [Layouter::with_drawer] the layouter is not consumed, so an embedding
+    /// can be drawn by several drawers without computing it again, see
+    /// [Layouter::set_file_path].
+    ///
+    pub fn set_drawer(&mut self, drawer: &'d dyn Drawer) -> &mut Self {
+        self.drawer = Some(LayouterDrawer::Borrowed(drawer));
+        self
+    }
+
+    ///
+    /// Sets the horizontal gap in logical coordinate units that is inserted between adjacent root
+    /// nodes when the tree is a forest, i.e. when it has more than one top-level node.
+    /// If this method is not called a gap of 2 units is used.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_root_gap(4);
+    /// ```
+    ///
+    pub fn with_root_gap(mut self, root_gap: usize) -> Self {
+        self.options.root_gap = root_gap;
+        self
+    }
+
+    ///
+    /// Requires the tree to have a single root, the embed methods fail with
+    /// [LayouterError::MultipleRoots] for a forest instead of placing its roots side by side.
+    /// An embedded subtree, see [Layouter::with_subtree_root], always has a single root.
+    /// Use [Layouter::root_count] to check the tree up front.
+    /// If this method is not called forests are embedded.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, LayouterError, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.token(MyNodeData(0), 1).unwrap();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let result = Layouter::new(&tree)
+    ///     .with_single_root(true)
+    ///     .embed_with_visualize();
+    /// assert!(matches!(result, Err(LayouterError::MultipleRoots { found: 2 })));
+    /// ```
+    ///
+    pub fn with_single_root(mut self, single_root: bool) -> Self {
+        self.options.single_root = single_root;
+        self
+    }
+
+    ///
+    /// Sets a hook that adjusts the nodes after each embed method has laid them out and before
+    /// they are written, e.g. to move a subtree manually for a presentation. The hook gets
+    /// mutable access to the embedding's nodes, which are sorted by their `ord`. It should only
+    /// change their coordinates and appearance, drawers rely on the `ord`s and parents.
+    /// The streaming embed methods don't run the hook, since they don't keep the nodes.
+    /// If this method is not called the nodes are kept as they are laid out.
+    ///
+    /// ```
+    /// use syntree_layout::{EmbeddedNode, Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_post_embed(|nodes: &mut [EmbeddedNode]| nodes[1].x_center += 4)
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert_eq!(5, layouter.embedding()[1].x_center);
+    /// ```
+    ///
+    pub fn with_post_embed(
+        mut self,
+        post_embed: impl FnMut(&mut [EmbeddedNode]) + 'static,
+    ) -> Self {
+        self.post_embed = Some(Box::new(post_embed));
+        self
+    }
+
+    ///
+    /// Sets the function that measures how many columns a single character of a node's text
+    /// representation occupies. This is used to calculate the x-extent of the nodes.
+    /// If this method is not called each character occupies one column, see
+    /// [single_column_char_width][crate::single_column_char_width].
+    ///
+    /// ```
+    /// use syntree_layout::{east_asian_char_width, Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_char_width(east_asian_char_width);
+    /// ```
+    ///
+    pub fn with_char_width(mut self, char_width: CharWidthFunction) -> Self {
+        self.options.char_width = char_width;
+        self
+    }
+
+    ///
+    /// Sets a function that measures the width of a line of a node's text representation in
+    /// columns, e.g. with the real metrics of a proportional font, whose pixel width is divided
+    /// by the drawer's unit width. It replaces the character widths for the x-extent of the
+    /// nodes, the widest line counts and fractions are rounded up to whole columns. The drawers
+    /// size the nodes' boxes with the same widths, see [EmbeddedNode::line_widths]. Truncating
+    /// and wrapping labels still uses the character widths of [Layouter::with_char_width].
+    /// If this method is not called the width is the sum of the character widths.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_text_measure(|line: &str| line.len() as f32 * 0.8);
+    /// ```
+    ///
+    pub fn with_text_measure(mut self, measure: impl Fn(&str) -> f32 + 'static) -> Self {
+        self.options.text_measure = Some(Box::new(measure));
+        self
+    }
+
+    ///
+    /// Sets the direction in which the layers of the tree grow.
+    /// If this method is not called the root is placed at the top and the children fan out
+    /// downwards, see [LayoutDirection::TopToBottom].
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, LayoutDirection, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_direction(LayoutDirection::LeftToRight);
+    /// ```
+    ///
+    pub fn with_direction(mut self, direction: LayoutDirection) -> Self {
+        self.options.direction = direction;
+        self
+    }
+
+    ///
+    /// Sets the arrangement of the layers. With [LayoutStyle::Radial] the root is placed at the
+    /// center and its descendants on concentric rings around it, the direction is then ignored.
+    /// The `SvgDrawer` draws radial embeddings accordingly, other drawers treat them like layered
+    /// ones.
+    /// If this method is not called the layers are parallel, see [LayoutStyle::Layered].
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, LayoutStyle, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_layout_style(LayoutStyle::Radial);
+    /// ```
+    ///
+    pub fn with_layout_style(mut self, layout_style: LayoutStyle) -> Self {
+        self.options.layout_style = layout_style;
+        self
+    }
+
+    ///
+    /// Inverts the order of the layers, so that the root is placed at the bottom and the leaves at
+    /// the top of the layout. With [LayoutDirection::LeftToRight] the root is placed at the right.
+    /// If this method is not called the root is placed in the first layer.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_inverted(true);
+    /// ```
+    ///
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.options.inverted = inverted;
+        self
+    }
+
+    ///
+    /// Sets where parents are placed relative to their children, e.g. over their first child
+    /// for list-like trees. The subtrees are widened where a parent overhangs its children.
+    /// If this method is not called parents are centered over their children, see
+    /// [ParentAlignment::Center].
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, ParentAlignment, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_parent_alignment(ParentAlignment::OverFirstChild);
+    /// ```
+    ///
+    pub fn with_parent_alignment(mut self, parent_alignment: ParentAlignment) -> Self {
+        self.options.parent_alignment = parent_alignment;
+        self
+    }
+
+    ///
+    /// Sets how the half width of the children is rounded when a parent is centered over
+    /// several children whose width is odd, e.g. [Rounding::Nearest] for symmetric looking
+    /// output of small trees, where a shift by one column is noticeable.
+    /// Parents over a single child stay aligned with it in any case.
+    /// If this method is not called the half width is rounded down, see [Rounding::Floor].
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Rounding, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_rounding(Rounding::Nearest);
+    /// ```
+    ///
+    pub fn with_rounding(mut self, rounding: Rounding) -> Self {
+        self.options.rounding = rounding;
+        self
+    }
+
+    ///
+    /// Places the siblings from right to left, so that the first child is the rightmost one,
+    /// e.g. for right-to-left scripts or grammars that build the children from right to left.
+    /// This mirrors each layer horizontally while the order of the layers is kept. Only the
+    /// placement is reversed, the `ord`s keep the order of the tree. With
+    /// [LayoutDirection::LeftToRight] the first child is placed at the bottom.
+    /// If this method is not called the siblings are placed from left to right.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_right_to_left(true);
+    /// ```
+    ///
+    pub fn with_right_to_left(mut self, right_to_left: bool) -> Self {
+        self.options.right_to_left = right_to_left;
+        self
+    }
+
+    ///
+    /// Reverses the order in which the children of each node are placed, for grammars that
+    /// build the children from right to left. The tree itself is not changed.
+    /// This is the same option as [Layouter::with_right_to_left], under the name that describes
+    /// the tree rather than the script.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_reversed_children(true);
+    /// ```
+    ///
+    pub fn with_reversed_children(self, reversed_children: bool) -> Self {
+        self.with_right_to_left(reversed_children)
+    }
+
+    ///
+    /// Centers the children of different parents concurrently, which speeds up the embedding of
+    /// very large trees. The embedding is the same as the one created sequentially, the compact
+    /// layout is always created sequentially.
+    /// This method is only available with the `rayon` feature, which enables the concurrent
+    /// centering by default.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_parallel_centering(false);
+    /// ```
+    ///
+    #[cfg(feature = "rayon")]
+    pub fn with_parallel_centering(mut self, parallel: bool) -> Self {
+        self.options.parallel = parallel;
+        self
+    }
+
+    ///
+    /// Places all leaves in the last layer, e.g. to align the tokens of a syntax tree in one
+    /// row. The edges to leaves above the last layer are elongated accordingly, the placement of
+    /// the other nodes is unchanged. Together with [Layouter::with_inverted] the leaves are
+    /// placed in the first layer.
+    /// If this method is not called each leaf is placed in the layer of its depth.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_leaves_at_bottom(true);
+    /// ```
+    ///
+    pub fn with_leaves_at_bottom(mut self, leaves_at_bottom: bool) -> Self {
+        self.options.leaves_at_bottom = leaves_at_bottom;
+        self
+    }
+
+    ///
+    /// Sets the maximum width of the layout in logical coordinate units.
+    /// Embedding a tree whose layout would be wider fails with
+    /// [LayouterError::LayoutTooLarge][crate::LayouterError::LayoutTooLarge], e.g. to reject
+    /// trees that are too wide to be viewed reasonably. The width is the one without
+    /// [compaction][Layouter::with_compaction].
+    /// If this method is not called the maximum is `isize::MAX`, which prevents the computed
+    /// extents from overflowing.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, LayouterError, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.token(MyNodeData(1000), 1).unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let result = Layouter::new(&tree)
+    ///     .with_max_layout_width(3)
+    ///     .embed_with_visualize();
+    /// assert!(matches!(result, Err(LayouterError::LayoutTooLarge(3))));
+    /// ```
+    ///
+    pub fn with_max_layout_width(mut self, max_layout_width: usize) -> Self {
+        self.options.max_layout_width = max_layout_width;
+        self
+    }
+
+    ///
+    /// Wraps the trees of a forest into several bands, so that the layout keeps an aspect ratio
+    /// closer to a page instead of becoming a single wide row of trees.
+    /// The trees are placed side by side until the next one would exceed the given width in
+    /// logical coordinate units. It then starts a new band whose roots are placed in the layer
+    /// below the deepest layer of the previous band. A tree that is wider than the width gets a
+    /// band of its own. Unlike [Layouter::with_max_layout_width] this never fails.
+    /// If this method is not called all trees are placed in a single band.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// tree.token(MyNodeData(2), 1).unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_max_width(3)
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// let (first, second) = (&layouter.embedding()[0], &layouter.embedding()[1]);
+    /// assert_eq!((0, 1), (first.y_order, second.y_order));
+    /// assert_eq!(first.x_center, second.x_center);
+    /// ```
+    ///
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.options.max_width = Some(max_width);
+        self
+    }
+
+    ///
+    /// Enables the compact layout, which places sibling subtrees as close as their contours
+    /// allow, in the style of the Reingold-Tilford algorithm. A narrow part of a subtree can then
+    /// be placed next to a wide part of its sibling subtree on a deeper layer, which reduces the
+    /// total width of bushy trees. Nodes never overlap and the order of the siblings is kept.
+    /// If this method is not called each subtree reserves the width of its widest layer.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_compaction(true);
+    /// ```
+    ///
+    pub fn with_compaction(mut self, compact: bool) -> Self {
+        self.options.compact = compact;
+        self
+    }
+
+    ///
+    /// Sets the spacing between two adjacent layers. It is given in empty text lines, or in empty
+    /// text columns for [LayoutDirection::LeftToRight], and is respected by the crate's drawers.
+    /// If this method is not called a spacing of 2 is used.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_layer_spacing(4);
+    /// ```
+    ///
+    pub fn with_layer_spacing(mut self, layer_spacing: usize) -> Self {
+        self.options.layer_spacing = layer_spacing;
+        self
+    }
+
+    ///
+    /// Sets the padding in logical coordinate units that is added to the extent of each node and
+    /// thus separates adjacent siblings.
+    /// If this method is not called a padding of 1 unit is used.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_sibling_spacing(3);
+    /// ```
+    ///
+    pub fn with_sibling_spacing(mut self, sibling_spacing: usize) -> Self {
+        self.options.sibling_spacing = sibling_spacing;
+        self
+    }
+
+    ///
+    /// Sets the minimum gap in logical coordinate units between sibling subtrees, so that
+    /// subtrees never touch even in dense layouts. In contrast to the sibling spacing, which
+    /// widens each node, the gutter separates the outer borders of adjacent subtrees of the same
+    /// parent. The compact layout packs the subtrees with at least this gap, the radial layout
+    /// ignores it.
+    /// If this method is not called no gap is inserted.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_subtree_gutter(2);
+    /// ```
+    ///
+    pub fn with_subtree_gutter(mut self, subtree_gutter: usize) -> Self {
+        self.options.subtree_gutter = subtree_gutter;
+        self
+    }
+
+    ///
+    /// Limits the embedding to the layers up to the given depth, the root is at depth 0.
+    /// Deeper nodes are omitted and the label of each node whose children are hidden gets an
+    /// ellipsis `…` appended.
+    /// If this method is not called all nodes are embedded.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_max_depth(3);
+    /// ```
+    ///
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.options.max_depth = Some(max_depth);
+        self
+    }
+
+    ///
+    /// Limits the column width of the nodes' labels.
+    /// Wider labels are truncated at a character boundary and end with an ellipsis `…`, so that
+    /// very long labels don't widen the whole layout. The complete text remains available in
+    /// [EmbeddedNode::full_text][crate::EmbeddedNode::full_text], the `SvgDrawer` shows it as
+    /// tooltip.
+    /// If this method is not called the labels are not truncated.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_max_label_width(20);
+    /// ```
+    ///
+    pub fn with_max_label_width(mut self, max_label_width: usize) -> Self {
+        self.options.max_label_width = Some(max_label_width);
+        self
+    }
+
+    ///
+    /// Wraps the lines of the nodes' labels that are wider than the given column width at word
+    /// boundaries, so that long labels grow in height instead of width. Words that don't fit
+    /// into a line on their own are broken at a character boundary. The nodes' line counts
+    /// reflect the wrapped lines, so drawers grow the nodes' boxes accordingly.
+    /// In contrast to [Layouter::with_max_label_width] no text is lost. If both are set, the
+    /// wrapped lines are truncated.
+    /// If this method is not called the labels are not wrapped.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(&'static str);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.token(MyNodeData("a rather long label"), 1).unwrap();
+    /// let tree = tree.build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_label_wrap(8)
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert_eq!("a rather\nlong\nlabel", layouter.embedding()[0].text);
+    /// ```
+    ///
+    pub fn with_label_wrap(mut self, max_width: usize) -> Self {
+        self.options.label_wrap = Some(max_width);
+        self
+    }
+
+    ///
+    /// Sets the minimum width of the nodes in text columns, so that nodes with empty or very
+    /// short labels, e.g. structural markers, are still drawn as visible boxes. The nodes'
+    /// `x_extent` grows accordingly, with [LayoutDirection::LeftToRight] the minimum applies to
+    /// the width of the layer instead.
+    /// If this method is not called the nodes are as wide as their labels.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_min_node_width(3);
+    /// ```
+    ///
+    pub fn with_min_node_width(mut self, min_node_width: usize) -> Self {
+        self.options.min_node_width = min_node_width;
+        self
+    }
+
+    ///
+    /// Adds the given number of text columns as padding on both sides of each label, so the
+    /// label stays centered within its node's box and the box grows by twice the padding.
+    /// `x_extent` grows accordingly, with [LayoutDirection::LeftToRight] the padding widens the
+    /// layers instead. The padding is part of the node, whereas the sibling spacing separates
+    /// the nodes.
+    /// If this method is not called the boxes fit the labels.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    ///
+    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_horizontal_padding(1);
+    /// ```
+    ///
+    pub fn with_horizontal_padding(mut self, horizontal_padding: usize) -> Self {
+        self.options.horizontal_padding = horizontal_padding;
+        self
+    }
+
+    ///
+    /// Sizes and orders the nodes by their spans in the source instead of their labels, which
+    /// gives a layout that mirrors the extents of the underlying text. A node's `x_extent` is
+    /// the length of its span plus the sibling spacing, resp. its height with
+    /// [LayoutDirection::LeftToRight], and siblings are placed in the order of their spans'
+    /// starts. The minimum node width and the horizontal padding still apply to the spans in
+    /// both directions, a sibling order set by [Layouter::with_sibling_sort] takes precedence
+    /// for siblings with equal starts.
+    /// Labels that are longer than their spans may overlap their neighbors.
+    /// If this method is not called the nodes are sized by their labels.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// tree.token(MyNodeData(1), 5).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_span_layout(true)
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert_eq!(6, layouter.embedding()[1].x_extent);
+    /// ```
+    ///
+    pub fn with_span_layout(mut self, span_layout: bool) -> Self {
+        self.options.span_layout = span_layout;
+        self
+    }
 
-///
-/// The Layouter type provides a simple builder mechanism with a fluent API.
-///
-pub struct Layouter<'t, 'd, 'p, T, I, W>
-where
-    I: Index,
-    W: Width,
-{
-    tree: &'t Tree<T, I, W>,
-    drawer: Option<&'d dyn Drawer>,
-    file_name: Option<&'p std::path::Path>,
-    embedding: Embedding,
-}
+    ///
+    /// Emphasizes the nodes with the given ids in addition to the nodes emphasized by the
+    /// visualization, e.g. to highlight the results of a search that are determined at runtime.
+    /// If this method is not called only the visualization decides which nodes are emphasized.
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// let leaf = tree.token(MyNodeData(1), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_emphasized_ids(&HashSet::from([leaf]))
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert!(!layouter.embedding()[0].is_emphasized);
+    /// assert!(layouter.embedding()[1].is_emphasized);
+    /// ```
+    ///
+    pub fn with_emphasized_ids(mut self, ids: &HashSet<W::Pointer>) -> Self {
+        self.options.emphasized_ids = ids.clone();
+        self
+    }
 
-impl<'t, 'd, 'p, T, I, W> Layouter<'t, 'd, 'p, T, I, W>
-where
-    I: Index,
-    W: Width,
-{
     ///
-    /// Creates a new Layouter with the required tree.
+    /// Embeds only the subtree of the node with the given id, e.g. to show a part of a large tree
+    /// without building a separate tree. The node becomes the root in layer 0, the layers of its
+    /// descendants are relative to it. All other nodes are omitted.
+    /// Embedding fails with [LayouterError::UnknownSubtreeRoot] if the id doesn't belong to a
+    /// node of the tree.
+    /// If this method is not called the whole tree is embedded.
     ///
     /// ```
     /// use syntree_layout::{Layouter, Visualize};
@@ -44,27 +1133,38 @@ where
     ///     fn emphasize(&self) -> bool { false }
     /// }
     ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// let inner = tree.open(MyNodeData(1)).unwrap();
+    /// tree.token(MyNodeData(2), 1).unwrap();
+    /// tree.close().unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
     ///
-    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
-    /// let layouter = Layouter::new(&tree);
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_subtree_root(inner)
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert_eq!(2, layouter.embedding().len());
+    /// assert_eq!("1", layouter.embedding()[0].text);
     /// ```
     ///
-    pub fn new(tree: &'t Tree<T, I, W>) -> Self {
-        Self {
-            tree,
-            drawer: None,
-            file_name: None,
-            embedding: Vec::default(),
-        }
+    pub fn with_subtree_root(mut self, id: W::Pointer) -> Self {
+        self.options.subtree_root = Some(id);
+        self
     }
 
     ///
-    /// Sets the path of the output file on the layouter.
+    /// Puts the subtree of the node with the given id in focus, e.g. for presentations: The
+    /// node and its descendants are drawn as usual, whereas all other nodes and their edges are
+    /// dimmed to a reduced [opacity][crate::EmbeddedNode::opacity] but stay visible as context.
+    /// Embedding fails with [LayouterError::UnknownFocus] if the id doesn't belong to an embedded
+    /// node of the tree.
+    /// If this method is not called no node is dimmed.
     ///
     /// ```
     /// use syntree_layout::{Layouter, Visualize};
     /// use syntree::{Tree, Builder};
-    /// use std::path::Path;
     ///
     /// struct MyNodeData(i32);
     ///
@@ -73,37 +1173,77 @@ where
     ///     fn emphasize(&self) -> bool { false }
     /// }
     ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// let inner = tree.open(MyNodeData(1)).unwrap();
+    /// tree.token(MyNodeData(2), 1).unwrap();
+    /// tree.close().unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
     ///
-    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
     /// let layouter = Layouter::new(&tree)
-    ///     .with_file_path(Path::new("target/tmp/test.svg"));
+    ///     .with_focus(inner)
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert!(layouter.embedding()[0].opacity.is_some());
+    /// assert!(layouter.embedding()[1].opacity.is_none());
     /// ```
     ///
-    pub fn with_file_path(self, path: &'p std::path::Path) -> Self {
-        Self {
-            tree: self.tree,
-            file_name: Some(path),
-            drawer: self.drawer,
-            embedding: self.embedding,
-        }
+    pub fn with_focus(mut self, id: W::Pointer) -> Self {
+        self.options.focus = Some(id);
+        self
     }
 
     ///
-    /// Sets a different drawer when you don't want to use the default svg-drawer.
-    /// If this method is not called the crate's own svg-drawer is used.
+    /// Sorts the siblings with the given comparator of the nodes' values before they are placed,
+    /// e.g. to show them in alphabetical order. This includes the roots of a forest.
+    /// The order only affects the layout, the tree itself isn't changed. The embedding's
+    /// [ord][crate::EmbeddedNode::ord]s follow the sorted order.
+    /// Siblings that compare equal keep their order in the tree.
+    /// If this method is not called the siblings are placed in their order in the tree.
     ///
     /// ```
-    /// use syntree_layout::{Drawer, Layouter, EmbeddedNode, Result, Visualize};
+    /// use syntree_layout::{Layouter, Visualize};
     /// use syntree::{Tree, Builder};
-    /// use std::path::Path;
     ///
-    /// struct NilDrawer;
-    /// impl Drawer for NilDrawer {
-    ///     fn draw(&self, _file_name: &Path, _embedding: &[EmbeddedNode]) -> Result<()> {
-    ///         Ok(())
-    ///     }
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
     /// }
     ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// tree.token(MyNodeData(2), 1).unwrap();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .with_sibling_sort(|a: &MyNodeData, b: &MyNodeData| a.0.cmp(&b.0))
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert_eq!("1", layouter.embedding()[1].text);
+    /// ```
+    ///
+    pub fn with_sibling_sort(mut self, compare: impl Fn(&T, &T) -> Ordering + 'static) -> Self {
+        self.options.sibling_order = Some(Box::new(compare));
+        self
+    }
+
+    ///
+    /// Sorts the roots of a forest with the given comparator of the nodes' values before they
+    /// are placed from left to right, e.g. by their position in the source, which may differ
+    /// from their order in the tree. In contrast to [Layouter::with_sibling_sort] only the
+    /// roots are sorted, for them this order takes precedence.
+    /// Roots that compare equal keep their order in the tree resp. in the sibling order.
+    /// If this method is not called the roots are placed like other siblings.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
     /// struct MyNodeData(i32);
     ///
     /// impl Visualize for MyNodeData {
@@ -111,21 +1251,21 @@ where
     ///     fn emphasize(&self) -> bool { false }
     /// }
     ///
+    /// let mut tree = Builder::new();
+    /// tree.token(MyNodeData(2), 1).unwrap();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// let tree = tree.build().unwrap();
     ///
-    /// let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
-    /// let drawer = NilDrawer;
     /// let layouter = Layouter::new(&tree)
-    ///     .with_drawer(&drawer)
-    ///     .with_file_path(Path::new("target/tmp/test.svg"));
+    ///     .with_root_order(|a: &MyNodeData, b: &MyNodeData| a.0.cmp(&b.0))
+    ///     .embed_with_visualize()
+    ///     .unwrap();
+    /// assert_eq!("1", layouter.embedding()[0].text);
     /// ```
     ///
-    pub fn with_drawer(self, drawer: &'d dyn Drawer) -> Self {
-        Self {
-            tree: self.tree,
-            file_name: self.file_name,
-            drawer: Some(drawer),
-            embedding: self.embedding,
-        }
+    pub fn with_root_order(mut self, compare: impl Fn(&T, &T) -> Ordering + 'static) -> Self {
+        self.options.root_order = Some(Box::new(compare));
+        self
     }
 
     ///
@@ -133,6 +1273,24 @@ where
     /// functionality and uses the drawer which writes the result to the output file in its own
     /// output format.
     ///
+    /// Without a drawer set by [Layouter::with_drawer] or [Layouter::with_boxed_drawer] the
+    /// drawer is chosen by the file's extension:
+    ///
+    /// | Extension          | Drawer             |
+    /// |--------------------|--------------------|
+    /// | `dot`, `gv`        | [DotDrawer]        |
+    /// | `json`             | [JsonDrawer]       |
+    /// | `graphml`          | [GraphMlDrawer]    |
+    /// | `mmd`, `mermaid`   | [MermaidDrawer]    |
+    /// | `puml`, `plantuml` | [PlantUmlDrawer]   |
+    /// | `tex`              | [TikzDrawer]       |
+    /// | `txt`              | [TextDrawer]       |
+    /// | `html`, `htm`      | [HtmlDrawer]       |
+    /// | `csv`              | `CsvDrawer`, with the `csv` feature |
+    /// | `png`              | `PngDrawer`, with the `png` feature |
+    ///
+    /// All other files, e.g. with the extension `svg`, are written by the [SvgDrawer].
+    ///
     /// ```
     /// use syntree_layout::{Layouter, Visualize, Result};
     /// use syntree::{Tree, Builder};
@@ -157,21 +1315,201 @@ where
     /// ```
     ///
     pub fn write(&self) -> Result<()> {
-        if self.file_name.is_none() {
-            Err(LayouterError::from_description(
-                "No output file name given - use Layouter::with_file_path.",
-            ))
+        if let Some(file_name) = self.file_name {
+            match &self.drawer {
+                Some(drawer) => drawer.get().draw(file_name, &self.embedding),
+                None => drawer_for_extension(file_name).draw(file_name, &self.embedding),
+            }
         } else {
-            let default_drawer = SvgDrawer::new();
-            let drawer = self.drawer.unwrap_or(&default_drawer);
-            drawer.draw(self.file_name.unwrap(), &self.embedding)
+            Err(LayouterError::MissingOutputPath)
         }
     }
 
-    /// Provides access to the embedding data for other uses than drawing, e.g. for tests
+    ///
+    /// Like [write][Layouter::write] but instead of writing to the output file the drawer's output
+    /// is returned as a string. No output file name is needed.
+    /// The drawer has to support [Drawer::draw_to_writer] for this to work, as the crate's
+    /// drawers do.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize, Result};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    ///     fn emphasize(&self) -> bool { false }
+    /// }
+    ///
+    /// fn test() -> Result<String> {
+    ///     let mut tree = Builder::new();
+    ///     tree.token(MyNodeData(0), 1).unwrap();
+    ///     let tree = tree.build().unwrap();
+    ///     Layouter::new(&tree)
+    ///         .embed_with_visualize()?
+    ///         .write_to_string()
+    /// }
+    ///
+    /// assert!(test().expect("Embedding should work").contains("<svg"));
+    /// ```
+    ///
+    pub fn write_to_string(&self) -> Result<String> {
+        let default_drawer = SvgDrawer::new();
+        let drawer = self
+            .drawer
+            .as_ref()
+            .map_or(&default_drawer as &dyn Drawer, LayouterDrawer::get);
+        let mut buffer = Vec::new();
+        drawer.draw_to_writer(&mut buffer, &self.embedding)?;
+        String::from_utf8(buffer)
+            .map_err(|_| LayouterError::from_description("The drawer's output is not valid UTF-8"))
+    }
+
+    ///
+    /// Like [write][Layouter::write] but with a [TypedDrawer], which gets the nodes' original
+    /// values along with the embedding. The drawer set by [Layouter::with_drawer] isn't used.
+    ///
+    /// ```
+    /// use syntree_layout::{EmbeddedNode, Embedding, Layouter, Result, TypedDrawer};
+    /// use std::{io::Write, path::Path};
+    ///
+    /// enum Token {
+    ///     Number(i32),
+    ///     Plus,
+    /// }
+    ///
+    /// struct TokenDrawer;
+    ///
+    /// impl TypedDrawer<Token> for TokenDrawer {
+    ///     fn draw_typed_to_writer(
+    ///         &self,
+    ///         writer: &mut dyn Write,
+    ///         _embedding: &Embedding,
+    ///         nodes: &[(&Token, &EmbeddedNode)],
+    ///     ) -> Result<()> {
+    ///         for (token, node) in nodes {
+    ///             let kind = match token {
+    ///                 Token::Number(_) => "number",
+    ///                 Token::Plus => "operator",
+    ///             };
+    ///             writeln!(writer, "{} at {}", kind, node.x_center)?;
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open(Token::Plus).unwrap();
+    /// tree.token(Token::Number(1), 1).unwrap();
+    /// tree.token(Token::Number(2), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// Layouter::new(&tree)
+    ///     .with_file_path(Path::new("target/tmp/tokens.txt"))
+    ///     .embed_with(
+    ///         |token| match token {
+    ///             Token::Number(n) => n.to_string(),
+    ///             Token::Plus => "+".to_string(),
+    ///         },
+    ///         |_| false,
+    ///     )
+    ///     .unwrap()
+    ///     .write_typed(&TokenDrawer)
+    ///     .unwrap();
+    /// ```
+    ///
+    pub fn write_typed(&self, drawer: &dyn TypedDrawer<T>) -> Result<()> {
+        let file_name = self.file_name.ok_or(LayouterError::MissingOutputPath)?;
+        drawer.draw_typed(file_name, &self.embedding, &self.typed_nodes())
+    }
+
+    ///
+    /// The embedded nodes together with their values in the tree, in the order of the
+    /// embedding, e.g. to call a [TypedDrawer] directly.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    pub fn typed_nodes(&self) -> Vec<(&'t T, &EmbeddedNode)> {
+        let values = self
+            .tree
+            .walk()
+            .map(|node| (node.id().get(), node.value()))
+            .collect::<HashMap<_, _>>();
+        self.embedding
+            .iter()
+            .filter_map(|e| values.get(&e.node_id).map(|value| (*value, e)))
+            .collect()
+    }
+
+    ///
+    /// Provides access to the embedding data for other uses than drawing, e.g. for tests or to
+    /// place the tree inside a larger scene.
+    /// The embedding is empty until one of the embed methods has been called.
+    /// See [Embedding] for the coordinate conventions.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, Visualize};
+    /// use syntree::{Tree, Builder};
+    ///
+    /// struct MyNodeData(i32);
+    ///
+    /// impl Visualize for MyNodeData {
+    ///     fn visualize(&self) -> std::string::String { self.0.to_string() }
+    /// }
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.open(MyNodeData(0)).unwrap();
+    /// tree.token(MyNodeData(1), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    /// let (root, child) = (&layouter.embedding()[0], &layouter.embedding()[1]);
+    /// assert_eq!((0, 1), (root.y_order, child.y_order));
+    /// assert_eq!(root.x_center, child.x_center);
+    /// ```
+    ///
     pub fn embedding(&self) -> &Embedding {
         &self.embedding
     }
+
+    /// Stores the new embedding after running the post embed hook on it
+    fn embedded(mut self, mut embedding: Embedding) -> Self {
+        if let Some(post_embed) = &mut self.post_embed {
+            embedding.update_nodes(|nodes| post_embed(nodes));
+        }
+        self.embedding = embedding;
+        self
+    }
+
+    ///
+    /// The number of roots of the tree, i.e. its top-level nodes, which is more than one for a
+    /// forest and zero for an empty tree. It is cheap to query before embedding, e.g. to
+    /// require a single root with [Layouter::with_single_root] or to choose the settings for
+    /// forests.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.token("first", 1).unwrap();
+    /// tree.token("second", 1).unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// assert_eq!(2, Layouter::new(&tree).root_count());
+    /// ```
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(r), where r is the number of roots.
+    ///
+    pub fn root_count(&self) -> usize {
+        self.tree.children().count()
+    }
 }
 
 impl<'t, 'd, 'p, T, I, W> Layouter<'t, 'd, 'p, T, I, W>
@@ -191,17 +1529,16 @@ where
     /// bugs in coding. Please report such panics.
     ///
     pub fn embed_with_visualize(self) -> Result<Self> {
-        let embedding = Embedder::embed(
-            self.tree,
-            Box::new(|value: &T| value.visualize()),
-            Box::new(|value: &T| value.emphasize()),
-        )?;
-        Ok(Self {
-            tree: self.tree,
-            file_name: self.file_name,
-            drawer: self.drawer,
-            embedding,
-        })
+        let embedding = layout::embed(self.tree, &TraitVisualizer, &self.options)?;
+        Ok(self.embedded(embedding))
+    }
+
+    ///
+    /// Like [embed_streaming_with][Layouter::embed_streaming_with] but the nodes representation
+    /// is taken from the [Visualize][crate::Visualize] implementation of type T.
+    ///
+    pub fn embed_streaming_with_visualize(&self) -> Result<EmbeddedNodes<W>> {
+        layout::embed_streaming(self.tree, &TraitVisualizer, &self.options)
     }
 }
 
@@ -221,17 +1558,12 @@ where
     /// bugs in coding. Please report such panics.
     ///
     pub fn embed_with_debug(self) -> Result<Self> {
-        let embedding = Embedder::embed(
+        let embedding = layout::embed(
             self.tree,
-            Box::new(|value: &T| format!("{value:?}")),
-            Box::new(|_value: &T| false),
+            &FnVisualizer(|value: &T| format!("{value:?}"), |_value: &T| false),
+            &self.options,
         )?;
-        Ok(Self {
-            tree: self.tree,
-            file_name: self.file_name,
-            drawer: self.drawer,
-            embedding,
-        })
+        Ok(self.embedded(embedding))
     }
 }
 
@@ -243,7 +1575,26 @@ where
 {
     ///
     /// This method creates an embedding of the nodes of the given tree in the plane.
-    /// The nodes representation is taken form the [Display] implementation of type T.
+    /// The nodes representation is taken form the [Display] implementation of type T, no node
+    /// is emphasized.
+    /// This way trees of primitive types or strings can be laid out without implementing the
+    /// [Visualize] trait.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    /// use syntree::Builder;
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.open(1).unwrap();
+    /// tree.token(2, 1).unwrap();
+    /// tree.token(3, 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree).embed().unwrap();
+    /// assert_eq!("2", layouter.embedding()[1].text);
+    /// assert!(!layouter.embedding()[1].is_emphasized);
+    /// ```
     ///
     /// # Panics
     ///
@@ -251,17 +1602,12 @@ where
     /// bugs in coding. Please report such panics.
     ///
     pub fn embed(self) -> Result<Self> {
-        let embedding = Embedder::embed(
+        let embedding = layout::embed(
             self.tree,
-            Box::new(|value: &T| format!("{value}")),
-            Box::new(|_value: &T| false),
+            &FnVisualizer(|value: &T| format!("{value}"), |_value: &T| false),
+            &self.options,
         )?;
-        Ok(Self {
-            tree: self.tree,
-            file_name: self.file_name,
-            drawer: self.drawer,
-            embedding,
-        })
+        Ok(self.embedded(embedding))
     }
 }
 
@@ -274,6 +1620,25 @@ where
     /// This method creates an embedding of the nodes of the given tree in the plane.
     /// The nodes representation is taken form the two given functions
     /// [stringify][Layouter::embed_with] and [emphasize][Layouter::embed_with].
+    /// This way no trait implementation is necessary for the tree's node type T, which is
+    /// especially useful for foreign types.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    /// use syntree::Builder;
+    ///
+    /// let mut tree = Builder::new();
+    /// tree.open("root".to_string()).unwrap();
+    /// tree.token("leaf".to_string(), 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .embed_with(|s: &String| s.to_uppercase(), |s: &String| s == "leaf")
+    ///     .unwrap();
+    /// assert_eq!("LEAF", layouter.embedding()[1].text);
+    /// assert!(layouter.embedding()[1].is_emphasized);
+    /// ```
     ///
     /// # Panics
     ///
@@ -281,16 +1646,103 @@ where
     /// bugs in coding. Please report such panics.
     ///
     pub fn embed_with(
+        self,
+        stringify: impl Fn(&T) -> String,
+        emphasize: impl Fn(&T) -> bool,
+    ) -> Result<Self> {
+        let embedding = layout::embed(
+            self.tree,
+            &FnVisualizer(stringify, emphasize),
+            &self.options,
+        )?;
+        Ok(self.embedded(embedding))
+    }
+
+    ///
+    /// Like [embed_with][Layouter::embed_with] but instead of storing the embedding in the
+    /// layouter its nodes are returned as an iterator, which converts them one at a time. This
+    /// way drawers that consume the nodes in a single pass don't need a second copy of them, see
+    /// [EmbeddedNodes]. The iterator yields the same nodes in the same order as
+    /// [Layouter::embedding] after embedding with the same settings, but the overlap check of
+    /// debug builds is skipped.
+    ///
+    /// ```
+    /// use syntree_layout::{JsonDrawer, Layouter};
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open(1).unwrap();
+    /// tree.token(2, 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let nodes = Layouter::new(&tree)
+    ///     .embed_streaming_with(|n| n.to_string(), |_| false)
+    ///     .unwrap();
+    /// let mut buffer = Vec::new();
+    /// JsonDrawer::new()
+    ///     .draw_nodes_to_writer(&mut buffer, nodes)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Complexity
+    ///
+    /// Each node is converted in constant time while iterating.
+    ///
+    pub fn embed_streaming_with(
         &self,
-        stringify: StringifyFunction<T>,
-        emphasize: EmphasizeFunction<T>,
+        stringify: impl Fn(&T) -> String,
+        emphasize: impl Fn(&T) -> bool,
+    ) -> Result<EmbeddedNodes<W>> {
+        layout::embed_streaming(
+            self.tree,
+            &FnVisualizer(stringify, emphasize),
+            &self.options,
+        )
+    }
+
+    ///
+    /// Like [embed_with][Layouter::embed_with] but the two functions additionally get the given
+    /// context, e.g. a symbol table or the source text the nodes' spans refer to. This way
+    /// labels can be derived from data that is held outside of the tree.
+    ///
+    /// ```
+    /// use syntree_layout::Layouter;
+    /// use syntree::Builder;
+    ///
+    /// let source = "let x = 1;";
+    ///
+    /// // The nodes hold the byte ranges of their text in the source
+    /// let mut tree = Builder::new();
+    /// tree.open(0..10).unwrap();
+    /// tree.token(4..5, 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let layouter = Layouter::new(&tree)
+    ///     .embed_with_context(
+    ///         source,
+    ///         |source: &str, span: &std::ops::Range<usize>| source[span.clone()].to_string(),
+    ///         |_, span| span.len() == 1,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!("x", layouter.embedding()[1].text);
+    /// assert!(layouter.embedding()[1].is_emphasized);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The method should not panic. If you encounter a panic this should be originated from
+    /// bugs in coding. Please report such panics.
+    ///
+    pub fn embed_with_context<C: ?Sized>(
+        self,
+        context: &C,
+        stringify: impl Fn(&C, &T) -> String,
+        emphasize: impl Fn(&C, &T) -> bool,
     ) -> Result<Self> {
-        let embedding = Embedder::embed(self.tree, stringify, emphasize)?;
-        Ok(Self {
-            tree: self.tree,
-            file_name: self.file_name,
-            drawer: self.drawer,
-            embedding,
-        })
+        self.embed_with(
+            |value| stringify(context, value),
+            |value| emphasize(context, value),
+        )
     }
 }
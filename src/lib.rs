@@ -1,14 +1,46 @@
+#[cfg(feature = "csv")]
+mod csv_drawer;
+mod dot_drawer;
 mod drawer;
 mod embedding;
 mod errors;
+mod graphml_drawer;
+mod html_drawer;
 mod internal;
+mod json_drawer;
+pub mod layout;
 mod layouter;
+mod measure;
+mod mermaid_drawer;
+mod plantuml_drawer;
+#[cfg(feature = "png")]
+mod png_drawer;
 mod svg_drawer;
+mod text_drawer;
+mod tikz_drawer;
 mod visualize;
 
-pub use drawer::Drawer;
-pub use embedding::{EmbeddedNode, Embedding};
+#[cfg(feature = "csv")]
+pub use csv_drawer::CsvDrawer;
+pub use dot_drawer::DotDrawer;
+pub use drawer::{Drawer, TypedDrawer};
+pub use embedding::{
+    EmbeddedNode, EmbeddedNodes, Embedding, LayoutDirection, LayoutStyle, ParentAlignment, Rounding,
+};
 pub use errors::{LayouterError, Result};
+pub use graphml_drawer::GraphMlDrawer;
+pub use html_drawer::HtmlDrawer;
+pub use json_drawer::JsonDrawer;
 pub use layouter::Layouter;
-pub use svg_drawer::SvgDrawer;
+pub use measure::{east_asian_char_width, single_column_char_width, CharWidthFunction};
+pub use mermaid_drawer::MermaidDrawer;
+pub use plantuml_drawer::PlantUmlDrawer;
+#[cfg(feature = "png")]
+pub use png_drawer::PngDrawer;
+pub use svg_drawer::{
+    Align, AnnotationKind, CanvasOverflow, EdgeStyle, EmphasisStyle, LayerSpacingFunction,
+    NodeShape, SvgDrawer, Theme, Unit,
+};
+pub use text_drawer::TextDrawer;
+pub use tikz_drawer::TikzDrawer;
 pub use visualize::Visualize;
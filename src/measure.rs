@@ -0,0 +1,158 @@
+//! The module with functions to measure the column width of the nodes' text representations.
+
+///
+/// Type of a function that returns the number of columns a single character occupies.
+/// It can be set with [Layouter::with_char_width][crate::Layouter::with_char_width].
+///
+pub type CharWidthFunction = fn(char) -> usize;
+
+///
+/// The default character width function. Each Unicode scalar value occupies exactly one column.
+///
+pub fn single_column_char_width(_c: char) -> usize {
+    1
+}
+
+///
+/// A character width function that lets East Asian wide and full-width characters (CJK ideographs,
+/// Hiragana, Katakana, Hangul, full-width forms) occupy two columns. All other characters occupy
+/// one column.
+///
+/// ```
+/// use syntree_layout::east_asian_char_width;
+///
+/// assert_eq!(1, east_asian_char_width('a'));
+/// assert_eq!(2, east_asian_char_width('日'));
+/// ```
+///
+pub fn east_asian_char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Measures the column width of the given text using the given character width function.
+/// For multi-line texts this is the width of the widest line.
+pub(crate) fn text_width(text: &str, char_width: CharWidthFunction) -> usize {
+    text.lines()
+        .map(|line| line.chars().map(char_width).sum())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Measures the column width of each line of the given text using the given character width
+/// function.
+pub(crate) fn line_widths(text: &str, char_width: CharWidthFunction) -> Vec<usize> {
+    text.lines()
+        .map(|line| line.chars().map(char_width).sum())
+        .collect()
+}
+
+/// Measures the column width of each line of the given text with the given measure, rounded up
+/// to whole columns.
+pub(crate) fn measured_line_widths(text: &str, measure: &dyn Fn(&str) -> f32) -> Vec<usize> {
+    text.lines()
+        .map(|line| measure(line).max(0.0).ceil() as usize)
+        .collect()
+}
+
+/// Truncates each line of the given text that is wider than `max_width` columns and ends it with
+/// an ellipsis `…`, so that it fits into `max_width` columns. The text is truncated at character
+/// boundaries only.
+/// Returns `None` if the text fits as it is.
+pub(crate) fn truncate(
+    text: &str,
+    max_width: usize,
+    char_width: CharWidthFunction,
+) -> Option<String> {
+    const ELLIPSIS: char = '…';
+    if text_width(text, char_width) <= max_width {
+        return None;
+    }
+    let available = max_width.saturating_sub(char_width(ELLIPSIS));
+    let truncated = text
+        .lines()
+        .map(|line| {
+            if text_width(line, char_width) <= max_width {
+                return line.to_string();
+            }
+            let mut width = 0;
+            let mut truncated = line
+                .chars()
+                .take_while(|c| {
+                    width += char_width(*c);
+                    width <= available
+                })
+                .collect::<String>();
+            truncated.push(ELLIPSIS);
+            truncated
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(truncated)
+}
+
+/// Wraps each line of the given text that is wider than `max_width` columns at word boundaries,
+/// so that no line exceeds `max_width` columns. Words that are wider on their own are broken at
+/// character boundaries. Lines are only joined at single spaces, and each line keeps at least
+/// one character.
+/// Returns `None` if the text fits as it is.
+pub(crate) fn wrap(text: &str, max_width: usize, char_width: CharWidthFunction) -> Option<String> {
+    if text_width(text, char_width) <= max_width {
+        return None;
+    }
+    let space = char_width(' ');
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        if text_width(line, char_width) <= max_width {
+            lines.push(line.to_string());
+            continue;
+        }
+        let mut current = String::new();
+        let mut width = 0;
+        for word in line.split_whitespace() {
+            if !current.is_empty() {
+                let word_width = text_width(word, char_width);
+                if width + space + word_width <= max_width {
+                    current.push(' ');
+                    current.push_str(word);
+                    width += space + word_width;
+                    continue;
+                }
+                lines.push(std::mem::take(&mut current));
+                width = 0;
+            }
+            // The word starts a new line, which is broken if the word is too wide
+            for c in word.chars() {
+                if !current.is_empty() && width + char_width(c) > max_width {
+                    lines.push(std::mem::take(&mut current));
+                    width = 0;
+                }
+                current.push(c);
+                width += char_width(c);
+            }
+        }
+        lines.push(current);
+    }
+    Some(lines.join("\n"))
+}
+
+/// Counts the lines of the given text, which is at least one even for an empty text.
+pub(crate) fn line_count(text: &str) -> usize {
+    text.lines().count().max(1)
+}
@@ -0,0 +1,101 @@
+//! The module with a drawer that emits Mermaid flowcharts.
+
+use crate::{Drawer, Embedding, LayoutDirection, Result};
+use std::io::Write;
+
+/// The name of the class that is applied to emphasized nodes
+const EMPHASIZED_CLASS: &str = "emphasized";
+
+///
+/// The `MermaidDrawer` type provides the transformation of the embedding information into a
+/// [Mermaid](https://mermaid.js.org) flowchart, which can be embedded e.g. into Markdown documents
+/// on GitHub.
+///
+/// Each node of the embedding becomes a node `n{ord}` with its text as label. The edges are
+/// reconstructed from the parent relationship. Emphasized nodes get the class `emphasized`
+/// which renders them bold.
+/// Since Mermaid does its own layout the computed coordinates are ignored, only the embedding's
+/// [LayoutDirection] is respected.
+///
+#[derive(Debug, Default)]
+pub struct MermaidDrawer;
+
+impl MermaidDrawer {
+    /// Method to create a fresh instance of the `MermaidDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Escapes the given text to be used inside a quoted Mermaid label.
+    /// Mermaid expects entity codes for characters that have a meaning in its syntax.
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '"' => escaped.push_str("#quot;"),
+                '#' => escaped.push_str("#35;"),
+                '[' => escaped.push_str("#91;"),
+                ']' => escaped.push_str("#93;"),
+                '(' => escaped.push_str("#40;"),
+                ')' => escaped.push_str("#41;"),
+                '{' => escaped.push_str("#123;"),
+                '}' => escaped.push_str("#125;"),
+                '<' => escaped.push_str("#lt;"),
+                '>' => escaped.push_str("#gt;"),
+                '\n' => escaped.push_str("<br>"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `MermaidDrawer`.
+///
+impl Drawer for MermaidDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        fn build_mermaid(out: &mut dyn Write, embedding: &Embedding) -> std::io::Result<()> {
+            let direction = match embedding.direction() {
+                LayoutDirection::TopToBottom => "TD",
+                LayoutDirection::LeftToRight => "LR",
+            };
+            writeln!(out, "graph {}", direction)?;
+            writeln!(
+                out,
+                "    classDef {} font-weight:bold,stroke-width:3px;",
+                EMPHASIZED_CLASS
+            )?;
+
+            for data in embedding {
+                writeln!(
+                    out,
+                    "    n{}[\"{}\"]",
+                    data.ord,
+                    MermaidDrawer::escape(&data.text)
+                )?;
+            }
+
+            for data in embedding {
+                if let Some(parent) = data.parent {
+                    writeln!(out, "    n{} --> n{}", parent, data.ord)?;
+                }
+            }
+
+            for data in embedding.iter().filter(|e| e.is_emphasized) {
+                writeln!(out, "    class n{} {};", data.ord, EMPHASIZED_CLASS)?;
+            }
+
+            out.flush()
+        }
+
+        Ok(build_mermaid(writer, embedding)?)
+    }
+}
@@ -0,0 +1,80 @@
+//! The module with a drawer that emits PlantUML diagrams.
+
+use crate::{Drawer, Embedding, LayoutDirection, Result};
+use std::io::Write;
+
+///
+/// The `PlantUmlDrawer` type provides the transformation of the embedding information into a
+/// [PlantUML](https://plantuml.com) diagram, which can be included e.g. into `.puml` documents.
+///
+/// Each node of the embedding becomes a rectangle `n{ord}` with its text as label. The edges are
+/// reconstructed from the parent relationship as `n{parent} --> n{ord}` relations. Emphasized
+/// nodes get a bold label.
+/// Since PlantUML does its own layout the computed coordinates are ignored, only the embedding's
+/// [LayoutDirection] is respected.
+///
+#[derive(Debug, Default)]
+pub struct PlantUmlDrawer;
+
+impl PlantUmlDrawer {
+    /// Method to create a fresh instance of the `PlantUmlDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Escapes the given text to be used inside a quoted PlantUML label.
+    /// Characters that end the label or start an escape sequence or markup are replaced by their
+    /// Unicode code point notation, line breaks by PlantUML's `\n`.
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '"' | '\\' | '<' | '>' | '~' => escaped.push_str(&format!("<U+{:04X}>", c as u32)),
+                '\n' => escaped.push_str("\\n"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `PlantUmlDrawer`.
+///
+impl Drawer for PlantUmlDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        fn build_plantuml(out: &mut dyn Write, embedding: &Embedding) -> std::io::Result<()> {
+            writeln!(out, "@startuml")?;
+            if embedding.direction() == LayoutDirection::LeftToRight {
+                writeln!(out, "left to right direction")?;
+            }
+
+            for data in embedding {
+                let label = PlantUmlDrawer::escape(&data.text);
+                if data.is_emphasized {
+                    writeln!(out, "rectangle \"<b>{}</b>\" as n{}", label, data.ord)?;
+                } else {
+                    writeln!(out, "rectangle \"{}\" as n{}", label, data.ord)?;
+                }
+            }
+
+            for data in embedding {
+                if let Some(parent) = data.parent {
+                    writeln!(out, "n{} --> n{}", parent, data.ord)?;
+                }
+            }
+
+            writeln!(out, "@enduml")?;
+            out.flush()
+        }
+
+        Ok(build_plantuml(writer, embedding)?)
+    }
+}
@@ -0,0 +1,101 @@
+//! The module with a drawer that rasterizes the svg output to the PNG format.
+
+use crate::{Drawer, Embedding, LayouterError, Result, SvgDrawer};
+use resvg::{tiny_skia, usvg};
+use std::io::Write;
+
+///
+/// The `PngDrawer` type provides the transformation of the embedding information into a PNG
+/// raster image. It is only available with the `png` feature.
+///
+/// The image is created from the same markup the [SvgDrawer] produces, which is rasterized with
+/// [resvg](https://crates.io/crates/resvg). The svg output can be configured by passing a
+/// customized `SvgDrawer`, the resolution of the raster by a scale factor.
+///
+/// ```
+/// use syntree_layout::{PngDrawer, SvgDrawer};
+///
+/// // Twice the resolution of the svg output, with arrowheads
+/// let drawer = PngDrawer::new()
+///     .with_scale(2.0)
+///     .with_svg_drawer(SvgDrawer::new().with_arrowheads(true));
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct PngDrawer {
+    svg_drawer: SvgDrawer,
+    scale: f32,
+}
+
+impl Default for PngDrawer {
+    fn default() -> Self {
+        Self {
+            svg_drawer: SvgDrawer::default(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl PngDrawer {
+    /// Method to create a fresh instance of the `PngDrawer` type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Sets the factor the svg output is scaled with, i.e. the number of pixels per svg pixel.
+    /// The default is 1.0.
+    ///
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    ///
+    /// Sets the `SvgDrawer` that creates the markup which is rasterized.
+    /// By default an `SvgDrawer` with default settings is used.
+    ///
+    pub fn with_svg_drawer(mut self, svg_drawer: SvgDrawer) -> Self {
+        self.svg_drawer = svg_drawer;
+        self
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `PngDrawer`.
+///
+impl Drawer for PngDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// The texts are rendered with the fonts installed on the system.
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        let mut svg = Vec::new();
+        self.svg_drawer.draw_to_writer(&mut svg, embedding)?;
+
+        let mut options = usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree = usvg::Tree::from_data(&svg, &options)
+            .map_err(|e| LayouterError::Description(format!("Invalid svg: {e}")))?;
+
+        let size = tree
+            .size()
+            .scale_by(self.scale)
+            .ok_or(LayouterError::from_description("Invalid scale factor"))?
+            .to_int_size();
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+            .ok_or(LayouterError::from_description("Invalid image size"))?;
+        resvg::render(
+            &tree,
+            tiny_skia::Transform::from_scale(self.scale, self.scale),
+            &mut pixmap.as_mut(),
+        );
+
+        let png = pixmap
+            .encode_png()
+            .map_err(|e| LayouterError::Description(format!("PNG encoding failed: {e}")))?;
+        writer.write_all(&png)?;
+        Ok(writer.flush()?)
+    }
+}
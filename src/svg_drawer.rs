@@ -1,40 +1,1025 @@
 //! The module with the crate's default drawer.
 
-use crate::{Drawer, EmbeddedNode, LayouterError, Result};
-use std::path::Path;
+use crate::{Drawer, EmbeddedNode, Embedding, LayoutDirection, LayoutStyle, LayouterError, Result};
+use std::{io::Write, rc::Rc};
 use xml_writer::XmlWriter;
 
-use std::fs::File;
+/// The default width of one column, i.e. of one character, in pixels
+const DEFAULT_UNIT_WIDTH: f32 = 10.0;
+/// The default height of one text line in pixels
+const DEFAULT_UNIT_HEIGHT: f32 = 10.0;
+/// The id of the arrowhead marker definition
+const ARROWHEAD_ID: &str = "arrowhead";
+/// The advance width of a character of the monospace font relative to the font size
+const CHAR_WIDTH_PER_FONT_SIZE: f32 = 0.6;
+/// The default font family of the texts
+const DEFAULT_FONT_FAMILY: &str = "Courier";
+/// The opacity of the grid lines, which are drawn in the color of the nodes' outlines
+const GRID_OPACITY: f32 = 0.25;
+/// The opacities of the alternating layer bands, which are filled in the color of the nodes'
+/// outlines
+const LAYER_BAND_OPACITIES: [f32; 2] = [0.08, 0.03];
 
-const X_MARGIN: f32 = 10.0;
-const Y_MARGIN: f32 = 25.0;
-const Y_FACTOR: f32 = 3.5;
-const FONT_X_SIZE: f32 = 10.0;
-const FONT_Y_SIZE: f32 = 10.0;
+///
+/// The type of functions that determine the vertical space in pixels below each layer of a
+/// [LayoutDirection::TopToBottom] layout, given the layer's depth.
+/// It can be set with [SvgDrawer::with_layer_spacing_fn].
+///
+pub type LayerSpacingFunction = Rc<dyn Fn(usize) -> f32>;
+
+/// Wraps the [LayerSpacingFunction] so that the [SvgDrawer] can still derive `Debug`
+#[derive(Clone)]
+struct LayerSpacing(LayerSpacingFunction);
+
+impl std::fmt::Debug for LayerSpacing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LayerSpacing(..)")
+    }
+}
+
+///
+/// The [EdgeStyle] determines how the [SvgDrawer] routes the edges between parents and their
+/// children.
+/// It can be set with [SvgDrawer::with_edge_style].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EdgeStyle {
+    /// A straight line from the parent to the child.
+    #[default]
+    Straight,
+    /// An elbow connector that leaves the parent towards the child's layer, runs along the
+    /// midpoint between both layers and enters the child, resulting in the classic org chart
+    /// look.
+    Orthogonal,
+    /// A cubic Bézier curve that leaves the parent and enters the child perpendicular to the
+    /// layers, which keeps the edges apart from sibling nodes on densely packed layers.
+    Curved,
+}
+
+///
+/// The [NodeShape] determines the outline the [SvgDrawer] draws around a node's text.
+/// It can be set for all nodes with [SvgDrawer::with_node_shape] and for single nodes with
+/// [Visualize::shape][crate::Visualize::shape].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeShape {
+    /// A rectangle.
+    #[default]
+    Rect,
+    /// A rectangle with corners rounded by the given radius in pixels.
+    RoundedRect {
+        /// The radius of the corners
+        radius: f32,
+    },
+    /// An ellipse through the corners of the text's box, which makes it wider than the
+    /// rectangle. Nodes with an ellipse obtained from the `Visualize` trait reserve the
+    /// additional width in the layout, for all other nodes consider a larger sibling spacing.
+    Ellipse,
+}
+
+///
+/// The [EmphasisStyle] determines how the [SvgDrawer] emphasizes nodes.
+/// The text of emphasized nodes is always printed bold, the styles can add a border around it.
+/// It can be set with [SvgDrawer::with_emphasis_style].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmphasisStyle {
+    /// The text is printed bold.
+    #[default]
+    Bold,
+    /// The text is printed bold and surrounded by a dashed border.
+    DashedBorder,
+    /// The text is printed bold and surrounded by a thick border.
+    ThickBorder,
+}
+
+///
+/// The [AnnotationKind] determines which identifier the [SvgDrawer] shows in the top-right
+/// corner of each node, e.g. to debug the rules that built the tree.
+/// It can be set with [SvgDrawer::with_node_annotations].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// No annotations are drawn.
+    #[default]
+    None,
+    /// The node's [ord][EmbeddedNode::ord], i.e. its position in the embedding.
+    Ord,
+    /// The value of the node's [id in the tree][EmbeddedNode::node_id].
+    NodeId,
+}
+
+impl AnnotationKind {
+    /// The identifier of the given node, if any
+    fn identifier(self, data: &EmbeddedNode) -> Option<usize> {
+        match self {
+            AnnotationKind::None => None,
+            AnnotationKind::Ord => Some(data.ord),
+            AnnotationKind::NodeId => Some(data.node_id),
+        }
+    }
+}
+
+///
+/// The [Unit] of the image's `width` and `height` attributes.
+/// It can be set with [SvgDrawer::with_units]. The drawing itself is always laid out in pixels,
+/// i.e. the user units of the `viewBox`, which is scaled to the physical size of the image.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Unit {
+    /// Pixels, written without a unit suffix.
+    #[default]
+    Px,
+    /// Points, i.e. 1/72 inch with 96 pixels per inch.
+    Pt,
+    /// Millimeters with 96 pixels per inch.
+    Mm,
+}
+
+impl Unit {
+    /// The suffix of the attribute values
+    fn suffix(self) -> &'static str {
+        match self {
+            Unit::Px => "",
+            Unit::Pt => "pt",
+            Unit::Mm => "mm",
+        }
+    }
+
+    /// Converts the given length in pixels to this unit
+    fn convert(self, px: f32) -> f32 {
+        match self {
+            Unit::Px => px,
+            Unit::Pt => px * 72.0 / 96.0,
+            Unit::Mm => px * 25.4 / 96.0,
+        }
+    }
+}
+
+///
+/// The [Align] determines where the [SvgDrawer] places the drawing on a canvas that is larger
+/// than the drawing. It can be set with [SvgDrawer::with_alignment].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    /// At the top left corner of the canvas.
+    TopLeft,
+    /// Centered along the top side of the canvas.
+    Top,
+    /// At the top right corner of the canvas.
+    TopRight,
+    /// Centered along the left side of the canvas.
+    Left,
+    /// In the center of the canvas.
+    #[default]
+    Center,
+    /// Centered along the right side of the canvas.
+    Right,
+    /// At the bottom left corner of the canvas.
+    BottomLeft,
+    /// Centered along the bottom side of the canvas.
+    Bottom,
+    /// At the bottom right corner of the canvas.
+    BottomRight,
+}
+
+impl Align {
+    /// The fractions of the free horizontal and vertical space before the drawing
+    fn fractions(self) -> (f32, f32) {
+        match self {
+            Align::TopLeft => (0.0, 0.0),
+            Align::Top => (0.5, 0.0),
+            Align::TopRight => (1.0, 0.0),
+            Align::Left => (0.0, 0.5),
+            Align::Center => (0.5, 0.5),
+            Align::Right => (1.0, 0.5),
+            Align::BottomLeft => (0.0, 1.0),
+            Align::Bottom => (0.5, 1.0),
+            Align::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+///
+/// The [CanvasOverflow] determines how the [SvgDrawer] handles a drawing that exceeds the canvas
+/// set with [SvgDrawer::with_canvas_size].
+/// It can be set with [SvgDrawer::with_canvas_overflow].
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CanvasOverflow {
+    /// The drawing is scaled down, keeping its aspect ratio, until it fits the canvas.
+    #[default]
+    ScaleToFit,
+    /// The drawing keeps its size and the parts outside of the canvas are cut off.
+    Clip,
+}
+
+///
+/// The [Theme] holds the colors the [SvgDrawer] draws with.
+/// It can be set with [SvgDrawer::with_theme]. The colors can be any SVG color value like
+/// `"#ffcc00"` or `"yellow"`.
+///
+/// ```
+/// use syntree_layout::{SvgDrawer, Theme};
+///
+/// let drawer = SvgDrawer::new().with_theme(Theme {
+///     edge_color: "gray".to_string(),
+///     ..Theme::dark()
+/// });
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// The fill of the nodes. If set every node is drawn as filled shape, otherwise only nodes
+    /// that need an outline are drawn. A background color obtained from the `Visualize` trait
+    /// takes precedence.
+    pub node_fill: Option<String>,
+    /// The color of the nodes' outlines
+    pub node_stroke: String,
+    /// The color of the texts
+    pub text_color: String,
+    /// The color of the edges and their arrowheads
+    pub edge_color: String,
+    /// The color of the texts and outlines of emphasized nodes
+    pub emphasis_color: String,
+    /// The color of the image's background
+    pub background: String,
+}
+
+impl Theme {
+    /// Black on white, which is the default.
+    pub fn light() -> Self {
+        Self {
+            node_fill: None,
+            node_stroke: "black".to_string(),
+            text_color: "black".to_string(),
+            edge_color: "black".to_string(),
+            emphasis_color: "black".to_string(),
+            background: "white".to_string(),
+        }
+    }
+
+    /// Light colors on a dark gray background.
+    pub fn dark() -> Self {
+        Self {
+            node_fill: Some("#2d2d2d".to_string()),
+            node_stroke: "#808080".to_string(),
+            text_color: "#d4d4d4".to_string(),
+            edge_color: "#a0a0a0".to_string(),
+            emphasis_color: "#ffcc00".to_string(),
+            background: "#1e1e1e".to_string(),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
 
 ///
 /// The `SvgDrawer` type provides the transformation of the embedding information into the Svg
 /// format.
 ///
-#[derive(Debug, Default)]
-pub struct SvgDrawer;
+/// The logical coordinates of the embedding are mapped to pixels by the unit width, i.e. the
+/// width of one column resp. character, and the unit height, i.e. the height of one text line.
+/// Both can be configured, either directly or derived from the font size.
+///
+/// The edges are drawn in a group `<g class="edges">` beneath the group `<g class="nodes">`
+/// with the nodes' shapes and texts, so CSS rules can target both independently.
+///
+/// ```
+/// use syntree_layout::SvgDrawer;
+///
+/// // Characters of 9.6 pixels width in lines of 16 pixels height
+/// let drawer = SvgDrawer::new().with_font_size(16.0);
+///
+/// // Wider columns, e.g. for a font with a larger advance width
+/// let drawer = SvgDrawer::new().with_unit_width(12.0).with_unit_height(14.0);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct SvgDrawer {
+    font_size: Option<f32>,
+    unit_width: f32,
+    unit_height: f32,
+    edge_style: EdgeStyle,
+    arrowheads: bool,
+    margin: Option<f32>,
+    emphasis_style: EmphasisStyle,
+    node_shape: Option<NodeShape>,
+    theme: Theme,
+    stroke_width: Option<f32>,
+    layer_spacing_fn: Option<LayerSpacing>,
+    font_family: String,
+    embedded_font: Option<Vec<u8>>,
+    grid: Option<usize>,
+    grid_labels: bool,
+    layer_bands: bool,
+    fragment_mode: bool,
+    symbol_id: Option<String>,
+    /// Prepended to the ids of the groups, so that the cells of a combined drawing have unique
+    /// ids
+    id_prefix: String,
+    /// The arrowhead marker and the font face are defined by the enclosing document
+    shared_defs: bool,
+    unit: Unit,
+    coordinate_precision: Option<u8>,
+    canvas_size: Option<(f32, f32)>,
+    alignment: Align,
+    canvas_overflow: CanvasOverflow,
+    depth_gradient: Option<(String, String)>,
+    accessibility: bool,
+    node_annotations: AnnotationKind,
+    internal_node_dots: bool,
+}
+
+impl Default for SvgDrawer {
+    fn default() -> Self {
+        Self {
+            font_size: None,
+            unit_width: DEFAULT_UNIT_WIDTH,
+            unit_height: DEFAULT_UNIT_HEIGHT,
+            edge_style: EdgeStyle::default(),
+            arrowheads: false,
+            margin: None,
+            emphasis_style: EmphasisStyle::default(),
+            node_shape: None,
+            theme: Theme::default(),
+            stroke_width: None,
+            layer_spacing_fn: None,
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            embedded_font: None,
+            grid: None,
+            grid_labels: false,
+            layer_bands: false,
+            fragment_mode: false,
+            symbol_id: None,
+            id_prefix: String::new(),
+            shared_defs: false,
+            unit: Unit::default(),
+            coordinate_precision: None,
+            canvas_size: None,
+            alignment: Align::default(),
+            canvas_overflow: CanvasOverflow::default(),
+            depth_gradient: None,
+            accessibility: false,
+            node_annotations: AnnotationKind::default(),
+            internal_node_dots: false,
+        }
+    }
+}
 
 impl SvgDrawer {
     /// Method to create a fresh instance of the `SvgDrawer` type.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    ///
+    /// Sets the font size of the texts in pixels.
+    /// By default no font size is set and the viewer's default is used.
+    ///
+    /// The unit width and height are adapted to the font size, so that the space reserved for
+    /// the texts fits them. Call [SvgDrawer::with_unit_width] or [SvgDrawer::with_unit_height]
+    /// afterwards to override them.
+    ///
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self.unit_width = font_size * CHAR_WIDTH_PER_FONT_SIZE;
+        self.unit_height = font_size;
+        self
+    }
+
+    ///
+    /// Sets the width of one column of the embedding in pixels.
+    /// Each character of a text occupies one column.
+    /// The default is 10 pixels.
+    ///
+    pub fn with_unit_width(mut self, unit_width: f32) -> Self {
+        self.unit_width = unit_width;
+        self
+    }
+
+    ///
+    /// Sets the height of one text line in pixels.
+    /// The layers and their spacing are measured in text lines.
+    /// The default is 10 pixels.
+    ///
+    pub fn with_unit_height(mut self, unit_height: f32) -> Self {
+        self.unit_height = unit_height;
+        self
+    }
+
+    ///
+    /// Sets the style the edges are drawn with. Embeddings with
+    /// [LayoutStyle::Radial][crate::LayoutStyle::Radial] are always drawn with straight edges.
+    /// The default is [EdgeStyle::Straight].
+    ///
+    /// ```
+    /// use syntree_layout::{EdgeStyle, SvgDrawer};
+    ///
+    /// let drawer = SvgDrawer::new().with_edge_style(EdgeStyle::Orthogonal);
+    /// ```
+    ///
+    pub fn with_edge_style(mut self, edge_style: EdgeStyle) -> Self {
+        self.edge_style = edge_style;
+        self
+    }
+
+    ///
+    /// Draws an arrowhead at the child's end of each edge to show the direction from the parent
+    /// to the child. The arrowhead is sized relative to the unit height, so it scales with the
+    /// font size.
+    /// By default no arrowheads are drawn.
+    ///
+    pub fn with_arrowheads(mut self, arrowheads: bool) -> Self {
+        self.arrowheads = arrowheads;
+        self
+    }
+
+    ///
+    /// Sets the margin in pixels that is left free around the whole figure on every side.
+    /// By default the margin is one unit width, so it scales with the font size.
+    ///
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = Some(margin);
+        self
+    }
+
+    ///
+    /// Sets the style emphasized nodes are drawn with.
+    /// The default is [EmphasisStyle::Bold].
+    ///
+    /// ```
+    /// use syntree_layout::{EmphasisStyle, SvgDrawer};
+    ///
+    /// let drawer = SvgDrawer::new().with_emphasis_style(EmphasisStyle::DashedBorder);
+    /// ```
+    ///
+    pub fn with_emphasis_style(mut self, emphasis_style: EmphasisStyle) -> Self {
+        self.emphasis_style = emphasis_style;
+        self
     }
 
-    fn scale_y(y: usize) -> f32 {
-        y as f32 * FONT_Y_SIZE * Y_FACTOR + Y_MARGIN
+    ///
+    /// Sets the shape of the outline drawn around every node.
+    /// By default nodes have no outline, only those with a background color or an emphasis
+    /// border are drawn as rectangles. Shapes obtained from the `Visualize` trait take
+    /// precedence.
+    ///
+    /// ```
+    /// use syntree_layout::{NodeShape, SvgDrawer};
+    ///
+    /// let drawer = SvgDrawer::new().with_node_shape(NodeShape::RoundedRect { radius: 4.0 });
+    /// ```
+    ///
+    pub fn with_node_shape(mut self, node_shape: NodeShape) -> Self {
+        self.node_shape = Some(node_shape);
+        self
     }
 
-    fn scale_x(x: usize) -> f32 {
-        x as f32 * FONT_X_SIZE + X_MARGIN
+    ///
+    /// Fills the nodes with a color depending on their layer, which is interpolated from the
+    /// first color for the layer `0` to the second color for the last layer. Both colors have to
+    /// be hex colors like `"#ffcc00"` or `"#fc0"`, drawing fails otherwise.
+    /// The background colors obtained from the `Visualize` trait take precedence, emphasized
+    /// nodes keep the theme's fill so that their emphasis color stands out.
+    /// By default the nodes are filled with the theme's fill, see [Theme::node_fill].
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_depth_gradient("#ffffcc", "#41b6c4");
+    /// ```
+    ///
+    pub fn with_depth_gradient(mut self, from_color: &str, to_color: &str) -> Self {
+        self.depth_gradient = Some((from_color.to_string(), to_color.to_string()));
+        self
     }
 
-    fn measure_string(str: &str) -> f32 {
-        str.len() as f32 * FONT_X_SIZE
+    ///
+    /// Makes the image accessible to screen readers. The image gets the `img` role and an
+    /// `aria-label` with the number of nodes, each node is wrapped in a group whose
+    /// `aria-label` is the node's complete label.
+    /// By default these attributes are omitted, because they make the output more verbose.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_accessibility(true);
+    /// ```
+    ///
+    pub fn with_accessibility(mut self, accessibility: bool) -> Self {
+        self.accessibility = accessibility;
+        self
+    }
+
+    ///
+    /// Draws the chosen identifier of each node as small text in the top-right corner of its
+    /// box, separate from the label, e.g. the node's `ord` to debug grammar rules.
+    /// By default no annotations are drawn, see [AnnotationKind::None].
+    ///
+    /// ```
+    /// use syntree_layout::{AnnotationKind, SvgDrawer};
+    ///
+    /// let drawer = SvgDrawer::new().with_node_annotations(AnnotationKind::Ord);
+    /// ```
+    ///
+    pub fn with_node_annotations(mut self, node_annotations: AnnotationKind) -> Self {
+        self.node_annotations = node_annotations;
+        self
+    }
+
+    ///
+    /// Draws the nodes like classic AST figures: Internal nodes are small dots with their label
+    /// next to them, whereas leaves are boxes around their texts, see [EmbeddedNode::child_count].
+    /// The leaves' boxes have the node's shape resp. the drawer's one, rectangles by default.
+    /// The labels of internal nodes extend to the right of their dots, so consider a larger
+    /// sibling spacing for long labels.
+    /// By default all nodes are drawn alike.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_internal_node_dots(true);
+    /// ```
+    ///
+    pub fn with_internal_node_dots(mut self, internal_node_dots: bool) -> Self {
+        self.internal_node_dots = internal_node_dots;
+        self
+    }
+
+    ///
+    /// Sets the colors of the drawing.
+    /// The default is [Theme::light].
+    ///
+    /// ```
+    /// use syntree_layout::{SvgDrawer, Theme};
+    ///
+    /// let drawer = SvgDrawer::new().with_theme(Theme::dark());
+    /// ```
+    ///
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    ///
+    /// Sets the width in pixels of the edges and of the nodes' outlines, the thick border of
+    /// [EmphasisStyle::ThickBorder] is twice as wide.
+    /// By default no width is set and the viewer's default of one pixel is used.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_stroke_width(0.5);
+    /// ```
+    ///
+    pub fn with_stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = Some(stroke_width);
+        self
+    }
+
+    ///
+    /// Sets the unit of the image's width and height, e.g. to place it at a predictable physical
+    /// size in print workflows. The size is converted from pixels at 96 pixels per inch, the
+    /// `viewBox` keeps the pixel coordinates, so the drawing scales to the physical size.
+    /// By default the size is given in pixels, see [Unit::Px].
+    ///
+    /// ```
+    /// use syntree_layout::{SvgDrawer, Unit};
+    ///
+    /// let drawer = SvgDrawer::new().with_units(Unit::Mm);
+    /// ```
+    ///
+    pub fn with_units(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    ///
+    /// Rounds the coordinates and sizes in the output to the given number of decimal places,
+    /// which keeps regenerated images small and their diffs quiet. Trailing zeros are omitted,
+    /// with a precision of `0` all coordinates are integers.
+    /// By default the coordinates are written with full precision.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_coordinate_precision(1);
+    /// ```
+    ///
+    pub fn with_coordinate_precision(mut self, decimal_places: u8) -> Self {
+        self.coordinate_precision = Some(decimal_places);
+        self
+    }
+
+    ///
+    /// Sets the color of the edges and their arrowheads, which overrides the edge color of the
+    /// theme, see [Theme::edge_color].
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_edge_color("#336699");
+    /// ```
+    ///
+    pub fn with_edge_color(mut self, edge_color: &str) -> Self {
+        self.theme.edge_color = edge_color.to_string();
+        self
+    }
+
+    ///
+    /// Sets the function that determines the space in pixels between the bottom of each layer
+    /// and the top of the next one, given the layer's depth. Each layer is then only as high as
+    /// its node with the most lines, so layers of short labels don't waste space for the
+    /// multi-line labels of other layers.
+    /// By default all layers are as high as the node with the most lines and are separated by
+    /// the embedding's layer spacing, see [Embedding::layer_spacing].
+    /// It only applies to [LayoutDirection::TopToBottom].
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// // More space below the root than between the deeper layers
+    /// let drawer = SvgDrawer::new()
+    ///     .with_layer_spacing_fn(|depth| if depth == 0 { 40.0 } else { 15.0 });
+    /// ```
+    ///
+    pub fn with_layer_spacing_fn(
+        mut self,
+        layer_spacing_fn: impl Fn(usize) -> f32 + 'static,
+    ) -> Self {
+        self.layer_spacing_fn = Some(LayerSpacing(Rc::new(layer_spacing_fn)));
+        self
+    }
+
+    ///
+    /// Sets the font family of the texts, the default is `Courier`. It's quoted as CSS string, so
+    /// a single family name is taken literally, quotes included.
+    ///
+    /// The space reserved for a text is derived from its number of characters, so that the
+    /// texts fit exactly only with a monospace font. With a proportional font use a unit width
+    /// of the font's average advance width, see [SvgDrawer::with_unit_width].
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_font_family("DejaVu Sans Mono");
+    /// ```
+    ///
+    pub fn with_font_family(mut self, font_family: &str) -> Self {
+        self.font_family = font_family.to_string();
+        self
+    }
+
+    ///
+    /// Embeds the given font file, e.g. a TrueType, OpenType or WOFF font, as `@font-face` of
+    /// the font family, so that the image looks the same on all viewers regardless of the
+    /// installed fonts. Set the font family with [SvgDrawer::with_font_family] before.
+    /// Note that the font is inlined base64 encoded, which increases the image size.
+    ///
+    /// ```no_run
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let font = std::fs::read("DejaVuSansMono.ttf").unwrap();
+    /// let drawer = SvgDrawer::new()
+    ///     .with_font_family("DejaVu Sans Mono")
+    ///     .with_embedded_font(&font);
+    /// ```
+    ///
+    pub fn with_embedded_font(mut self, font: &[u8]) -> Self {
+        self.embedded_font = Some(font.to_vec());
+        self
+    }
+
+    ///
+    /// Draws a faint grid of the logical coordinates behind the nodes and edges, which helps to
+    /// debug layouts. The lines along the sibling axis are drawn at every `spacing` units of
+    /// `x_center`, the lines along the layer axis at every `spacing` layers, i.e. through the
+    /// first baselines resp. the left borders of the layers.
+    /// Embeddings with [LayoutStyle::Radial][crate::LayoutStyle::Radial] get no grid.
+    /// By default no grid is drawn.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_grid(2).with_grid_labels(true);
+    /// ```
+    ///
+    pub fn with_grid(mut self, spacing: usize) -> Self {
+        self.grid = Some(spacing.max(1));
+        self
+    }
+
+    ///
+    /// Labels the lines of the grid with their logical coordinates along the top and left edge
+    /// of the image. It has only an effect together with [SvgDrawer::with_grid].
+    ///
+    pub fn with_grid_labels(mut self, grid_labels: bool) -> Self {
+        self.grid_labels = grid_labels;
+        self
+    }
+
+    ///
+    /// Draws a band behind each layer that spans the full width resp. height of the image, with
+    /// alternating faint fills, which makes the depth of the nodes visually explicit, e.g. for
+    /// teaching materials. Adjacent bands meet halfway between their layers.
+    /// Embeddings with [LayoutStyle::Radial][crate::LayoutStyle::Radial] get no bands.
+    /// By default no bands are drawn.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_layer_bands(true);
+    /// ```
+    ///
+    pub fn with_layer_bands(mut self, layer_bands: bool) -> Self {
+        self.layer_bands = layer_bands;
+        self
+    }
+
+    ///
+    /// Emits only a `<g>` group with the drawing instead of a standalone SVG document, i.e.
+    /// without the XML prolog and without the outer `<svg>` element and its namespace
+    /// declarations. The group can thus be inserted into an HTML page or a larger SVG document.
+    /// It still contains the background rectangle, which spans the image's resp. the canvas'
+    /// size, so the group's bounding box is the one of the standalone image.
+    /// The host document has to declare the `xlink` namespace if nodes are linked.
+    /// By default a standalone SVG document is emitted.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new().with_fragment_mode(true);
+    /// ```
+    ///
+    pub fn with_fragment_mode(mut self, fragment_mode: bool) -> Self {
+        self.fragment_mode = fragment_mode;
+        self
+    }
+
+    ///
+    /// Wraps the drawing in a `<symbol>` with the given id, which has the `viewBox` of the image
+    /// and can be referenced elsewhere with `<use href="#id"/>`, e.g. to show the same small
+    /// tree repeatedly. Combined with the fragment mode only the symbol is emitted, which
+    /// supports building SVG sprite sheets of trees. A standalone document additionally uses
+    /// the symbol, so it still shows the drawing.
+    /// The id is expected to be unique within the host document.
+    /// By default the drawing isn't wrapped.
+    ///
+    /// ```
+    /// use syntree_layout::SvgDrawer;
+    ///
+    /// let drawer = SvgDrawer::new()
+    ///     .with_fragment_mode(true)
+    ///     .with_symbol_id("expression-tree");
+    /// ```
+    ///
+    pub fn with_symbol_id(mut self, symbol_id: &str) -> Self {
+        self.symbol_id = Some(symbol_id.to_string());
+        self
+    }
+
+    ///
+    /// Draws on a canvas of the given width and height in pixels instead of cropping the image
+    /// tightly around the drawing. The image's size and `viewBox` are the canvas' size and the
+    /// drawing is placed on it according to [SvgDrawer::with_alignment]. A drawing that exceeds
+    /// the canvas is handled according to [SvgDrawer::with_canvas_overflow].
+    /// By default the image has the size of the drawing.
+    ///
+    /// ```
+    /// use syntree_layout::{Align, SvgDrawer};
+    ///
+    /// let drawer = SvgDrawer::new()
+    ///     .with_canvas_size(800.0, 600.0)
+    ///     .with_alignment(Align::TopLeft);
+    /// ```
+    ///
+    pub fn with_canvas_size(mut self, width: f32, height: f32) -> Self {
+        self.canvas_size = Some((width, height));
+        self
+    }
+
+    ///
+    /// Sets where the drawing is placed on the canvas set with [SvgDrawer::with_canvas_size].
+    /// The default is [Align::Center].
+    ///
+    pub fn with_alignment(mut self, alignment: Align) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    ///
+    /// Sets how a drawing that exceeds the canvas set with [SvgDrawer::with_canvas_size] is
+    /// handled. The default is [CanvasOverflow::ScaleToFit].
+    ///
+    pub fn with_canvas_overflow(mut self, canvas_overflow: CanvasOverflow) -> Self {
+        self.canvas_overflow = canvas_overflow;
+        self
+    }
+}
+
+impl SvgDrawer {
+    /// The theme the drawer draws with
+    pub(crate) fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// The offset and the scale factor that place the drawing of the given size on the canvas
+    fn placement(
+        &self,
+        (width, height): (f32, f32),
+        (canvas_width, canvas_height): (f32, f32),
+    ) -> (f32, f32, f32) {
+        let scale = match self.canvas_overflow {
+            CanvasOverflow::ScaleToFit => {
+                (canvas_width / width).min(canvas_height / height).min(1.0)
+            }
+            CanvasOverflow::Clip => 1.0,
+        };
+        let (fx, fy) = self.alignment.fractions();
+        (
+            (canvas_width - width * scale) * fx,
+            (canvas_height - height * scale) * fy,
+            scale,
+        )
+    }
+
+    ///
+    /// Draws several embeddings side by side into one standalone SVG document, e.g. to show a
+    /// gallery of trees. The embeddings are tiled into a grid with the given number of columns,
+    /// so `embeddings.len()` columns place them all in one row and one column stacks them.
+    /// Each embedding is drawn with the drawer's settings as if it was drawn alone, below its
+    /// caption and translated into its cell. The cells are separated by the margin.
+    /// The symbol id isn't applied to the cells, the ids of their grids and layer bands are
+    /// prefixed with `cell{i}-`. The arrowhead marker and the embedded font are defined once for
+    /// all cells.
+    ///
+    /// ```
+    /// use syntree_layout::{Layouter, SvgDrawer};
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// tree.open("root").unwrap();
+    /// tree.token("leaf", 1).unwrap();
+    /// tree.close().unwrap();
+    /// let tree = tree.build().unwrap();
+    ///
+    /// let first = Layouter::new(&tree).embed().unwrap();
+    /// let second = Layouter::new(&tree).with_inverted(true).embed().unwrap();
+    /// let mut buffer = Vec::new();
+    /// SvgDrawer::new()
+    ///     .draw_combined_to_writer(
+    ///         &mut buffer,
+    ///         &[("First", first.embedding()), ("Inverted", second.embedding())],
+    ///         2,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n), where n is the number of all nodes.
+    ///
+    pub fn draw_combined_to_writer(
+        &self,
+        writer: &mut dyn Write,
+        embeddings: &[(&str, &Embedding)],
+        columns: usize,
+    ) -> Result<()> {
+        let columns = columns.clamp(1, embeddings.len().max(1));
+        // The cells are drawn in place, the symbol id would be repeated in each of them
+        let mut cell_drawer = self.clone().with_fragment_mode(true);
+        cell_drawer.symbol_id = None;
+        cell_drawer.shared_defs = true;
+        let mut fragments = Vec::with_capacity(embeddings.len());
+        for (i, (_, embedding)) in embeddings.iter().enumerate() {
+            cell_drawer.id_prefix = format!("cell{}-", i);
+            let mut fragment = Vec::new();
+            cell_drawer.draw_to_writer(&mut fragment, embedding)?;
+            let fragment = String::from_utf8(fragment).map_err(|_| {
+                LayouterError::from_description("The svg output is not valid UTF-8")
+            })?;
+            let size = self
+                .canvas_size
+                .unwrap_or_else(|| Geometry::new(embedding, self).image_size(embedding));
+            fragments.push((fragment, size));
+        }
+
+        // The columns are as wide as their widest cell and the rows as high as their highest
+        // cell, which includes the caption's line
+        let caption_height = 1.5 * self.unit_height;
+        let gap = self.margin.unwrap_or(self.unit_width);
+        let mut column_widths = vec![0.0f32; columns];
+        let mut row_heights = vec![0.0f32; embeddings.len().div_ceil(columns)];
+        for (i, (_, (width, height))) in fragments.iter().enumerate() {
+            column_widths[i % columns] = column_widths[i % columns].max(*width);
+            row_heights[i / columns] = row_heights[i / columns].max(caption_height + height);
+        }
+        let offsets = |sizes: &[f32]| {
+            sizes
+                .iter()
+                .scan(0.0, |offset, size| {
+                    let cell_offset = *offset;
+                    *offset += size + gap;
+                    Some(cell_offset)
+                })
+                .collect::<Vec<_>>()
+        };
+        let (column_offsets, row_offsets) = (offsets(&column_widths), offsets(&row_heights));
+        let total =
+            |sizes: &[f32]| sizes.iter().sum::<f32>() + gap * sizes.len().saturating_sub(1) as f32;
+        let (img_width, img_height) = (total(&column_widths), total(&row_heights));
+
+        fn build_xml(
+            mut xml: XmlWriter<&mut dyn Write>,
+            drawer: &SvgDrawer,
+            embeddings: &[(&str, &Embedding)],
+            cells: Vec<Cell>,
+            (img_width, img_height): (f32, f32),
+            caption_height: f32,
+        ) -> std::io::Result<()> {
+            let n = |value| format_number(value, drawer.coordinate_precision);
+            xml.dtd("UTF-8")?;
+            xml.begin_elem("svg")?;
+            xml.attr("xmlns", "http://www.w3.org/2000/svg")?;
+            xml.attr("version", "1.1")?;
+            xml.attr("lang", "en")?;
+            if embeddings
+                .iter()
+                .any(|(_, embedding)| embedding.iter().any(|data| data.link.is_some()))
+            {
+                xml.attr("xmlns:xlink", "http://www.w3.org/1999/xlink")?;
+            }
+            let unit = drawer.unit;
+            let (width, height) = (unit.convert(img_width), unit.convert(img_height));
+            xml.attr("width", format!("{}{}", n(width), unit.suffix()).as_str())?;
+            xml.attr("height", format!("{}{}", n(height), unit.suffix()).as_str())?;
+            xml.attr(
+                "viewBox",
+                format!("0 0 {} {}", n(img_width), n(img_height)).as_str(),
+            )?;
+
+            let theme = &drawer.theme;
+            xml.begin_elem("rect")?;
+            xml.attr("x", "0")?;
+            xml.attr("y", "0")?;
+            xml.attr("width", n(img_width).as_str())?;
+            xml.attr("height", n(img_height).as_str())?;
+            xml.attr_esc("fill", &theme.background)?;
+            xml.end_elem()?;
+            write_defs(&mut xml, drawer)?;
+
+            let caption_font = format!(
+                "font-family: {}; font-weight: bold; font-style: normal",
+                css_string(&drawer.font_family)
+            );
+            for ((caption, _), cell) in embeddings.iter().zip(cells) {
+                let (width, (x, y)) = (cell.size.0, cell.position);
+                xml.begin_elem("text")?;
+                xml.attr("x", n(x + width / 2.0).as_str())?;
+                xml.attr("y", n(y + drawer.unit_height).as_str())?;
+                xml.attr("text-anchor", "middle")?;
+                xml.attr_esc("style", caption_font.as_str())?;
+                xml.attr_esc("fill", &theme.text_color)?;
+                xml.text(caption)?;
+                xml.end_elem()?;
+
+                xml.begin_elem("g")?;
+                xml.attr(
+                    "transform",
+                    format!("translate({} {})", n(x), n(y + caption_height)).as_str(),
+                )?;
+                // Writing the empty text completes the start tag before the raw fragment
+                xml.text("")?;
+                xml.write(&cell.fragment)?;
+                xml.end_elem()?;
+            }
+            xml.end_elem()?;
+            xml.close()?;
+            xml.flush()
+        }
+
+        let cells = fragments
+            .into_iter()
+            .enumerate()
+            .map(|(i, (fragment, size))| Cell {
+                fragment,
+                size,
+                position: (column_offsets[i % columns], row_offsets[i / columns]),
+            })
+            .collect();
+        Ok(build_xml(
+            XmlWriter::new(writer),
+            self,
+            embeddings,
+            cells,
+            (img_width, img_height),
+            caption_height,
+        )?)
     }
 }
 
@@ -43,15 +1028,18 @@ impl SvgDrawer {
 ///
 impl Drawer for SvgDrawer {
     ///
-    /// The concrete implementation of the `Drawer::draw` trait method.
-    /// The realization is as it is - with no way to configure for instance the font used.
-    /// This decision was mode for the sake of simplicity.
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    /// The realization is kept simple - apart from the font size and the scale there is no way
+    /// to configure for instance the font used.
     ///
     /// Anyway it should be easy to provide ones own Drawer implementation that fits the concrete
     /// use case better.
     /// When using the Layouter API you can set the Drawer instance by calling the `with_drawer`
     /// method.
     ///
+    /// An empty embedding, i.e. the embedding of an empty tree, results in a valid image that
+    /// consists of the margins and the height of one empty layer.
+    ///
     /// # Panics
     ///
     /// The method should not panic. If you encounter a panic this should be originated from
@@ -61,98 +1049,1177 @@ impl Drawer for SvgDrawer {
     ///
     /// The algorithm is of time complexity class O(n).
     ///
-    fn draw(&self, file_name: &Path, embedding: &[EmbeddedNode]) -> Result<()> {
-        let file = File::create(file_name).map_err(LayouterError::from_io_error)?;
-        let xml = XmlWriter::new(file);
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        let gradient = match &self.depth_gradient {
+            Some((from, to)) => Some(parse_hex_color(from).zip(parse_hex_color(to)).ok_or(
+                LayouterError::from_description(
+                    "The colors of the depth gradient must be hex colors like #ffcc00",
+                ),
+            )?),
+            None => None,
+        };
+        let xml = XmlWriter::new(writer);
 
-        fn build_xml(mut xml: XmlWriter<File>, embedding: &[EmbeddedNode]) -> std::io::Result<()> {
-            xml.dtd("UTF-8")?;
-            xml.begin_elem("svg")?;
-            xml.attr("xmlns", "http://www.w3.org/2000/svg")?;
-            xml.attr("version", "1.1")?;
-            xml.attr("lang", "en")?;
+        fn build_xml(
+            mut xml: XmlWriter<&mut dyn Write>,
+            embedding: &Embedding,
+            drawer: &SvgDrawer,
+            gradient: Option<([u8; 3], [u8; 3])>,
+        ) -> std::io::Result<()> {
+            let font_family = css_string(&drawer.font_family);
+            let string_font = format!("font-family: {}; font-style: normal", font_family);
+            let emphasize_font = format!(
+                "font-family: {}; font-weight: bold; font-style: normal",
+                font_family
+            );
 
-            const STRING_FONT: &str = "font-family: 'Courier'; font-style: normal";
-            const EMPHASIZE_FONT: &str =
-                "font-family: 'Courier'; font-weight: bold; font-style: normal";
+            let font_size = drawer
+                .font_size
+                .map(|size| format!("; font-size: {}px", size))
+                .unwrap_or_default();
+            let geometry = Geometry::new(embedding, drawer);
+            let (img_width, img_height) = geometry.image_size(embedding);
+            let (canvas_width, canvas_height) =
+                drawer.canvas_size.unwrap_or((img_width, img_height));
+            let view_box = format!(
+                "0 0 {} {}",
+                geometry.number(canvas_width),
+                geometry.number(canvas_height)
+            );
 
-            let tree_depth =
-                embedding
-                    .iter()
-                    .fold(0, |acc, e| if e.y_order > acc { e.y_order } else { acc });
-            let tree_width = embedding.iter().fold(0, |acc, e| {
-                if e.x_extent_children > acc {
-                    e.x_extent_children
-                } else {
-                    acc
+            let accessibility = |xml: &mut XmlWriter<&mut dyn Write>| -> std::io::Result<()> {
+                if drawer.accessibility {
+                    xml.attr("role", "img")?;
+                    xml.attr(
+                        "aria-label",
+                        format!("Tree with {} nodes", embedding.len()).as_str(),
+                    )?;
                 }
-            });
-
-            let img_width = SvgDrawer::scale_x(tree_width);
-            let img_height = SvgDrawer::scale_y(tree_depth + 1);
-
-            xml.attr("width", format!("{}", img_width).as_str())?;
-            xml.attr("height", format!("{}", img_height).as_str())?;
+                Ok(())
+            };
+            if !drawer.fragment_mode {
+                xml.dtd("UTF-8")?;
+                xml.begin_elem("svg")?;
+                xml.attr("xmlns", "http://www.w3.org/2000/svg")?;
+                xml.attr("version", "1.1")?;
+                xml.attr("lang", "en")?;
+                if embedding.iter().any(|data| data.link.is_some()) {
+                    xml.attr("xmlns:xlink", "http://www.w3.org/1999/xlink")?;
+                }
+                accessibility(&mut xml)?;
+                let unit = drawer.unit;
+                let (width, height) = (unit.convert(canvas_width), unit.convert(canvas_height));
+                let (n, suffix) = (|value| geometry.number(value), unit.suffix());
+                xml.attr("width", format!("{}{}", n(width), suffix).as_str())?;
+                xml.attr("height", format!("{}{}", n(height), suffix).as_str())?;
+                xml.attr("viewBox", view_box.as_str())?;
+            }
+            if let Some(id) = &drawer.symbol_id {
+                xml.begin_elem("symbol")?;
+                xml.attr_esc("id", id)?;
+                xml.attr("viewBox", view_box.as_str())?;
+            }
+            if drawer.fragment_mode || drawer.symbol_id.is_some() {
+                xml.begin_elem("g")?;
+                if drawer.fragment_mode {
+                    accessibility(&mut xml)?;
+                }
+            }
 
-            // Draw on a white rectangle to be visible also on black backgrounds.
+            // Draw on a filled rectangle to be visible also on backgrounds of other colors.
+            let theme = &drawer.theme;
+            let stroke_width = drawer.stroke_width.map(|width| format!("{}", width));
             xml.begin_elem("rect")?;
             xml.attr("x", "0")?;
             xml.attr("y", "0")?;
-            xml.attr("width", format!("{}", img_width).as_str())?;
-            xml.attr("height", format!("{}", img_height).as_str())?;
-            xml.attr("fill", "white")?;
+            xml.attr("width", geometry.number(canvas_width).as_str())?;
+            xml.attr("height", geometry.number(canvas_height).as_str())?;
+            xml.attr_esc("fill", &theme.background)?;
             xml.end_elem()?;
 
-            for data in embedding {
-                let font = if data.is_emphasized {
-                    EMPHASIZE_FONT
-                } else {
-                    STRING_FONT
+            // The drawing is moved to its place on the canvas
+            if drawer.canvas_size.is_some() {
+                let (dx, dy, scale) =
+                    drawer.placement((img_width, img_height), (canvas_width, canvas_height));
+                xml.begin_elem("g")?;
+                xml.attr(
+                    "transform",
+                    format!(
+                        "translate({} {}) scale({})",
+                        geometry.number(dx),
+                        geometry.number(dy),
+                        scale
+                    )
+                    .as_str(),
+                )?;
+            }
+
+            // The bands and the grid are drawn first to stay behind the nodes and edges
+            if drawer.layer_bands && geometry.radial.is_none() {
+                let size = match geometry.direction {
+                    LayoutDirection::TopToBottom => img_height,
+                    LayoutDirection::LeftToRight => img_width,
                 };
-                let szx = SvgDrawer::measure_string(&data.text);
-                let x = SvgDrawer::scale_x(data.x_center) - szx / 2.0;
-                let y = SvgDrawer::scale_y(data.y_order);
-                xml.begin_elem("text")?;
-                xml.attr("x", format!("{}", x).as_str())?;
-                xml.attr("y", format!("{}", y).as_str())?;
-                xml.attr("style", font)?;
-                xml.text(data.text.as_str())?;
+                xml.begin_elem("g")?;
+                xml.attr_esc("id", format!("{}layer-bands", drawer.id_prefix).as_str())?;
+                xml.attr_esc("fill", &theme.node_stroke)?;
+                for (layer, (start, end)) in geometry.layer_bands(size).into_iter().enumerate() {
+                    let (x, y, width, height) = match geometry.direction {
+                        LayoutDirection::TopToBottom => (0.0, start, img_width, end - start),
+                        LayoutDirection::LeftToRight => (start, 0.0, end - start, img_height),
+                    };
+                    xml.begin_elem("rect")?;
+                    xml.attr("x", geometry.number(x).as_str())?;
+                    xml.attr("y", geometry.number(y).as_str())?;
+                    xml.attr("width", geometry.number(width).as_str())?;
+                    xml.attr("height", geometry.number(height).as_str())?;
+                    xml.attr(
+                        "fill-opacity",
+                        format!("{}", LAYER_BAND_OPACITIES[layer % 2]).as_str(),
+                    )?;
+                    xml.end_elem()?;
+                }
                 xml.end_elem()?;
+            }
+            if let Some(spacing) = drawer.grid.filter(|_| geometry.radial.is_none()) {
+                let (tree_width, layer_count) = embedding.dimensions();
+                xml.begin_elem("g")?;
+                xml.attr_esc("id", format!("{}grid", drawer.id_prefix).as_str())?;
+                xml.attr_esc("stroke", &theme.node_stroke)?;
+                xml.attr("stroke-opacity", format!("{}", GRID_OPACITY).as_str())?;
+                let sibling_lines = (0..=tree_width).step_by(spacing).map(|x| (x, false));
+                let layer_lines = (0..layer_count).step_by(spacing).map(|y| (y, true));
+                for (value, is_layer) in sibling_lines.chain(layer_lines) {
+                    let vertical = is_layer == (geometry.direction == LayoutDirection::LeftToRight);
+                    let position = match (geometry.direction, is_layer) {
+                        (LayoutDirection::TopToBottom, false) => geometry.scale_x(value),
+                        (LayoutDirection::TopToBottom, true) => geometry.scale_layer(value),
+                        (LayoutDirection::LeftToRight, false) => geometry.scale_sibling(value),
+                        (LayoutDirection::LeftToRight, true) => geometry.layer_offsets[value],
+                    };
+                    let (x1, y1, x2, y2) = if vertical {
+                        (position, 0.0, position, img_height)
+                    } else {
+                        (0.0, position, img_width, position)
+                    };
+                    xml.begin_elem("line")?;
+                    xml.attr("x1", geometry.number(x1).as_str())?;
+                    xml.attr("y1", geometry.number(y1).as_str())?;
+                    xml.attr("x2", geometry.number(x2).as_str())?;
+                    xml.attr("y2", geometry.number(y2).as_str())?;
+                    xml.end_elem()?;
+                    if drawer.grid_labels {
+                        // Along the top edge resp. the left edge of the image
+                        let (x, y, anchor) = if vertical {
+                            (position, geometry.unit_height * 0.6, "middle")
+                        } else {
+                            (0.0, position, "start")
+                        };
+                        xml.begin_elem("text")?;
+                        xml.attr("x", geometry.number(x).as_str())?;
+                        xml.attr("y", geometry.number(y).as_str())?;
+                        xml.attr("text-anchor", anchor)?;
+                        xml.attr_esc(
+                            "style",
+                            format!(
+                                "{}; font-size: {}px",
+                                string_font,
+                                geometry.unit_height * 0.6
+                            )
+                            .as_str(),
+                        )?;
+                        xml.attr_esc("fill", &theme.text_color)?;
+                        xml.attr("stroke", "none")?;
+                        xml.text(&value.to_string())?;
+                        xml.end_elem()?;
+                    }
+                }
+                xml.end_elem()?;
+            }
 
-                if let Some(parent_index) = data.parent {
-                    let parent_data = embedding.iter().find(|e| e.ord == parent_index).unwrap();
+            if !drawer.shared_defs {
+                write_defs(&mut xml, drawer)?;
+            }
 
-                    // Draw a line from the nodes parent down to this node
-                    xml.begin_elem("line")?;
-                    xml.attr(
-                        "x1",
-                        format!("{}", (SvgDrawer::scale_x(parent_data.x_center))).as_str(),
-                    )?;
-                    xml.attr(
-                        "y1",
-                        format!(
-                            "{}",
-                            (SvgDrawer::scale_y(parent_data.y_order) + FONT_Y_SIZE)
-                        )
-                        .as_str(),
+            // The edges are drawn beneath the nodes, each in a group of their own for styling
+            xml.begin_elem("g")?;
+            xml.attr("class", "edges")?;
+            // Draw a line from each node's parent to the node
+            for (parent_data, data) in embedding.edges() {
+                // The edge belongs to the child, so it's dimmed with it
+                if let Some(opacity) = data.opacity {
+                    xml.begin_elem("g")?;
+                    xml.attr("opacity", format!("{}", opacity).as_str())?;
+                }
+                let ((x1, y1), (x2, y2)) = geometry.edge(parent_data, data);
+                let edge_style = match embedding.layout_style() {
+                    LayoutStyle::Layered => drawer.edge_style,
+                    // The edges are radial segments
+                    LayoutStyle::Radial => EdgeStyle::Straight,
+                };
+                let n = |value| geometry.number(value);
+                match edge_style {
+                    EdgeStyle::Straight => {
+                        xml.begin_elem("line")?;
+                        xml.attr("x1", geometry.number(x1).as_str())?;
+                        xml.attr("y1", geometry.number(y1).as_str())?;
+                        xml.attr("x2", geometry.number(x2).as_str())?;
+                        xml.attr("y2", geometry.number(y2).as_str())?;
+                    }
+                    EdgeStyle::Orthogonal => {
+                        let d = match geometry.direction {
+                            LayoutDirection::TopToBottom => format!(
+                                "M {} {} V {} H {} V {}",
+                                n(x1),
+                                n(y1),
+                                n(geometry.bend(parent_data, data, y1, y2)),
+                                n(x2),
+                                n(y2)
+                            ),
+                            LayoutDirection::LeftToRight => format!(
+                                "M {} {} H {} V {} H {}",
+                                n(x1),
+                                n(y1),
+                                n(geometry.bend(parent_data, data, x1, x2)),
+                                n(y2),
+                                n(x2)
+                            ),
+                        };
+                        xml.begin_elem("path")?;
+                        xml.attr("d", d.as_str())?;
+                        xml.attr("fill", "none")?;
+                    }
+                    EdgeStyle::Curved => {
+                        // The control points are offset towards the other layer by half
+                        // the gap between both layers
+                        let d = match geometry.direction {
+                            LayoutDirection::TopToBottom => {
+                                let dy = (y2 - y1) / 2.0;
+                                format!(
+                                    "M {} {} C {} {} {} {} {} {}",
+                                    n(x1),
+                                    n(y1),
+                                    n(x1),
+                                    n(y1 + dy),
+                                    n(x2),
+                                    n(y2 - dy),
+                                    n(x2),
+                                    n(y2)
+                                )
+                            }
+                            LayoutDirection::LeftToRight => {
+                                let dx = (x2 - x1) / 2.0;
+                                format!(
+                                    "M {} {} C {} {} {} {} {} {}",
+                                    n(x1),
+                                    n(y1),
+                                    n(x1 + dx),
+                                    n(y1),
+                                    n(x2 - dx),
+                                    n(y2),
+                                    n(x2),
+                                    n(y2)
+                                )
+                            }
+                        };
+                        xml.begin_elem("path")?;
+                        xml.attr("d", d.as_str())?;
+                        xml.attr("fill", "none")?;
+                    }
+                }
+                xml.attr_esc("stroke", &theme.edge_color)?;
+                if let Some(width) = &stroke_width {
+                    xml.attr("stroke-width", width)?;
+                }
+                if drawer.arrowheads {
+                    xml.attr("marker-end", format!("url(#{})", ARROWHEAD_ID).as_str())?;
+                }
+                xml.end_elem()?;
+
+                if let Some(label) = &data.edge_label {
+                    // Centered on the edge, the background keeps the label readable
+                    let (x, y) =
+                        geometry.edge_midpoint(parent_data, data, edge_style, (x1, y1), (x2, y2));
+                    xml.begin_elem("text")?;
+                    xml.attr("x", n(x).as_str())?;
+                    xml.attr("y", n(y).as_str())?;
+                    xml.attr("text-anchor", "middle")?;
+                    xml.attr("dominant-baseline", "central")?;
+                    xml.attr_esc("style", format!("{}{}", string_font, font_size).as_str())?;
+                    xml.attr_esc("fill", &theme.text_color)?;
+                    xml.attr_esc("stroke", &theme.background)?;
+                    xml.attr("stroke-width", "3")?;
+                    xml.attr("paint-order", "stroke")?;
+                    xml.text(label)?;
+                    xml.end_elem()?;
+                }
+                if data.opacity.is_some() {
+                    xml.end_elem()?;
+                }
+            }
+            xml.end_elem()?;
+
+            xml.begin_elem("g")?;
+            xml.attr("class", "nodes")?;
+            for data in embedding {
+                let (font, color) = if data.is_emphasized {
+                    (&emphasize_font, &theme.emphasis_color)
+                } else {
+                    (&string_font, &theme.text_color)
+                };
+                let (x, y) = geometry.text_position(data);
+                if let Some(opacity) = data.opacity {
+                    xml.begin_elem("g")?;
+                    xml.attr("opacity", format!("{}", opacity).as_str())?;
+                }
+                if drawer.accessibility {
+                    // Screen readers read the complete label as one line
+                    let label = data.full_text.as_ref().unwrap_or(&data.text);
+                    xml.begin_elem("g")?;
+                    xml.attr_esc("aria-label", &label.lines().collect::<Vec<_>>().join(" "))?;
+                }
+                // The node's shape and text become the clickable content of the link
+                if let Some(link) = &data.link {
+                    xml.begin_elem("a")?;
+                    xml.attr_esc("xlink:href", link)?;
+                }
+                let border = if data.is_emphasized {
+                    drawer.emphasis_style
+                } else {
+                    EmphasisStyle::Bold
+                };
+                let shape = data.shape.or(drawer.node_shape);
+                let depth_fill = gradient.filter(|_| !data.is_emphasized).map(|(from, to)| {
+                    let t = data.y_order as f32 / embedding.height().max(1) as f32;
+                    interpolate_color(from, to, t)
+                });
+                let fill = data
+                    .background_color
+                    .as_ref()
+                    .or(depth_fill.as_ref())
+                    .or(theme.node_fill.as_ref());
+                let (is_dot, leaf_box) = match drawer.internal_node_dots {
+                    true => (data.child_count > 0, data.child_count == 0),
+                    false => (false, false),
+                };
+                // The label of a dot starts right of it
+                let mut label_x = None;
+                if is_dot {
+                    let (box_x, box_y, box_width, box_height) = geometry.text_box(data);
+                    let (cx, cy) = (box_x + box_width / 2.0, box_y + box_height / 2.0);
+                    let radius = geometry.unit_height / 4.0;
+                    xml.begin_elem("circle")?;
+                    xml.attr("cx", geometry.number(cx).as_str())?;
+                    xml.attr("cy", geometry.number(cy).as_str())?;
+                    xml.attr("r", geometry.number(radius).as_str())?;
+                    let dot_color = match data.is_emphasized {
+                        true => &theme.emphasis_color,
+                        false => &theme.node_stroke,
+                    };
+                    xml.attr_esc("fill", fill.unwrap_or(dot_color))?;
+                    xml.attr_esc("stroke", dot_color)?;
+                    if let Some(width) = &stroke_width {
+                        xml.attr("stroke-width", width)?;
+                    }
+                    xml.end_elem()?;
+                    label_x = Some(cx + radius + geometry.unit_width / 2.0);
+                } else if leaf_box
+                    || shape.is_some()
+                    || fill.is_some()
+                    || border != EmphasisStyle::Bold
+                {
+                    begin_shape(
+                        &mut xml,
+                        &geometry,
+                        shape.unwrap_or_default(),
+                        geometry.text_box(data),
                     )?;
-                    xml.attr(
-                        "x2",
-                        format!("{}", (SvgDrawer::scale_x(data.x_center))).as_str(),
+                    xml.attr_esc("fill", fill.map_or("none", String::as_str))?;
+                    match border {
+                        EmphasisStyle::Bold if shape.is_some() || leaf_box => {
+                            xml.attr_esc("stroke", &theme.node_stroke)?;
+                            if let Some(width) = &stroke_width {
+                                xml.attr("stroke-width", width)?;
+                            }
+                        }
+                        EmphasisStyle::Bold => (),
+                        EmphasisStyle::DashedBorder => {
+                            xml.attr_esc("stroke", &theme.emphasis_color)?;
+                            xml.attr("stroke-dasharray", "4 2")?;
+                            if let Some(width) = &stroke_width {
+                                xml.attr("stroke-width", width)?;
+                            }
+                        }
+                        EmphasisStyle::ThickBorder => {
+                            let width = drawer.stroke_width.unwrap_or(1.0) * 2.0;
+                            xml.attr_esc("stroke", &theme.emphasis_color)?;
+                            xml.attr("stroke-width", format!("{}", width).as_str())?;
+                        }
+                    }
+                    xml.end_elem()?;
+                }
+                xml.begin_elem("text")?;
+                xml.attr("x", geometry.number(label_x.unwrap_or(x)).as_str())?;
+                xml.attr("y", geometry.number(y).as_str())?;
+                xml.attr_esc("style", format!("{}{}", font, font_size).as_str())?;
+                xml.attr_esc("fill", color)?;
+                // Shown when hovering the node, the complete text of a truncated label serves
+                // as fallback
+                if let Some(title) = data.tooltip.as_ref().or(data.full_text.as_ref()) {
+                    xml.begin_elem("title")?;
+                    xml.text(title)?;
+                    xml.end_elem()?;
+                }
+                if data.line_count > 1 {
+                    // Each line starts at its own position below the previous one
+                    for (i, line) in data.text.lines().enumerate() {
+                        xml.begin_elem("tspan")?;
+                        let line_x = label_x.unwrap_or_else(|| geometry.line_x(data, i, line));
+                        xml.attr("x", geometry.number(line_x).as_str())?;
+                        if i > 0 {
+                            xml.attr("dy", geometry.number(geometry.unit_height).as_str())?;
+                        }
+                        xml.text(line)?;
+                        xml.end_elem()?;
+                    }
+                } else {
+                    xml.text(data.text.as_str())?;
+                }
+                xml.end_elem()?;
+                if let Some(identifier) = drawer.node_annotations.identifier(data) {
+                    // Superscript-like within the box's top-right corner
+                    let (box_x, box_y, box_width, _) = geometry.text_box(data);
+                    xml.begin_elem("text")?;
+                    xml.attr("class", "annotation")?;
+                    xml.attr("x", geometry.number(box_x + box_width).as_str())?;
+                    xml.attr("y", geometry.number(box_y).as_str())?;
+                    xml.attr("text-anchor", "end")?;
+                    xml.attr("dominant-baseline", "hanging")?;
+                    xml.attr_esc(
+                        "style",
+                        format!("{}; font-size: 0.6em", string_font).as_str(),
                     )?;
-                    xml.attr("y2", format!("{}", (y - FONT_Y_SIZE)).as_str())?;
-                    xml.attr("stroke", "black")?;
+                    xml.attr_esc("fill", &theme.text_color)?;
+                    if drawer.accessibility {
+                        xml.attr("aria-hidden", "true")?;
+                    }
+                    xml.text(&identifier.to_string())?;
+                    xml.end_elem()?;
+                }
+                if data.link.is_some() {
+                    xml.end_elem()?;
+                }
+                if drawer.accessibility {
+                    xml.end_elem()?;
+                }
+                if data.opacity.is_some() {
                     xml.end_elem()?;
                 }
             }
+            xml.end_elem()?;
 
+            if drawer.canvas_size.is_some() {
+                xml.end_elem()?;
+            }
             xml.end_elem()?;
+            if let Some(id) = &drawer.symbol_id {
+                // The symbol itself isn't rendered, a standalone document shows it with a use
+                xml.end_elem()?;
+                if !drawer.fragment_mode {
+                    xml.begin_elem("use")?;
+                    xml.attr_esc("href", format!("#{}", id).as_str())?;
+                    xml.end_elem()?;
+                    xml.end_elem()?;
+                }
+            }
             xml.close()?;
             xml.flush()?;
 
             Ok(())
         }
 
-        build_xml(xml, embedding).map_err(LayouterError::from_io_error)
+        Ok(build_xml(xml, embedding, self, gradient)?)
+    }
+}
+
+/// Formats the given coordinate or size with the given number of decimal places, trailing zeros
+/// are omitted
+fn format_number(value: f32, precision: Option<u8>) -> String {
+    match precision {
+        None => format!("{}", value),
+        Some(precision) => {
+            let rounded = format!("{:.*}", precision as usize, value);
+            let trimmed = if rounded.contains('.') {
+                rounded.trim_end_matches('0').trim_end_matches('.')
+            } else {
+                &rounded
+            };
+            // Rounding tiny negative values mustn't result in a negative zero
+            match trimmed {
+                "-0" => "0".to_string(),
+                _ => trimmed.to_string(),
+            }
+        }
+    }
+}
+
+/// The red, green and blue components of a hex color like `#ffcc00` or `#fc0`
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let digits = color.strip_prefix('#')?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let component = |i: usize, len: usize| {
+        let value = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16).ok()?;
+        // A single digit is repeated, i.e. `f` is `ff`
+        Some(if len == 1 { value * 17 } else { value })
+    };
+    let len = match digits.len() {
+        3 => 1,
+        6 => 2,
+        _ => return None,
+    };
+    Some([component(0, len)?, component(1, len)?, component(2, len)?])
+}
+
+/// The hex color at the fraction `t` of the way from the first to the second color
+fn interpolate_color(from: [u8; 3], to: [u8; 3], t: f32) -> String {
+    let [r, g, b] =
+        [0, 1, 2].map(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Quotes the given text as CSS string. Quotes, backslashes and line breaks are escaped, and so is
+/// `>`, so that the string can't end a CDATA section.
+fn css_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('\'');
+    for c in text.chars() {
+        match c {
+            '\'' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' | '\r' | '>' => quoted.push_str(&format!("\\{:x} ", c as u32)),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Writes the definitions of the embedded font and the arrowhead marker, if they are enabled
+fn write_defs(xml: &mut XmlWriter<&mut dyn Write>, drawer: &SvgDrawer) -> std::io::Result<()> {
+    if let Some(font) = &drawer.embedded_font {
+        xml.begin_elem("defs")?;
+        xml.begin_elem("style")?;
+        // The quotes of the font family mustn't be escaped, the CSS string can't end the CDATA
+        // section
+        xml.cdata(
+            format!(
+                "@font-face {{ font-family: {}; src: url(data:{};base64,{}); }}",
+                css_string(&drawer.font_family),
+                font_mime_type(font),
+                base64(font)
+            )
+            .as_str(),
+        )?;
+        xml.end_elem()?;
+        xml.end_elem()?;
+    }
+
+    if drawer.arrowheads {
+        // The tip of the arrowhead is placed at the end of the edge
+        let size = format_number(drawer.unit_height * 0.8, drawer.coordinate_precision);
+        xml.begin_elem("defs")?;
+        xml.begin_elem("marker")?;
+        xml.attr("id", ARROWHEAD_ID)?;
+        xml.attr("viewBox", "0 0 10 10")?;
+        xml.attr("refX", "10")?;
+        xml.attr("refY", "5")?;
+        xml.attr("markerUnits", "userSpaceOnUse")?;
+        xml.attr("markerWidth", size.as_str())?;
+        xml.attr("markerHeight", size.as_str())?;
+        xml.attr("orient", "auto")?;
+        xml.begin_elem("path")?;
+        xml.attr("d", "M 0 0 L 10 5 L 0 10 z")?;
+        xml.attr_esc("fill", &drawer.theme.edge_color)?;
+        xml.end_elem()?;
+        xml.end_elem()?;
+        xml.end_elem()?;
+    }
+    Ok(())
+}
+
+/// The MIME type of the given font file, derived from its signature
+fn font_mime_type(font: &[u8]) -> &'static str {
+    match font.get(..4) {
+        Some(b"wOF2") => "font/woff2",
+        Some(b"wOFF") => "font/woff",
+        Some(b"OTTO") => "font/otf",
+        _ => "font/ttf",
+    }
+}
+
+/// Encodes the given bytes with the standard base64 alphabet and padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i)) & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Begins the element of the given shape that spans the given box as x, y, width and height.
+/// The caller adds the style attributes and ends the element.
+fn begin_shape(
+    xml: &mut XmlWriter<&mut dyn Write>,
+    geometry: &Geometry,
+    shape: NodeShape,
+    (x, y, width, height): (f32, f32, f32, f32),
+) -> std::io::Result<()> {
+    match shape {
+        NodeShape::Rect | NodeShape::RoundedRect { .. } => {
+            xml.begin_elem("rect")?;
+            xml.attr("x", geometry.number(x).as_str())?;
+            xml.attr("y", geometry.number(y).as_str())?;
+            xml.attr("width", geometry.number(width).as_str())?;
+            xml.attr("height", geometry.number(height).as_str())?;
+            if let NodeShape::RoundedRect { radius } = shape {
+                xml.attr("rx", geometry.number(radius).as_str())?;
+            }
+        }
+        NodeShape::Ellipse => {
+            // The ellipse with the box's aspect ratio that passes through the box's corners
+            xml.begin_elem("ellipse")?;
+            xml.attr("cx", geometry.number(x + width / 2.0).as_str())?;
+            xml.attr("cy", geometry.number(y + height / 2.0).as_str())?;
+            xml.attr(
+                "rx",
+                geometry.number(width / std::f32::consts::SQRT_2).as_str(),
+            )?;
+            xml.attr(
+                "ry",
+                geometry.number(height / std::f32::consts::SQRT_2).as_str(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+///
+/// A cell of the grid drawn by [SvgDrawer::draw_combined_to_writer].
+///
+struct Cell {
+    /// The drawing of the cell's embedding in fragment mode
+    fragment: String,
+    /// The width and height of the drawing
+    size: (f32, f32),
+    /// The upper left corner of the cell
+    position: (f32, f32),
+}
+
+///
+/// Maps the logical coordinates of an embedding to svg coordinates, depending on the embedding's
+/// [LayoutDirection].
+///
+struct Geometry {
+    direction: LayoutDirection,
+    /// The y coordinates of the layers' first baselines for [LayoutDirection::TopToBottom] resp.
+    /// the x coordinates of the layers' left borders for [LayoutDirection::LeftToRight]
+    layer_offsets: Vec<f32>,
+    /// The width of one column in pixels
+    unit_width: f32,
+    /// The height of one text line in pixels
+    unit_height: f32,
+    /// The free space around the figure in pixels
+    margin: f32,
+    /// The number of text lines of each layer, at least one for an empty embedding
+    layer_line_counts: Vec<usize>,
+    /// The drawer's shape of the nodes, which determines where edges meet the nodes' outlines
+    node_shape: Option<NodeShape>,
+    /// The rings of a [LayoutStyle::Radial] embedding
+    radial: Option<Rings>,
+    /// The number of decimal places of the output's numbers
+    precision: Option<u8>,
+    /// The minimum width of the nodes' boxes without the padding around the text
+    min_box_width: f32,
+    /// The padding on both sides of the nodes' texts in pixels
+    padding: f32,
+    /// The space between two adjacent layers of [LayoutDirection::LeftToRight] in pixels
+    layer_gap: f32,
+}
+
+///
+/// The concentric rings of the layers of a [LayoutStyle::Radial] embedding.
+///
+struct Rings {
+    /// The center of the image, where the root is placed
+    center: (f32, f32),
+    /// The distance between two adjacent rings in pixels
+    distance: f32,
+    /// The ring of the first layer, the roots of a forest are placed on the first ring around
+    /// the center instead of on top of each other
+    first: usize,
+    /// The logical width of the embedding, which spans the full circle
+    width: f32,
+}
+
+impl Geometry {
+    fn new(embedding: &Embedding, drawer: &SvgDrawer) -> Self {
+        let direction = embedding.direction();
+        let unit_width = drawer.unit_width;
+        let unit_height = drawer.unit_height;
+        // The layer spacing is given in text lines resp. columns
+        let layer_spacing = embedding.layer_spacing() as f32;
+        // Each layer is as high as its node with the most lines
+        let mut layer_line_counts = embedding.layer_heights();
+        if layer_line_counts.is_empty() {
+            layer_line_counts.push(1);
+        }
+        let margin = drawer.margin.unwrap_or(unit_width);
+        let layer_gap = (layer_spacing + 1.0) * unit_width;
+        let layer_offsets = match direction {
+            LayoutDirection::TopToBottom => {
+                // See `Geometry::y_margin`
+                let first_baseline = margin + 1.5 * unit_height;
+                if let Some(LayerSpacing(layer_spacing_fn)) = &drawer.layer_spacing_fn {
+                    // The text spans from one line above its first baseline to half a line below
+                    // its last baseline
+                    layer_line_counts
+                        .iter()
+                        .enumerate()
+                        .scan(first_baseline, |baseline, (layer, line_count)| {
+                            let layer_baseline = *baseline;
+                            *baseline +=
+                                (*line_count as f32 + 0.5) * unit_height + layer_spacing_fn(layer);
+                            Some(layer_baseline)
+                        })
+                        .collect()
+                } else {
+                    layer_line_counts
+                        .iter()
+                        .scan(first_baseline, |baseline, line_count| {
+                            let layer_baseline = *baseline;
+                            *baseline += (layer_spacing + *line_count as f32) * unit_height;
+                            Some(layer_baseline)
+                        })
+                        .collect()
+                }
+            }
+            LayoutDirection::LeftToRight => {
+                // Each layer is as wide as its widest text
+                let mut layer_widths = Vec::new();
+                for e in embedding {
+                    if layer_widths.len() <= e.y_order {
+                        layer_widths.resize(e.y_order + 1, 0.0);
+                    }
+                    let width = (e.text_width() as f32 * unit_width)
+                        .max(embedding.min_node_width() as f32 * unit_width)
+                        + 2.0 * embedding.horizontal_padding() as f32 * unit_width;
+                    if width > layer_widths[e.y_order] {
+                        layer_widths[e.y_order] = width;
+                    }
+                }
+                layer_widths
+                    .iter()
+                    .scan(margin, |offset, width| {
+                        let layer_offset = *offset;
+                        *offset += width + layer_gap;
+                        Some(layer_offset)
+                    })
+                    .collect()
+            }
+        };
+        Self {
+            direction,
+            layer_offsets,
+            unit_width,
+            unit_height,
+            margin,
+            radial: Self::rings(embedding, unit_width, unit_height, margin),
+            precision: drawer.coordinate_precision,
+            min_box_width: embedding.min_node_width() as f32 * unit_width,
+            padding: embedding.horizontal_padding() as f32 * unit_width,
+            layer_gap,
+            layer_line_counts,
+            node_shape: drawer.node_shape,
+        }
+    }
+
+    /// The rings of a [LayoutStyle::Radial] embedding.
+    /// The rings are as far apart as the layers of a layered embedding, but at least as far as
+    /// necessary for the first ring to provide a column per logical unit.
+    fn rings(
+        embedding: &Embedding,
+        unit_width: f32,
+        unit_height: f32,
+        margin: f32,
+    ) -> Option<Rings> {
+        if embedding.layout_style() != LayoutStyle::Radial {
+            return None;
+        }
+        let (_, layer_count) = embedding.dimensions();
+        let max_line_count = embedding.layer_heights().into_iter().max().unwrap_or(1);
+        // The roots' subtrees span the slots of all leaves
+        let width = embedding
+            .iter()
+            .filter(|e| e.parent.is_none())
+            .map(|e| e.x_center + e.x_extent_children - e.x_extent_children / 2)
+            .max()
+            .unwrap_or_default()
+            .max(1) as f32;
+        let distance = ((embedding.layer_spacing() + max_line_count) as f32 * unit_height)
+            .max(width * unit_width / std::f32::consts::TAU);
+        let first = usize::from(embedding.iter().filter(|e| e.parent.is_none()).count() > 1);
+        let radius = (layer_count.max(1) - 1 + first) as f32 * distance;
+        // The texts on the outermost ring extend beyond it by half their box
+        let (half_width, half_height) = embedding.iter().fold((0.0f32, 0.0f32), |(w, h), e| {
+            (
+                w.max((e.text_width() as f32 * unit_width + unit_width) / 2.0),
+                h.max(unit_height * (0.75 + e.line_count.saturating_sub(1) as f32 / 2.0)),
+            )
+        });
+        Some(Rings {
+            center: (margin + radius + half_width, margin + radius + half_height),
+            distance,
+            first,
+            width,
+        })
+    }
+
+    /// The center of the node's text on the rings of a [LayoutStyle::Radial] embedding.
+    /// The angle starts at the top and grows clockwise with the logical x coordinate.
+    fn radial_center(&self, data: &EmbeddedNode) -> Option<(f32, f32)> {
+        self.radial.as_ref().map(|rings| {
+            let radius = (data.y_order + rings.first) as f32 * rings.distance;
+            let angle = std::f32::consts::TAU * data.x_center as f32 / rings.width
+                - std::f32::consts::FRAC_PI_2;
+            (
+                rings.center.0 + radius * angle.cos(),
+                rings.center.1 + radius * angle.sin(),
+            )
+        })
+    }
+
+    /// Formats the given coordinate or size with the configured precision
+    fn number(&self, value: f32) -> String {
+        format_number(value, self.precision)
+    }
+
+    /// The offset of the logical x coordinate 0
+    fn x_margin(&self) -> f32 {
+        self.margin
+    }
+
+    /// The offset of the first layer's baseline resp. of the logical sibling coordinate 0, which
+    /// leaves room for the first text line above its baseline
+    fn y_margin(&self) -> f32 {
+        self.margin + 1.5 * self.unit_height
+    }
+
+    /// Scales the logical x coordinate of [LayoutDirection::TopToBottom]
+    fn scale_x(&self, x: usize) -> f32 {
+        x as f32 * self.unit_width + self.x_margin()
+    }
+
+    /// The width of the widest line of the node's text
+    fn text_width(&self, data: &EmbeddedNode) -> f32 {
+        data.text_width() as f32 * self.unit_width
+    }
+
+    /// The width of the node's box without the half column around the text, which is at least
+    /// the minimum node width and includes the horizontal padding on both sides
+    fn box_width(&self, data: &EmbeddedNode) -> f32 {
+        self.text_width(data).max(self.min_box_width) + 2.0 * self.padding
+    }
+
+    /// The width and height of the whole image
+    fn image_size(&self, embedding: &Embedding) -> (f32, f32) {
+        if let Some(rings) = &self.radial {
+            // The rings are centered
+            return (2.0 * rings.center.0, 2.0 * rings.center.1);
+        }
+        let (tree_width, layer_count) = embedding.dimensions();
+        match self.direction {
+            LayoutDirection::TopToBottom => {
+                // The text of the last layer extends half a line below its last baseline.
+                // An empty embedding still gets the height of one layer.
+                let last_layer = layer_count.max(1) - 1;
+                let text_bottom =
+                    (self.layer_line_counts[last_layer] as f32 - 0.5) * self.unit_height;
+                (
+                    self.scale_x(tree_width) + self.margin,
+                    self.scale_layer(last_layer) + text_bottom + self.margin,
+                )
+            }
+            LayoutDirection::LeftToRight => {
+                let width = embedding.iter().fold(0.0, |acc, e| {
+                    let right = self.layer_offsets[e.y_order] + self.box_width(e);
+                    if right > acc {
+                        right
+                    } else {
+                        acc
+                    }
+                });
+                (
+                    width + self.unit_width / 2.0 + self.margin,
+                    self.scale_sibling(tree_width) + self.margin,
+                )
+            }
+        }
+    }
+
+    /// The position of the left end of the baseline of the text's first line
+    fn text_position(&self, data: &EmbeddedNode) -> (f32, f32) {
+        if let Some((x, y)) = self.radial_center(data) {
+            // The text spans from one line above its first baseline to half a line below its
+            // last baseline
+            return (
+                x - self.text_width(data) / 2.0,
+                y + self.unit_height / 4.0 - self.extra_height(data) / 2.0,
+            );
+        }
+        match self.direction {
+            LayoutDirection::TopToBottom => {
+                let szx = self.text_width(data);
+                (
+                    self.scale_x(data.x_center) - szx / 2.0,
+                    self.scale_layer(data.y_order),
+                )
+            }
+            LayoutDirection::LeftToRight => (
+                self.layer_offsets[data.y_order] + self.padding,
+                self.scale_sibling(data.x_center) + self.unit_height / 2.0
+                    - self.extra_height(data) / 2.0,
+            ),
+        }
+    }
+
+    /// The x coordinate of the given line of a multi-line text.
+    /// In [LayoutDirection::TopToBottom] each line is centered on its own.
+    fn line_x(&self, data: &EmbeddedNode, index: usize, line: &str) -> f32 {
+        let width = data.line_width(index, line) as f32 * self.unit_width;
+        if let Some((x, _)) = self.radial_center(data) {
+            return x - width / 2.0;
+        }
+        match self.direction {
+            LayoutDirection::TopToBottom => self.scale_x(data.x_center) - width / 2.0,
+            LayoutDirection::LeftToRight => self.layer_offsets[data.y_order] + self.padding,
+        }
+    }
+
+    /// The height the text's lines following the first one add to the node
+    fn extra_height(&self, data: &EmbeddedNode) -> f32 {
+        data.line_count.saturating_sub(1) as f32 * self.unit_height
+    }
+
+    /// The box around the node's text as x, y, width and height.
+    /// It spans half a character beyond the text on either side. Boxes widened to the minimum
+    /// node width stay centered, except for [LayoutDirection::LeftToRight] where the texts start
+    /// at the layer's left side.
+    fn text_box(&self, data: &EmbeddedNode) -> (f32, f32, f32, f32) {
+        let (x, y) = self.text_position(data);
+        let width = self.box_width(data);
+        let widening = match (&self.radial, self.direction) {
+            (None, LayoutDirection::LeftToRight) => self.padding,
+            _ => (width - self.text_width(data)) / 2.0,
+        };
+        (
+            x - widening - self.unit_width / 2.0,
+            y - self.unit_height,
+            width + self.unit_width,
+            self.unit_height * 1.5 + self.extra_height(data),
+        )
+    }
+
+    /// The layer coordinate of the middle segment of an orthogonal edge from the given start to
+    /// the given end coordinate.
+    /// The segment runs halfway between the parent's layer and the adjacent layer towards the
+    /// child. Edges that span several layers, e.g. to leaves placed in the last layer, thus pass
+    /// the layers in between within the child's own column.
+    fn bend(&self, parent: &EmbeddedNode, child: &EmbeddedNode, from: f32, to: f32) -> f32 {
+        let layer_offset = |layer: usize| match self.direction {
+            LayoutDirection::TopToBottom => self.scale_layer(layer),
+            LayoutDirection::LeftToRight => self.layer_offsets[layer],
+        };
+        let adjacent = if parent.y_order <= child.y_order {
+            (parent.y_order + 1).min(child.y_order)
+        } else {
+            parent.y_order - 1
+        };
+        // The distance covered by the layers in between
+        let span = layer_offset(child.y_order) - layer_offset(adjacent);
+        from + (to - from - span) / 2.0
+    }
+
+    /// How far the node's outline extends beyond the text box on the sides facing the adjacent
+    /// layers, which is only the case for an ellipse passing through the box's corners
+    fn overhang(&self, data: &EmbeddedNode) -> f32 {
+        match data.shape.or(self.node_shape) {
+            Some(NodeShape::Ellipse) => {
+                let (_, _, width, height) = self.text_box(data);
+                let size = match self.direction {
+                    LayoutDirection::TopToBottom => height,
+                    LayoutDirection::LeftToRight => width,
+                };
+                size * (std::f32::consts::FRAC_1_SQRT_2 - 0.5)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The start and end point of the edge from a parent to its child.
+    /// The edge connects the facing sides of both nodes, which also holds for inverted layers
+    /// where the parent is placed behind its children. It starts and ends at the nodes'
+    /// outlines, so that it doesn't run over the texts.
+    fn edge(&self, parent: &EmbeddedNode, child: &EmbeddedNode) -> ((f32, f32), (f32, f32)) {
+        if let Some(edge) = self.radial_edge(parent, child) {
+            return edge;
+        }
+        let forward = parent.y_order <= child.y_order;
+        match self.direction {
+            LayoutDirection::TopToBottom => {
+                // The text spans from one line above its first baseline to half a line below its
+                // last baseline
+                let (parent_offset, child_offset) = if forward {
+                    (
+                        self.unit_height / 2.0 + self.extra_height(parent) + self.overhang(parent),
+                        -self.unit_height - self.overhang(child),
+                    )
+                } else {
+                    (
+                        -self.unit_height - self.overhang(parent),
+                        self.unit_height / 2.0 + self.extra_height(child) + self.overhang(child),
+                    )
+                };
+                (
+                    (
+                        self.scale_x(parent.x_center),
+                        self.scale_layer(parent.y_order) + parent_offset,
+                    ),
+                    (
+                        self.scale_x(child.x_center),
+                        self.scale_layer(child.y_order) + child_offset,
+                    ),
+                )
+            }
+            LayoutDirection::LeftToRight => {
+                let left_side = |e: &EmbeddedNode| {
+                    self.layer_offsets[e.y_order] - self.unit_width / 2.0 - self.overhang(e)
+                };
+                let right_side = |e: &EmbeddedNode| {
+                    self.layer_offsets[e.y_order]
+                        + self.box_width(e)
+                        + self.unit_width / 2.0
+                        + self.overhang(e)
+                };
+                let (x1, x2) = if forward {
+                    // From the right side of the parent's text to the left side of the child's text
+                    (right_side(parent), left_side(child))
+                } else {
+                    (left_side(parent), right_side(child))
+                };
+                (
+                    (x1, self.scale_sibling(parent.x_center)),
+                    (x2, self.scale_sibling(child.x_center)),
+                )
+            }
+        }
+    }
+
+    /// The point halfway along the edge from the given start to the given end point, which is
+    /// the middle of the bar for orthogonal edges. Symmetric curves pass the middle between
+    /// their end points just like straight edges.
+    fn edge_midpoint(
+        &self,
+        parent: &EmbeddedNode,
+        child: &EmbeddedNode,
+        edge_style: EdgeStyle,
+        (x1, y1): (f32, f32),
+        (x2, y2): (f32, f32),
+    ) -> (f32, f32) {
+        let middle = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+        match (edge_style, self.direction) {
+            (EdgeStyle::Orthogonal, LayoutDirection::TopToBottom) => {
+                (middle.0, self.bend(parent, child, y1, y2))
+            }
+            (EdgeStyle::Orthogonal, LayoutDirection::LeftToRight) => {
+                (self.bend(parent, child, x1, x2), middle.1)
+            }
+            _ => middle,
+        }
+    }
+
+    /// The radial segment between the nodes' centers of a [LayoutStyle::Radial] embedding,
+    /// clipped to both nodes' text boxes
+    fn radial_edge(
+        &self,
+        parent: &EmbeddedNode,
+        child: &EmbeddedNode,
+    ) -> Option<((f32, f32), (f32, f32))> {
+        let (from, to) = (self.radial_center(parent)?, self.radial_center(child)?);
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        // The share of the segment that lies within the node's box
+        let inside = |data: &EmbeddedNode| {
+            let (_, _, width, height) = self.text_box(data);
+            let share = |half: f32, d: f32| {
+                if d == 0.0 {
+                    f32::INFINITY
+                } else {
+                    half / d.abs()
+                }
+            };
+            share(width / 2.0, dx).min(share(height / 2.0, dy))
+        };
+        let (start, end) = (inside(parent), 1.0 - inside(child));
+        Some((
+            (from.0 + start * dx, from.1 + start * dy),
+            (from.0 + end * dx, from.1 + end * dy),
+        ))
+    }
+
+    /// Scales the layer to the y coordinate of [LayoutDirection::TopToBottom]
+    fn scale_layer(&self, y: usize) -> f32 {
+        self.layer_offsets[y]
+    }
+
+    /// The start and end of each layer's band along the layer axis, where the bands cover the
+    /// given size of the image without gaps
+    fn layer_bands(&self, size: f32) -> Vec<(f32, f32)> {
+        // The boundary between each layer and the next one lies halfway between them
+        let boundaries = (1..self.layer_offsets.len()).map(|layer| match self.direction {
+            LayoutDirection::TopToBottom => {
+                let previous = layer - 1;
+                let bottom = self.scale_layer(previous)
+                    + (self.layer_line_counts[previous] as f32 - 0.5) * self.unit_height;
+                let top = self.scale_layer(layer) - self.unit_height;
+                (bottom + top) / 2.0
+            }
+            LayoutDirection::LeftToRight => self.layer_offsets[layer] - self.layer_gap / 2.0,
+        });
+        let boundaries = std::iter::once(0.0)
+            .chain(boundaries)
+            .chain(std::iter::once(size))
+            .collect::<Vec<_>>();
+        boundaries.windows(2).map(|w| (w[0], w[1])).collect()
+    }
+
+    /// Scales the logical coordinate along the sibling axis of [LayoutDirection::LeftToRight]
+    fn scale_sibling(&self, x: usize) -> f32 {
+        x as f32 * self.unit_height + self.y_margin()
     }
 }
@@ -0,0 +1,291 @@
+//! The module with a drawer that renders the embedding as text, e.g. for terminal output.
+
+use crate::{Drawer, EmbeddedNode, Embedding, LayoutDirection, Result};
+use std::io::Write;
+
+// Connection flags of a grid cell
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+// The cell is covered by a wide character left of it and isn't printed
+const COVERED: u8 = 16;
+
+///
+/// The `TextDrawer` type provides the transformation of the embedding information into plain
+/// text, which is suitable to be printed to a terminal.
+///
+/// The nodes are drawn as boxes made of Unicode box-drawing characters and are connected by
+/// branches like `│`, `├` and `└`. The positions are derived from the embedding's
+/// coordinates, so the result structurally matches the output of the
+/// [SvgDrawer][crate::SvgDrawer].
+///
+#[derive(Debug, Default)]
+pub struct TextDrawer;
+
+impl TextDrawer {
+    /// Method to create a fresh instance of the `TextDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `TextDrawer`.
+///
+impl Drawer for TextDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// Each line of a node's text occupies the columns the layouter measured, see
+    /// [Layouter::with_char_width][crate::Layouter::with_char_width]. So with
+    /// [east_asian_char_width][crate::east_asian_char_width] wide characters occupy two columns,
+    /// like in most terminals.
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        let mut grid = Grid::new(embedding);
+        for data in embedding {
+            grid.draw_box(data);
+        }
+        for (parent_data, data) in embedding.edges() {
+            grid.draw_edge(parent_data, data);
+        }
+        Ok(grid.write(writer)?)
+    }
+}
+
+///
+/// A character grid onto which the boxes and edges are drawn.
+/// Lines are collected as connection flags per cell and converted to box-drawing characters when
+/// the grid is written, so that crossing and joining lines result in the correct junctions.
+///
+struct Grid {
+    direction: LayoutDirection,
+    /// The first row resp. column of each layer
+    layer_offsets: Vec<usize>,
+    /// The number of rows of each layer, only used for [LayoutDirection::TopToBottom]
+    layer_heights: Vec<usize>,
+    /// The number of rows resp. columns between two layers
+    layer_gap: usize,
+    /// Per cell either a text character or the cell's connection flags
+    cells: Vec<Vec<(Option<char>, u8)>>,
+}
+
+impl Grid {
+    fn new(embedding: &Embedding) -> Self {
+        let direction = embedding.direction();
+        let (_, depth) = embedding.dimensions();
+        let (layer_offsets, layer_heights, layer_gap) = match direction {
+            LayoutDirection::TopToBottom => {
+                // Each layer is as high as its highest box
+                let layer_gap = embedding.layer_spacing().max(1);
+                let mut layer_heights = vec![0; depth];
+                for e in embedding {
+                    layer_heights[e.y_order] = layer_heights[e.y_order].max(Self::box_height(e));
+                }
+                let layer_offsets = Self::offsets(&layer_heights, layer_gap);
+                (layer_offsets, layer_heights, layer_gap)
+            }
+            LayoutDirection::LeftToRight => {
+                // Each layer is as wide as its widest box
+                let layer_gap = 2 * embedding.layer_spacing().max(1);
+                let mut layer_widths = vec![0; depth];
+                for e in embedding {
+                    layer_widths[e.y_order] = layer_widths[e.y_order].max(Self::box_width(e));
+                }
+                (
+                    Self::offsets(&layer_widths, layer_gap),
+                    Vec::new(),
+                    layer_gap,
+                )
+            }
+        };
+        Self {
+            direction,
+            layer_offsets,
+            layer_heights,
+            layer_gap,
+            cells: Vec::new(),
+        }
+    }
+
+    /// The first row resp. column of each layer, given the layers' sizes
+    fn offsets(layer_sizes: &[usize], layer_gap: usize) -> Vec<usize> {
+        layer_sizes
+            .iter()
+            .scan(0, |offset, size| {
+                let layer_offset = *offset;
+                *offset += size + layer_gap;
+                Some(layer_offset)
+            })
+            .collect()
+    }
+
+    /// The width of a node's box including its borders
+    fn box_width(data: &EmbeddedNode) -> usize {
+        data.text_width() + 2
+    }
+
+    /// The height of a node's box including its borders
+    fn box_height(data: &EmbeddedNode) -> usize {
+        data.line_count.max(1) + 2
+    }
+
+    /// Scales the logical coordinate along the sibling axis.
+    /// Two grid cells per unit leave enough room for the box borders.
+    fn scale_sibling(x: usize) -> usize {
+        2 * x + 1
+    }
+
+    /// The upper left corner of the node's box as row and column
+    fn box_origin(&self, data: &EmbeddedNode) -> (usize, usize) {
+        let width = Self::box_width(data);
+        match self.direction {
+            LayoutDirection::TopToBottom => (
+                self.layer_offsets[data.y_order],
+                Self::scale_sibling(data.x_center).saturating_sub(width / 2),
+            ),
+            LayoutDirection::LeftToRight => (
+                Self::scale_sibling(data.x_center).saturating_sub(Self::box_height(data) / 2),
+                self.layer_offsets[data.y_order],
+            ),
+        }
+    }
+
+    fn cell(&mut self, row: usize, col: usize) -> &mut (Option<char>, u8) {
+        if self.cells.len() <= row {
+            self.cells.resize(row + 1, Vec::new());
+        }
+        let line = &mut self.cells[row];
+        if line.len() <= col {
+            line.resize(col + 1, (None, 0));
+        }
+        &mut line[col]
+    }
+
+    /// Draws a straight horizontal or vertical line between the two given cells
+    fn draw_line(&mut self, (r1, c1): (usize, usize), (r2, c2): (usize, usize)) {
+        debug_assert!(r1 == r2 || c1 == c2);
+        if r1 == r2 {
+            for c in c1.min(c2)..c1.max(c2) {
+                self.cell(r1, c).1 |= RIGHT;
+                self.cell(r1, c + 1).1 |= LEFT;
+            }
+        } else {
+            for r in r1.min(r2)..r1.max(r2) {
+                self.cell(r, c1).1 |= DOWN;
+                self.cell(r + 1, c1).1 |= UP;
+            }
+        }
+    }
+
+    fn draw_box(&mut self, data: &EmbeddedNode) {
+        let (top, left) = self.box_origin(data);
+        let right = left + Self::box_width(data) - 1;
+        let bottom = top + Self::box_height(data) - 1;
+        self.draw_line((top, left), (top, right));
+        self.draw_line((top, right), (bottom, right));
+        self.draw_line((bottom, right), (bottom, left));
+        self.draw_line((bottom, left), (top, left));
+        for (row, line) in data.text.lines().enumerate() {
+            let mut count = 0;
+            for (i, c) in line.chars().enumerate() {
+                self.cell(top + 1 + row, left + 1 + i).0 = Some(c);
+                count += 1;
+            }
+            // The wide characters extend into the columns following the text
+            for i in count..data.line_width(row, line) {
+                self.cell(top + 1 + row, left + 1 + i).1 |= COVERED;
+            }
+        }
+    }
+
+    /// Draws the edge from the parent's box to the child's box.
+    /// The edge leaves the parent, runs along a bar next to the adjacent layer and enters the
+    /// child, which results in junctions like `├` or `┴` where several children share the bar.
+    /// Edges that span several layers pass the layers in between within the child's column.
+    fn draw_edge(&mut self, parent: &EmbeddedNode, child: &EmbeddedNode) {
+        let forward = parent.y_order <= child.y_order;
+        let (parent_top, parent_left) = self.box_origin(parent);
+        let (child_top, child_left) = self.box_origin(child);
+        let adjacent = if forward {
+            (parent.y_order + 1).min(child.y_order)
+        } else {
+            parent.y_order - 1
+        };
+        match self.direction {
+            LayoutDirection::TopToBottom => {
+                let parent_col = Self::scale_sibling(parent.x_center);
+                let child_col = Self::scale_sibling(child.x_center);
+                let (parent_row, child_row, bar_row) = if forward {
+                    (
+                        parent_top + Self::box_height(parent) - 1,
+                        child_top,
+                        self.layer_offsets[adjacent] - 1,
+                    )
+                } else {
+                    // The bar runs below the adjacent layer, whose boxes may differ in height
+                    let child_bottom = child_top + Self::box_height(child) - 1;
+                    let bar_row = self.layer_offsets[adjacent] + self.layer_heights[adjacent];
+                    (parent_top, child_bottom, bar_row)
+                };
+                self.draw_line((parent_row, parent_col), (bar_row, parent_col));
+                self.draw_line((bar_row, parent_col), (bar_row, child_col));
+                self.draw_line((bar_row, child_col), (child_row, child_col));
+            }
+            LayoutDirection::LeftToRight => {
+                let parent_row = Self::scale_sibling(parent.x_center);
+                let child_row = Self::scale_sibling(child.x_center);
+                let (parent_col, child_col, bar_col) = if forward {
+                    let bar_col = self.layer_offsets[adjacent] - self.layer_gap / 2;
+                    (
+                        parent_left + Self::box_width(parent) - 1,
+                        child_left,
+                        bar_col,
+                    )
+                } else {
+                    let child_right = child_left + Self::box_width(child) - 1;
+                    let bar_col = if adjacent == child.y_order {
+                        child_right + self.layer_gap / 2
+                    } else {
+                        parent_left - self.layer_gap / 2
+                    };
+                    (parent_left, child_right, bar_col)
+                };
+                self.draw_line((parent_row, parent_col), (parent_row, bar_col));
+                self.draw_line((parent_row, bar_col), (child_row, bar_col));
+                self.draw_line((child_row, bar_col), (child_row, child_col));
+            }
+        }
+    }
+
+    fn to_char(cell: &(Option<char>, u8)) -> Option<char> {
+        let c = match cell {
+            (Some(c), _) => *c,
+            (None, flags) if flags & COVERED != 0 => return None,
+            (None, flags) => match *flags {
+                0 => ' ',
+                f if f == UP | DOWN || f == UP || f == DOWN => '│',
+                f if f == LEFT | RIGHT || f == LEFT || f == RIGHT => '─',
+                f if f == DOWN | RIGHT => '┌',
+                f if f == DOWN | LEFT => '┐',
+                f if f == UP | RIGHT => '└',
+                f if f == UP | LEFT => '┘',
+                f if f == UP | DOWN | RIGHT => '├',
+                f if f == UP | DOWN | LEFT => '┤',
+                f if f == DOWN | LEFT | RIGHT => '┬',
+                f if f == UP | LEFT | RIGHT => '┴',
+                _ => '┼',
+            },
+        };
+        Some(c)
+    }
+
+    fn write(&self, writer: &mut dyn Write) -> std::io::Result<()> {
+        for line in &self.cells {
+            let text = line.iter().filter_map(Self::to_char).collect::<String>();
+            writeln!(writer, "{}", text.trim_end())?;
+        }
+        writer.flush()
+    }
+}
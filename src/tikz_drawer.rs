@@ -0,0 +1,105 @@
+//! The module with a drawer that emits TikZ pictures for LaTeX documents.
+
+use crate::{Drawer, Embedding, LayoutDirection, Result};
+use std::io::Write;
+
+///
+/// The `TikzDrawer` type provides the transformation of the embedding information into a
+/// `tikzpicture` environment, which can be included into LaTeX documents, so that the figure uses
+/// the document's font.
+///
+/// Each node of the embedding becomes a `\node` named `n{ord}` at the logical coordinates
+/// `(x_center, -y_order)`, in [LayoutDirection::LeftToRight] at `(y_order, -x_center)`. The units
+/// of the axes are set with the picture's options, so that a logical unit along the sibling
+/// axis roughly matches one character. The edges become `\draw` commands between the parent's
+/// and the child's node. Emphasized nodes are set in bold.
+///
+/// The document needs the `tikz` package.
+///
+#[derive(Debug, Default)]
+pub struct TikzDrawer;
+
+impl TikzDrawer {
+    /// Method to create a fresh instance of the `TikzDrawer` type.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Escapes the given text to be used as content of a node.
+    /// Line breaks become `\\`, which requires the node's `align` option.
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' => escaped.push_str("\\textbackslash{}"),
+                '_' | '%' | '&' | '#' | '$' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '~' => escaped.push_str("\\textasciitilde{}"),
+                '^' => escaped.push_str("\\textasciicircum{}"),
+                '\n' => escaped.push_str("\\\\"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+///
+/// The concrete implementation of the `Drawer` trait for `TikzDrawer`.
+///
+impl Drawer for TikzDrawer {
+    ///
+    /// The concrete implementation of the `Drawer::draw_to_writer` trait method.
+    ///
+    /// # Complexity
+    ///
+    /// The algorithm is of time complexity class O(n).
+    ///
+    fn draw_to_writer(&self, writer: &mut dyn Write, embedding: &Embedding) -> Result<()> {
+        fn build_tikz(out: &mut dyn Write, embedding: &Embedding) -> std::io::Result<()> {
+            let direction = embedding.direction();
+            let units = match direction {
+                LayoutDirection::TopToBottom => "x=0.5em, y=1.5cm",
+                LayoutDirection::LeftToRight => "x=2cm, y=1.2em",
+            };
+            writeln!(out, "\\begin{{tikzpicture}}[{}]", units)?;
+
+            for data in embedding {
+                let (x, y) = match direction {
+                    LayoutDirection::TopToBottom => (data.x_center, data.y_order),
+                    LayoutDirection::LeftToRight => (data.y_order, data.x_center),
+                };
+                let mut options = vec!["draw"];
+                if data.line_count > 1 {
+                    options.push("align=center");
+                }
+                if data.is_emphasized {
+                    options.push("font=\\bfseries");
+                }
+                writeln!(
+                    out,
+                    "  \\node[{}] (n{}) at ({}, -{}) {{{}}};",
+                    options.join(", "),
+                    data.ord,
+                    x,
+                    y,
+                    TikzDrawer::escape(&data.text)
+                )?;
+            }
+
+            for data in embedding {
+                if let Some(parent) = data.parent {
+                    writeln!(out, "  \\draw (n{}) -- (n{});", parent, data.ord)?;
+                }
+            }
+
+            writeln!(out, "\\end{{tikzpicture}}")?;
+
+            out.flush()
+        }
+
+        Ok(build_tikz(writer, embedding)?)
+    }
+}
@@ -1,5 +1,7 @@
 //! The visualize module provides the `Visualize` trait.
 
+use crate::NodeShape;
+
 /// The `Visualize` trait abstracts the visual presentation of the node's data.
 /// It can be implemented by the Tree<T, ...>'s node type T when custom visualization is desired.
 /// Only mandatory to implement is the `visualize` method.
@@ -12,4 +14,39 @@ pub trait Visualize {
     fn emphasize(&self) -> bool {
         false
     }
+
+    /// When this method returns a color the drawer can fill the background of the node with it.
+    /// The color's format is drawer dependent, the `SvgDrawer` accepts any SVG color value like
+    /// `"#ffcc00"` or `"yellow"`. Nodes without a color are drawn with the drawer's default.
+    fn background_color(&self) -> Option<String> {
+        None
+    }
+
+    /// When this method returns a text the drawer can show it as tooltip of the node, e.g. the
+    /// `SvgDrawer` emits it as `<title>` element which browsers show on hovering.
+    /// This is independent of the visible label, so a short label can be detailed by a long
+    /// description.
+    fn tooltip(&self) -> Option<String> {
+        None
+    }
+
+    /// When this method returns a shape the drawer can draw the node's outline with it, e.g. the
+    /// `SvgDrawer` draws the node as ellipse for [NodeShape::Ellipse]. Nodes without a shape are
+    /// drawn with the drawer's default.
+    fn shape(&self) -> Option<NodeShape> {
+        None
+    }
+
+    /// When this method returns a URL the drawer can turn the node into a hyperlink, e.g. the
+    /// `SvgDrawer` wraps it in an `<a>` element, which makes it clickable in a browser.
+    fn link(&self) -> Option<String> {
+        None
+    }
+
+    /// When this method returns a text the drawer can label the edge from the node to its
+    /// parent with it, e.g. the production alternative taken in a derivation tree. The
+    /// `SvgDrawer` places it at the edge's midpoint. Nodes without a label get a plain edge.
+    fn edge_label(&self) -> Option<String> {
+        None
+    }
 }
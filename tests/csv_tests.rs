@@ -0,0 +1,76 @@
+#![cfg(feature = "csv")]
+
+use syntree::Builder;
+use syntree_layout::{CsvDrawer, Layouter};
+
+/// Splits CSV text into rows of fields, undoing the quoting
+fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    rows
+}
+
+#[test]
+fn csv_drawer_round_trips() {
+    let mut tree = Builder::new();
+    tree.open("root").unwrap();
+    tree.token("a, b", 1).unwrap();
+    tree.token("say \"hi\"", 1).unwrap();
+    tree.token("two\nlines", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_drawer(&CsvDrawer)
+        .embed_with(|s: &&str| s.to_string(), |s: &&str| s.starts_with("say"))
+        .unwrap();
+    let csv = layouter.write_to_string().unwrap();
+    let rows = parse_csv(&csv);
+    let embedding = layouter.embedding();
+    assert_eq!(embedding.len() + 1, rows.len());
+    assert_eq!(
+        vec![
+            "ord",
+            "parent",
+            "y_order",
+            "x_center",
+            "x_extent",
+            "emphasized",
+            "text"
+        ],
+        rows[0]
+    );
+
+    for (row, data) in rows[1..].iter().zip(embedding.iter()) {
+        let expected = vec![
+            data.ord.to_string(),
+            data.parent.map(|p| p.to_string()).unwrap_or_default(),
+            data.y_order.to_string(),
+            data.x_center.to_string(),
+            data.x_extent.to_string(),
+            data.is_emphasized.to_string(),
+            data.text.clone(),
+        ];
+        assert_eq!(&expected, row);
+    }
+    assert!(csv.contains(",\"a, b\"\n"));
+    assert!(csv.contains(",true,\"say \"\"hi\"\"\"\n"));
+}
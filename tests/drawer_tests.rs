@@ -0,0 +1,2655 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use syntree::{Builder, Tree};
+use syntree_layout::{
+    east_asian_char_width, Align, AnnotationKind, CanvasOverflow, DotDrawer, Drawer, EdgeStyle,
+    EmbeddedNode, Embedding, EmphasisStyle, GraphMlDrawer, HtmlDrawer, JsonDrawer, LayoutDirection,
+    LayoutStyle, Layouter, LayouterError, MermaidDrawer, NodeShape, PlantUmlDrawer, Result,
+    SvgDrawer, TextDrawer, Theme, TikzDrawer, TypedDrawer, Unit, Visualize,
+};
+
+#[derive(Debug)]
+struct MyNodeData(i32);
+
+impl Visualize for MyNodeData {
+    fn visualize(&self) -> std::string::String {
+        self.0.to_string()
+    }
+    fn emphasize(&self) -> bool {
+        self.0 == 2
+    }
+}
+
+#[derive(Debug)]
+struct Label(&'static str);
+
+impl Visualize for Label {
+    fn visualize(&self) -> std::string::String {
+        self.0.to_string()
+    }
+}
+
+//      0
+//     / \
+//    1   2
+//   / \
+//  3   4
+fn more_complex_tree() -> Tree<MyNodeData, u32, usize> {
+    let mut tree = Builder::new();
+
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(3), 1).unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+
+    tree.build().unwrap()
+}
+
+fn output_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(file_name)
+}
+
+#[test]
+fn dot_drawer_round_trip() {
+    let tree = more_complex_tree();
+    let path = output_path("dot_drawer_round_trip.dot");
+    let drawer = DotDrawer::new();
+    Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .with_file_path(&path)
+        .embed_with_visualize()
+        .unwrap()
+        .write()
+        .unwrap();
+
+    let dot = std::fs::read_to_string(&path).unwrap();
+    let lines = dot.lines().map(str::trim).collect::<Vec<_>>();
+    assert_eq!(Some(&"digraph {"), lines.first());
+    assert_eq!(Some(&"}"), lines.last());
+
+    let nodes = lines
+        .iter()
+        .filter(|l| l.contains("[label="))
+        .map(|l| {
+            l.split_whitespace()
+                .next()
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(vec![0, 1, 2, 3, 4], nodes);
+
+    let mut edges = lines
+        .iter()
+        .filter_map(|l| l.strip_suffix(';')?.split_once(" -> "))
+        .map(|(from, to)| (from.parse::<usize>().unwrap(), to.parse::<usize>().unwrap()))
+        .collect::<Vec<_>>();
+    edges.sort();
+    assert_eq!(vec![(0, 1), (0, 4), (1, 2), (1, 3)], edges);
+
+    // Only the node with the data 2 is emphasized
+    let emphasized = lines
+        .iter()
+        .filter(|l| l.contains("style=bold"))
+        .collect::<Vec<_>>();
+    assert_eq!(1, emphasized.len());
+    assert!(emphasized[0].contains("label=\"2\""));
+}
+
+#[test]
+fn svg_drawer_writes_to_string() {
+    let tree = more_complex_tree();
+    let svg = Layouter::new(&tree)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.contains("<svg"));
+    assert_eq!(5, svg.matches("<text").count());
+    assert_eq!(4, svg.matches("<line").count());
+}
+
+#[test]
+fn drawer_without_writer_support() {
+    struct FileOnlyDrawer;
+    impl Drawer for FileOnlyDrawer {
+        fn draw(&self, _file_name: &Path, _embedding: &Embedding) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    let tree = more_complex_tree();
+    let drawer = FileOnlyDrawer;
+    let layouter = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap();
+    assert!(matches!(
+        layouter.write_to_string(),
+        Err(LayouterError::WriterNotSupported)
+    ));
+}
+
+/// Collects the values of the given attribute of all elements with the given name
+fn attribute_values(svg: &str, element: &str, attribute: &str) -> Vec<f32> {
+    let start = format!("<{element} ");
+    let key = format!("{attribute}=\"");
+    svg.match_indices(&start)
+        .filter_map(|(pos, _)| {
+            let tag = &svg[pos..pos + svg[pos..].find('>').unwrap()];
+            let value = &tag[tag.find(&key)? + key.len()..];
+            value[..value.find('"').unwrap()].parse().ok()
+        })
+        .collect()
+}
+
+#[test]
+fn svg_drawer_respects_layer_spacing() {
+    let tree = more_complex_tree();
+    let layer_ys = |layer_spacing| {
+        let svg = Layouter::new(&tree)
+            .with_layer_spacing(layer_spacing)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        let mut ys = attribute_values(&svg, "text", "y");
+        ys.sort_by(f32::total_cmp);
+        ys.dedup();
+        ys
+    };
+
+    let narrow = layer_ys(0);
+    let wide = layer_ys(4);
+    assert_eq!(3, narrow.len());
+    assert_eq!(3, wide.len());
+    assert_eq!(narrow[0], wide[0]);
+    // Default font size is 10, so each additional line of spacing adds 10
+    assert_eq!(10.0, narrow[1] - narrow[0]);
+    assert_eq!(50.0, wide[1] - wide[0]);
+    assert_eq!(50.0, wide[2] - wide[1]);
+}
+
+#[test]
+fn write_errors() {
+    let tree = more_complex_tree();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert!(matches!(
+        layouter.write(),
+        Err(LayouterError::MissingOutputPath)
+    ));
+
+    let path = output_path("does/not/exist.svg");
+    let layouter = Layouter::new(&tree)
+        .with_file_path(&path)
+        .embed_with_visualize()
+        .unwrap();
+    let err = layouter.write().unwrap_err();
+    assert!(matches!(&err, LayouterError::Io(e) if e.kind() == std::io::ErrorKind::NotFound));
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+/// A writer that refuses all writes like a read-only destination
+struct ReadOnlyWriter;
+
+impl std::io::Write for ReadOnlyWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::PermissionDenied.into())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn io_errors_are_the_source() {
+    let tree = more_complex_tree();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let source_kind = |err: &LayouterError| {
+        std::error::Error::source(err)
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .map(std::io::Error::kind)
+    };
+
+    // A directory can't be written as a file
+    let err = SvgDrawer::new()
+        .draw(&output_path(""), layouter.embedding())
+        .unwrap_err();
+    assert_eq!(Some(std::io::ErrorKind::IsADirectory), source_kind(&err));
+
+    let drawers: [&dyn Drawer; 4] = [
+        &SvgDrawer::new(),
+        &DotDrawer::new(),
+        &JsonDrawer::new(),
+        &TextDrawer::new(),
+    ];
+    for drawer in drawers {
+        let err = drawer
+            .draw_to_writer(&mut ReadOnlyWriter, layouter.embedding())
+            .unwrap_err();
+        assert_eq!(
+            Some(std::io::ErrorKind::PermissionDenied),
+            source_kind(&err)
+        );
+    }
+
+    let err: LayouterError = std::io::Error::from(std::io::ErrorKind::PermissionDenied).into();
+    assert!(matches!(err, LayouterError::Io(_)));
+}
+
+#[test]
+fn text_drawer_draws_boxes_and_branches() {
+    let tree = more_complex_tree();
+    let drawer = TextDrawer::new();
+    let text = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let expected = "
+      ┌─┐
+      │0│
+      └┬┘
+       │
+     ┌─┴───┐
+    ┌┴┐   ┌┴┐
+    │1│   │2│
+    └┬┘   └─┘
+     │
+   ┌─┴─┐
+  ┌┴┐ ┌┴┐
+  │3│ │4│
+  └─┘ └─┘
+";
+    assert_eq!(expected.trim_start_matches('\n'), text);
+}
+
+#[test]
+fn text_drawer_left_to_right() {
+    let tree = more_complex_tree();
+    let drawer = TextDrawer::new();
+    let text = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .with_direction(LayoutDirection::LeftToRight)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    // Edges leave the parents on their right side and enter the children on their left side
+    assert!(text.contains("│0├"));
+    assert!(text.contains("┤1├"));
+    assert!(text.contains("┤2│"));
+    assert!(text.contains("┤3│"));
+    assert!(text.contains("┤4│"));
+}
+
+#[test]
+fn svg_drawer_fills_background_color() {
+    struct Colored(&'static str, Option<&'static str>);
+    impl Visualize for Colored {
+        fn visualize(&self) -> String {
+            self.0.to_string()
+        }
+        fn background_color(&self) -> Option<String> {
+            self.1.map(str::to_string)
+        }
+    }
+
+    let mut tree = Builder::new();
+    tree.open(Colored("fn", Some("#ffcc00"))).unwrap();
+    tree.token(Colored("main", None), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!(
+        Some("#ffcc00".to_string()),
+        layouter.embedding()[0].background_color
+    );
+    assert_eq!(None, layouter.embedding()[1].background_color);
+
+    let svg = layouter.write_to_string().unwrap();
+    assert!(svg.contains("fill=\"#ffcc00\""));
+    // The image background and the colored node
+    assert_eq!(2, svg.matches("<rect").count());
+}
+
+#[test]
+fn svg_drawer_escapes_background_color() {
+    struct Colored(&'static str);
+    impl Visualize for Colored {
+        fn visualize(&self) -> String {
+            self.0.to_string()
+        }
+        fn background_color(&self) -> Option<String> {
+            Some("red\" onload=\"alert('&<')".to_string())
+        }
+    }
+
+    let mut tree = Builder::new();
+    tree.open(Colored("fn")).unwrap();
+    tree.token(Colored("main"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let svg = Layouter::new(&tree)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let fills = document
+        .descendants()
+        .filter(|node| node.has_tag_name("rect"))
+        .filter_map(|node| node.attribute("fill"))
+        .filter(|fill| *fill == "red\" onload=\"alert('&<')")
+        .count();
+    assert_eq!(2, fills);
+    assert!(document
+        .descendants()
+        .all(|node| node.attribute("onload").is_none()));
+}
+
+#[test]
+fn svg_drawer_renders_multi_line_labels() {
+    let mut tree = Builder::new();
+    tree.open("fn\nmain_function").unwrap();
+    tree.token("a", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed().unwrap();
+    let svg = layouter.write_to_string().unwrap();
+    assert_eq!(2, svg.matches("<tspan").count());
+    assert!(svg.contains(">fn</tspan>"));
+    assert!(svg.contains(">main_function</tspan>"));
+
+    // Both lines are placed one line apart and the child starts below the last line
+    assert_eq!(vec![10.0], attribute_values(&svg, "tspan", "dy"));
+    let y = attribute_values(&svg, "text", "y");
+    assert_eq!(40.0, y[1] - y[0]);
+
+    let text = Layouter::new(&tree)
+        .with_drawer(&TextDrawer)
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(text.contains("│fn           │"));
+    assert!(text.contains("│main_function│"));
+}
+
+#[test]
+fn svg_drawer_scales_with_font_size() {
+    let tree = more_complex_tree();
+    let render = |drawer: &SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let small = render(&SvgDrawer::new().with_font_size(10.0));
+    let large = render(&SvgDrawer::new().with_font_size(20.0));
+    assert!(small.contains("font-size: 10px"));
+    assert!(large.contains("font-size: 20px"));
+
+    let small_width = attribute_values(&small, "svg", "width")[0];
+    let large_width = attribute_values(&large, "svg", "width")[0];
+    assert_eq!(2.0 * small_width, large_width);
+    let small_height = attribute_values(&small, "svg", "height")[0];
+    let large_height = attribute_values(&large, "svg", "height")[0];
+    assert_eq!(2.0 * small_height, large_height);
+
+    // The default scale is kept when only the unit height changes
+    let default = render(&SvgDrawer::new());
+    let tall = render(&SvgDrawer::new().with_unit_height(20.0));
+    assert!(!default.contains("font-size"));
+    assert_eq!(
+        attribute_values(&default, "svg", "width"),
+        attribute_values(&tall, "svg", "width")
+    );
+}
+
+#[test]
+fn mermaid_drawer_emits_flowchart() {
+    let tree = more_complex_tree();
+    let mermaid = Layouter::new(&tree)
+        .with_drawer(&MermaidDrawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let lines = mermaid.lines().map(str::trim).collect::<Vec<_>>();
+    assert_eq!(Some(&"graph TD"), lines.first());
+
+    let nodes = lines.iter().filter(|l| l.ends_with("\"]")).count();
+    assert_eq!(5, nodes);
+    assert!(lines.contains(&"n4[\"2\"]"));
+
+    let mut edges = lines
+        .iter()
+        .filter_map(|l| l.split_once(" --> "))
+        .collect::<Vec<_>>();
+    edges.sort();
+    assert_eq!(
+        vec![("n0", "n1"), ("n0", "n4"), ("n1", "n2"), ("n1", "n3")],
+        edges
+    );
+
+    // Only the node with the data 2 is emphasized, which has the ord 4
+    assert!(lines.iter().any(|l| l.starts_with("classDef emphasized")));
+    let classes = lines
+        .iter()
+        .filter(|l| l.starts_with("class "))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![&"class n4 emphasized;"], classes);
+}
+
+#[test]
+fn mermaid_drawer_escapes_labels() {
+    let mut tree = Builder::new();
+    tree.open("say \"[hi]\"").unwrap();
+    tree.token("a\nb", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let mermaid = Layouter::new(&tree)
+        .with_direction(LayoutDirection::LeftToRight)
+        .with_drawer(&MermaidDrawer)
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(mermaid.starts_with("graph LR\n"));
+    assert!(mermaid.contains("n0[\"say #quot;#91;hi#93;#quot;\"]"));
+    assert!(mermaid.contains("n1[\"a<br>b\"]"));
+}
+
+#[test]
+fn svg_drawer_routes_orthogonal_edges() {
+    let tree = more_complex_tree();
+    let render = |direction| {
+        Layouter::new(&tree)
+            .with_direction(direction)
+            .with_drawer(&SvgDrawer::new().with_edge_style(EdgeStyle::Orthogonal))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let path_data = |svg: &str| {
+        svg.match_indices(" d=\"")
+            .map(|(i, _)| {
+                let rest = &svg[i + 4..];
+                rest[..rest.find('"').unwrap()].to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let svg = render(LayoutDirection::TopToBottom);
+    assert!(!svg.contains("<line"));
+    let paths = path_data(&svg);
+    assert_eq!(4, paths.len());
+    for d in &paths {
+        // A move followed by three segments: down, across and down
+        let commands = d
+            .split_whitespace()
+            .filter(|t| t.parse::<f32>().is_err())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["M", "V", "H", "V"], commands, "{d}");
+    }
+    // All children of the root share the horizontal segment between layer 0 and 1
+    let bars = paths
+        .iter()
+        .map(|d| d.split_whitespace().nth(4).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(2, bars.iter().filter(|y| **y == bars[0]).count());
+
+    let svg = render(LayoutDirection::LeftToRight);
+    for d in path_data(&svg) {
+        let commands = d
+            .split_whitespace()
+            .filter(|t| t.parse::<f32>().is_err())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["M", "H", "V", "H"], commands, "{d}");
+    }
+}
+
+#[test]
+fn svg_drawer_draws_arrowheads() {
+    let tree = more_complex_tree();
+    let render = |drawer: &SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let svg = render(&SvgDrawer::new());
+    assert!(!svg.contains("<marker"));
+    assert!(!svg.contains("marker-end"));
+
+    let svg = render(&SvgDrawer::new().with_arrowheads(true));
+    assert!(svg.contains("<defs>"));
+    assert!(svg.contains("<marker id=\"arrowhead\""));
+    assert_eq!(4, svg.matches("marker-end=\"url(#arrowhead)\"").count());
+    assert_eq!(vec![8.0], attribute_values(&svg, "marker", "markerWidth"));
+
+    // The arrowheads scale with the font size
+    let svg = render(&SvgDrawer::new().with_font_size(20.0).with_arrowheads(true));
+    assert_eq!(vec![16.0], attribute_values(&svg, "marker", "markerWidth"));
+}
+
+#[test]
+fn svg_drawer_shows_full_text_of_truncated_labels() {
+    let mut tree = Builder::new();
+    tree.open("a_rather_long_identifier").unwrap();
+    tree.token("x", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let svg = Layouter::new(&tree)
+        .with_max_label_width(8)
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(svg.contains("<title>a_rather_long_identifier</title>a_rathe…</text>"));
+    assert_eq!(1, svg.matches("<title>").count());
+}
+
+#[test]
+fn svg_drawer_emits_tooltips() {
+    struct Described(&'static str, Option<&'static str>);
+    impl Visualize for Described {
+        fn visualize(&self) -> String {
+            self.0.to_string()
+        }
+        fn tooltip(&self) -> Option<String> {
+            self.1.map(str::to_string)
+        }
+    }
+
+    let mut tree = Builder::new();
+    tree.open(Described("fn", Some("function main, defined in main.rs")))
+        .unwrap();
+    tree.token(Described("main", None), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!(None, layouter.embedding()[1].tooltip);
+    let svg = layouter.write_to_string().unwrap();
+    assert!(svg.contains("<title>function main, defined in main.rs</title>fn</text>"));
+    assert_eq!(1, svg.matches("<title>").count());
+}
+
+#[test]
+fn embedding_is_written_by_several_drawers() {
+    let tree = more_complex_tree();
+    let svg_path = output_path("embedding_is_written_by_several_drawers.svg");
+    let dot_path = output_path("embedding_is_written_by_several_drawers.dot");
+    let dot_drawer = DotDrawer::new();
+    let text_drawer = TextDrawer::new();
+
+    let mut layouter = Layouter::new(&tree)
+        .with_file_path(&svg_path)
+        .embed_with_visualize()
+        .unwrap();
+    layouter.write().unwrap();
+    layouter.set_file_path(&dot_path).set_drawer(&dot_drawer);
+    layouter.write().unwrap();
+    layouter.set_drawer(&text_drawer);
+    let text = layouter.write_to_string().unwrap();
+
+    assert!(std::fs::read_to_string(&svg_path).unwrap().contains("<svg"));
+    assert!(std::fs::read_to_string(&dot_path)
+        .unwrap()
+        .starts_with("digraph {"));
+    assert!(text.contains("│0│"));
+    assert_eq!(5, layouter.embedding().len());
+}
+
+#[test]
+fn svg_drawer_leaves_margin() {
+    let tree = more_complex_tree();
+    let render = |margin| {
+        Layouter::new(&tree)
+            .with_drawer(&SvgDrawer::new().with_margin(margin))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let min = |values: Vec<f32>| values.into_iter().fold(f32::MAX, f32::min);
+
+    let narrow = render(0.0);
+    let wide = render(20.0);
+    assert_eq!(
+        min(attribute_values(&narrow, "text", "x")) + 20.0,
+        min(attribute_values(&wide, "text", "x"))
+    );
+    assert_eq!(
+        min(attribute_values(&narrow, "text", "y")) + 20.0,
+        min(attribute_values(&wide, "text", "y"))
+    );
+    let size = |svg: &str| {
+        (
+            attribute_values(svg, "svg", "width")[0],
+            attribute_values(svg, "svg", "height")[0],
+        )
+    };
+    let (narrow_width, narrow_height) = size(&narrow);
+    let (wide_width, wide_height) = size(&wide);
+    assert_eq!(narrow_width + 40.0, wide_width);
+    assert_eq!(narrow_height + 40.0, wide_height);
+    assert!(wide.contains(&format!("viewBox=\"0 0 {} {}\"", wide_width, wide_height)));
+}
+
+#[test]
+fn svg_drawer_draws_curved_edges() {
+    let tree = more_complex_tree();
+    let svg = Layouter::new(&tree)
+        .with_drawer(&SvgDrawer::new().with_edge_style(EdgeStyle::Curved))
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(!svg.contains("<line"));
+    let paths = svg
+        .match_indices(" d=\"")
+        .map(|(i, _)| {
+            let rest = &svg[i + 4..];
+            rest[..rest.find('"').unwrap()].to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(4, paths.len());
+    for d in &paths {
+        let tokens = d.split_whitespace().collect::<Vec<_>>();
+        assert_eq!("M", tokens[0]);
+        assert_eq!("C", tokens[3]);
+        assert_eq!(10, tokens.len(), "{d}");
+        assert!(!tokens.contains(&"L"));
+        // The control points are vertically offset from the endpoints
+        let y = |i: usize| tokens[i].parse::<f32>().unwrap();
+        assert!(y(2) < y(5) && y(5) < y(9) && y(7) < y(9));
+    }
+}
+
+#[test]
+fn empty_tree_results_in_valid_output() {
+    let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert!(layouter.embedding().is_empty());
+
+    let svg = layouter.write_to_string().unwrap();
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    for attribute in ["width", "height"] {
+        let value = attribute_values(&svg, "svg", attribute)[0];
+        assert!(value.is_finite() && value >= 1.0, "{attribute}: {value}");
+    }
+    assert!(!svg.contains("<text"));
+    assert!(!svg.contains("NaN"));
+
+    let write = |drawer: &dyn Drawer| {
+        Layouter::new(&tree)
+            .with_drawer(drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let dot = write(&DotDrawer);
+    assert!(dot.starts_with("digraph {") && dot.trim_end().ends_with('}'));
+    assert_eq!("", write(&TextDrawer));
+    assert!(write(&MermaidDrawer).starts_with("graph TD"));
+}
+
+#[test]
+fn svg_drawer_borders_emphasized_nodes() {
+    let tree = more_complex_tree();
+    let render = |style| {
+        Layouter::new(&tree)
+            .with_drawer(&SvgDrawer::new().with_emphasis_style(style))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    // Only the image background
+    let svg = render(EmphasisStyle::Bold);
+    assert_eq!(1, svg.matches("<rect").count());
+    assert!(svg.contains("font-weight: bold"));
+
+    // The node with the data 2 is emphasized
+    let svg = render(EmphasisStyle::DashedBorder);
+    assert_eq!(2, svg.matches("<rect").count());
+    let rect = &svg[svg.rfind("<rect").unwrap()..];
+    let rect = &rect[..=rect.find('>').unwrap()];
+    assert!(rect.contains("stroke-dasharray="), "{rect}");
+    assert!(rect.contains("fill=\"none\""), "{rect}");
+    let text_x = attribute_values(&svg, "text", "x");
+    assert!(text_x.contains(&(attribute_values(rect, "rect", "x")[0] + 5.0)));
+
+    let svg = render(EmphasisStyle::ThickBorder);
+    assert_eq!(1, svg.matches("stroke-width=\"2\"").count());
+    assert!(!svg.contains("stroke-dasharray"));
+}
+
+#[test]
+fn svg_drawer_draws_node_shapes() {
+    let tree = more_complex_tree();
+    let render = |shape| {
+        Layouter::new(&tree)
+            .with_drawer(&SvgDrawer::new().with_node_shape(shape))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let node_count = tree.walk().count();
+
+    // The image background plus one rect per node
+    let svg = render(NodeShape::Rect);
+    assert_eq!(node_count + 1, svg.matches("<rect ").count());
+    assert!(!svg.contains("<ellipse "));
+
+    let svg = render(NodeShape::RoundedRect { radius: 3.0 });
+    assert_eq!(node_count, svg.matches("rx=\"3\"").count());
+
+    let svg = render(NodeShape::Ellipse);
+    assert_eq!(1, svg.matches("<rect ").count());
+    assert_eq!(node_count, svg.matches("<ellipse ").count());
+}
+
+#[test]
+fn svg_drawer_draws_node_shapes_from_visualize() {
+    struct Shaped(&'static str, Option<NodeShape>);
+    impl Visualize for Shaped {
+        fn visualize(&self) -> String {
+            self.0.to_string()
+        }
+        fn shape(&self) -> Option<NodeShape> {
+            self.1
+        }
+    }
+
+    let mut tree = Builder::new();
+    tree.open(Shaped("expression", None)).unwrap();
+    tree.token(Shaped("terminal", Some(NodeShape::Ellipse)), 1)
+        .unwrap();
+    tree.token(Shaped("terminal", None), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    // The ellipse reserves more width than the plain label
+    assert!(embedding[1].x_extent > embedding[2].x_extent);
+    let svg = layouter.write_to_string().unwrap();
+    assert_eq!(1, svg.matches("<ellipse ").count());
+    assert_eq!(1, svg.matches("<rect ").count());
+}
+
+#[test]
+fn svg_drawer_applies_theme() {
+    let tree = more_complex_tree();
+    let render = |theme| {
+        Layouter::new(&tree)
+            .with_drawer(&SvgDrawer::new().with_theme(theme))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let svg = render(Theme::default());
+    assert_eq!(render(Theme::light()), svg);
+    assert!(svg.contains("fill=\"white\""));
+
+    let dark = Theme::dark();
+    let svg = render(dark.clone());
+    // The first rect is the image's background
+    let background = &svg[svg.find("<rect ").unwrap()..];
+    let background = &background[..background.find('>').unwrap()];
+    assert!(background.contains(&format!("fill=\"{}\"", dark.background)));
+    assert!(!svg.contains("white"));
+    assert!(!svg.contains("black"));
+    // Every node is filled
+    assert_eq!(tree.walk().count() + 1, svg.matches("<rect ").count());
+    assert_eq!(
+        1,
+        svg.matches(&format!("fill=\"{}\"", dark.emphasis_color))
+            .count()
+    );
+}
+
+#[test]
+fn svg_drawer_escapes_theme_colors() {
+    let tree = more_complex_tree();
+    let color = |name: &str| format!("{name}\" onload=\"alert('&<')");
+    let theme = Theme {
+        node_fill: Some(color("fill")),
+        node_stroke: color("stroke"),
+        text_color: color("text"),
+        edge_color: color("edge"),
+        emphasis_color: color("emphasis"),
+        background: color("background"),
+    };
+    let svg = Layouter::new(&tree)
+        .with_drawer(
+            &SvgDrawer::new()
+                .with_theme(theme.clone())
+                .with_arrowheads(true)
+                .with_grid(2)
+                .with_grid_labels(true)
+                .with_layer_bands(true),
+        )
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let colors = document
+        .descendants()
+        .flat_map(|node| [node.attribute("fill"), node.attribute("stroke")])
+        .flatten()
+        .collect::<HashSet<_>>();
+    for expected in [
+        theme.node_fill.as_ref().unwrap(),
+        &theme.node_stroke,
+        &theme.text_color,
+        &theme.edge_color,
+        &theme.emphasis_color,
+        &theme.background,
+    ] {
+        assert!(colors.contains(expected.as_str()), "{}", expected);
+    }
+    assert!(document
+        .descendants()
+        .all(|node| node.attribute("onload").is_none()));
+}
+
+#[test]
+fn svg_drawer_wraps_linked_nodes_in_anchors() {
+    struct Linked(&'static str, Option<&'static str>);
+    impl Visualize for Linked {
+        fn visualize(&self) -> String {
+            self.0.to_string()
+        }
+        fn link(&self) -> Option<String> {
+            self.1.map(str::to_string)
+        }
+    }
+
+    let mut tree = Builder::new();
+    tree.open(Linked(
+        "fn",
+        Some("https://example.com/src?file=main.rs&line=1"),
+    ))
+    .unwrap();
+    tree.token(Linked("main", None), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let svg = Layouter::new(&tree)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(svg.contains("xmlns:xlink=\"http://www.w3.org/1999/xlink\""));
+    assert_eq!(1, svg.matches("<a ").count());
+    let anchor = &svg[svg.find("<a ").unwrap()..svg.find("</a>").unwrap()];
+    assert!(
+        anchor.starts_with("<a xlink:href=\"https://example.com/src?file=main.rs&amp;line=1\">")
+    );
+    assert!(anchor.contains(">fn</text>"));
+    assert!(!anchor.contains("main</text>"));
+
+    // Without links the output doesn't change
+    let svg = Layouter::new(&more_complex_tree())
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(!svg.contains("xlink"));
+}
+
+#[test]
+fn svg_drawer_applies_stroke_width_and_edge_color() {
+    let tree = more_complex_tree();
+    let node_count = tree.walk().count();
+    for edge_style in [EdgeStyle::Straight, EdgeStyle::Orthogonal] {
+        let drawer = SvgDrawer::new()
+            .with_edge_style(edge_style)
+            .with_node_shape(NodeShape::Rect)
+            .with_stroke_width(0.5)
+            .with_edge_color("#336699");
+        let svg = Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        let edge = if edge_style == EdgeStyle::Straight {
+            "<line "
+        } else {
+            "<path "
+        };
+        let edges = svg
+            .match_indices(edge)
+            .map(|(pos, _)| &svg[pos..pos + svg[pos..].find('>').unwrap()])
+            .collect::<Vec<_>>();
+        assert_eq!(node_count - 1, edges.len());
+        for edge in edges {
+            assert!(edge.contains("stroke=\"#336699\""), "{edge}");
+            assert!(edge.contains("stroke-width=\"0.5\""), "{edge}");
+        }
+        // The nodes' outlines have the same width
+        assert_eq!(
+            2 * node_count - 1,
+            svg.matches("stroke-width=\"0.5\"").count()
+        );
+    }
+}
+
+#[test]
+fn json_drawer_writes_one_object_per_node() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+    let drawer = JsonDrawer::new();
+    let layouter = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap();
+    let json = layouter.write_to_string().unwrap();
+
+    let nodes: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let nodes = nodes.as_array().unwrap();
+    assert_eq!(layouter.embedding().len(), nodes.len());
+    for (node, data) in nodes.iter().zip(layouter.embedding().iter()) {
+        assert_eq!(data.ord, node["ord"].as_u64().unwrap() as usize);
+        assert_eq!(data.parent, node["parent"].as_u64().map(|p| p as usize));
+        assert_eq!(data.text, node["text"].as_str().unwrap());
+        assert_eq!(data.x_center, node["x_center"].as_u64().unwrap() as usize);
+        assert_eq!(data.x_extent, node["x_extent"].as_u64().unwrap() as usize);
+        assert_eq!(data.y_order, node["y_order"].as_u64().unwrap() as usize);
+        assert_eq!(data.is_emphasized, node["emphasized"].as_bool().unwrap());
+    }
+}
+
+#[test]
+fn json_drawer_escapes_labels() {
+    let mut tree = Builder::new();
+    tree.token("say \"hi\"\\\n\u{1}", 1).unwrap();
+    let tree = tree.build().unwrap();
+    let json = Layouter::new(&tree)
+        .with_drawer(&JsonDrawer::new())
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let nodes: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!("say \"hi\"\\\n\u{1}", nodes[0]["text"]);
+}
+
+#[test]
+fn json_drawer_streams_nodes() {
+    let tree = more_complex_tree();
+    let layouter = Layouter::new(&tree).with_drawer(&JsonDrawer);
+    let mut streamed = Vec::new();
+    JsonDrawer::new()
+        .draw_nodes_to_writer(
+            &mut streamed,
+            layouter.embed_streaming_with_visualize().unwrap(),
+        )
+        .unwrap();
+    let eager = layouter
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert_eq!(eager, String::from_utf8(streamed).unwrap());
+
+    let mut empty = Vec::new();
+    JsonDrawer::new()
+        .draw_nodes_to_writer(&mut empty, std::iter::empty())
+        .unwrap();
+    let nodes: serde_json::Value = serde_json::from_slice(&empty).unwrap();
+    assert!(nodes.as_array().unwrap().is_empty());
+}
+
+#[test]
+fn tikz_drawer_emits_nodes_and_edges() {
+    let tree = more_complex_tree();
+    let layouter = Layouter::new(&tree)
+        .with_drawer(&TikzDrawer)
+        .embed_with_visualize()
+        .unwrap();
+    let tikz = layouter.write_to_string().unwrap();
+    assert!(tikz.starts_with("\\begin{tikzpicture}"));
+    assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+
+    let node_count = tree.walk().count();
+    assert_eq!(node_count, tikz.matches("\\node[").count());
+    assert_eq!(node_count - 1, tikz.matches("\\draw (").count());
+    let root = &layouter.embedding()[0];
+    assert!(tikz.contains(&format!("(n0) at ({}, -0) {{0}};", root.x_center)));
+    // The node with the data 2 is emphasized
+    assert_eq!(1, tikz.matches("font=\\bfseries").count());
+}
+
+#[test]
+fn tikz_drawer_escapes_labels() {
+    let mut tree = Builder::new();
+    tree.open("a_b 50% & #1").unwrap();
+    tree.token("$x$\ny", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let tikz = Layouter::new(&tree)
+        .with_drawer(&TikzDrawer)
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(tikz.contains("{a\\_b 50\\% \\& \\#1};"));
+    assert!(tikz.contains("[draw, align=center] (n1) at"));
+    assert!(tikz.contains("{\\$x\\$\\\\y};"));
+}
+
+#[test]
+fn svg_drawer_applies_layer_spacing_fn() {
+    let tree = more_complex_tree();
+    let layer_ys = |drawer: SvgDrawer| {
+        let svg = Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        let mut ys = attribute_values(&svg, "text", "y");
+        ys.sort_by(f32::total_cmp);
+        ys.dedup();
+        ys
+    };
+
+    let uniform = layer_ys(SvgDrawer::new().with_layer_spacing_fn(|_| 10.0));
+    let spaced =
+        layer_ys(SvgDrawer::new().with_layer_spacing_fn(
+            |depth| {
+                if depth == 1 {
+                    50.0
+                } else {
+                    10.0
+                }
+            },
+        ));
+    assert_eq!(3, uniform.len());
+    // Each layer of single lines spans 1.5 lines of 10 pixels
+    assert_eq!(25.0, uniform[1] - uniform[0]);
+    assert_eq!(25.0, uniform[2] - uniform[1]);
+    // Only the layers below layer 1 are moved
+    assert_eq!(uniform[..2], spaced[..2]);
+    assert_eq!(uniform[2] + 40.0, spaced[2]);
+}
+
+#[test]
+fn svg_drawer_applies_capturing_layer_spacing_fn() {
+    let tree = more_complex_tree();
+    let spacings = [30.0, 10.0];
+    let drawer = SvgDrawer::new()
+        .with_layer_spacing_fn(move |depth| spacings.get(depth).copied().unwrap_or_default());
+    // The drawer stays cloneable with the captured state
+    let drawer = drawer.clone();
+    let svg = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let mut ys = attribute_values(&svg, "text", "y");
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+    // Each layer of single lines spans 1.5 lines of 10 pixels
+    assert_eq!(
+        vec![15.0 + 30.0, 15.0 + 10.0],
+        vec![ys[1] - ys[0], ys[2] - ys[1]]
+    );
+}
+
+#[test]
+fn graphml_drawer_emits_nodes_and_edges() {
+    let mut tree = Builder::new();
+    tree.open("<expr> & \"more\"").unwrap();
+    tree.token("a", 1).unwrap();
+    tree.token("b", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+    let drawer = GraphMlDrawer::new();
+    let layouter = Layouter::new(&tree).with_drawer(&drawer).embed().unwrap();
+    let graphml = layouter.write_to_string().unwrap();
+
+    let document = roxmltree::Document::parse(&graphml).unwrap();
+    let elements = |name| {
+        document
+            .descendants()
+            .filter(|n| n.has_tag_name(name))
+            .collect::<Vec<_>>()
+    };
+    let nodes = elements("node");
+    assert_eq!(3, nodes.len());
+    assert_eq!(2, elements("edge").len());
+    let label = nodes[0]
+        .children()
+        .find(|n| n.attribute("key") == Some("label"))
+        .and_then(|n| n.text());
+    assert_eq!(Some("<expr> & \"more\""), label);
+    let x = nodes[0]
+        .children()
+        .find(|n| n.attribute("key") == Some("x"))
+        .and_then(|n| n.text());
+    assert_eq!(
+        Some(layouter.embedding()[0].x_center.to_string().as_str()),
+        x
+    );
+    assert_eq!(Some("n0"), elements("edge")[1].attribute("source"));
+    assert_eq!(Some("n2"), elements("edge")[1].attribute("target"));
+}
+
+#[test]
+fn svg_drawer_sets_and_embeds_fonts() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let styles = |svg: &str| {
+        let document = roxmltree::Document::parse(svg).unwrap();
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name("text"))
+            .map(|node| node.attribute("style").unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let svg = render(SvgDrawer::new());
+    assert!(styles(&svg)[0].starts_with("font-family: 'Courier'"));
+    assert!(!svg.contains("@font-face"));
+
+    let svg = render(SvgDrawer::new().with_font_family("Fira Mono"));
+    let styles = styles(&svg);
+    assert_eq!(svg.matches("<text ").count(), styles.len());
+    assert!(styles
+        .iter()
+        .all(|style| style.starts_with("font-family: 'Fira Mono'")));
+
+    let svg = render(
+        SvgDrawer::new()
+            .with_font_family("Fira Mono")
+            .with_embedded_font(b"wOF2font"),
+    );
+    assert!(svg.contains(
+        "<style><![CDATA[@font-face { font-family: 'Fira Mono'; \
+         src: url(data:font/woff2;base64,d09GMmZvbnQ=); }]]></style>"
+    ));
+}
+
+#[test]
+fn svg_drawer_quotes_font_family() {
+    let tree = more_complex_tree();
+    let svg = Layouter::new(&tree)
+        .with_drawer(
+            &SvgDrawer::new()
+                .with_font_family("It's\" onload=\"]]>")
+                .with_embedded_font(b"wOF2font"),
+        )
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    assert!(document
+        .descendants()
+        .all(|node| node.attribute("onload").is_none()));
+    let family = "font-family: 'It\\'s\" onload=\"]]\\3e '";
+    let style = document
+        .descendants()
+        .find(|node| node.has_tag_name("style"))
+        .and_then(|node| node.text())
+        .unwrap();
+    assert!(style.starts_with(&format!("@font-face {{ {};", family)));
+    assert!(document
+        .descendants()
+        .filter(|node| node.has_tag_name("text"))
+        .all(|node| node.attribute("style").unwrap().starts_with(family)));
+}
+
+#[test]
+fn svg_drawer_draws_grid() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(&drawer.with_edge_style(EdgeStyle::Orthogonal))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let svg = render(SvgDrawer::new());
+    assert!(!svg.contains("<line "));
+    assert!(!svg.contains("id=\"grid\""));
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let (width, layers) = layouter.embedding().dimensions();
+    let svg = render(SvgDrawer::new().with_grid(2));
+    let grid_start = svg.find("<g id=\"grid\"").unwrap();
+    let grid = &svg[grid_start..grid_start + svg[grid_start..].find("</g>").unwrap()];
+    assert_eq!(
+        width / 2 + 1 + layers.div_ceil(2),
+        grid.matches("<line ").count()
+    );
+    assert_eq!(
+        svg.matches("<line ").count(),
+        grid.matches("<line ").count()
+    );
+    assert!(!grid.contains("<text "));
+    // Behind the nodes and edges
+    assert!(grid_start < svg.find("<text ").unwrap());
+    assert!(grid_start < svg.find("<path ").unwrap());
+
+    let svg = render(SvgDrawer::new().with_grid(2).with_grid_labels(true));
+    let grid_start = svg.find("<g id=\"grid\"").unwrap();
+    let grid = &svg[grid_start..grid_start + svg[grid_start..].find("</g>").unwrap()];
+    assert_eq!(
+        grid.matches("<line ").count(),
+        grid.matches("<text ").count()
+    );
+}
+
+#[test]
+fn svg_drawer_emphasizes_runtime_selected_ids() {
+    let mut tree = Builder::new();
+    tree.open("root").unwrap();
+    let first = tree.token("first", 1).unwrap();
+    tree.token("second", 1).unwrap();
+    let third = tree.token("third", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_emphasized_ids(&HashSet::from([first, third]))
+        .embed()
+        .unwrap();
+    let emphasized = layouter
+        .embedding()
+        .iter()
+        .filter(|e| e.is_emphasized)
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["first", "third"], emphasized);
+
+    let svg = layouter.write_to_string().unwrap();
+    assert_eq!(2, svg.matches("font-weight: bold").count());
+}
+
+#[test]
+fn svg_drawer_starts_edges_at_the_node_outlines() {
+    let mut tree = Builder::new();
+    tree.open("parent").unwrap();
+    tree.token("child", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+    let render = |shape| {
+        Layouter::new(&tree)
+            .with_drawer(&SvgDrawer::new().with_node_shape(shape))
+            .embed()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+
+    let svg = render(NodeShape::Rect);
+    let (ys, heights) = (
+        attribute_values(&svg, "rect", "y"),
+        attribute_values(&svg, "rect", "height"),
+    );
+    // The first rect is the background
+    let (parent_bottom, child_top) = (ys[1] + heights[1], ys[2]);
+    let (y1, y2) = (
+        attribute_values(&svg, "line", "y1")[0],
+        attribute_values(&svg, "line", "y2")[0],
+    );
+    assert!(close(parent_bottom, y1), "{parent_bottom} != {y1}");
+    assert!(close(child_top, y2), "{child_top} != {y2}");
+    let parent_center = ys[1] + heights[1] / 2.0;
+    assert!(y1 > parent_center);
+
+    let svg = render(NodeShape::Ellipse);
+    let (cys, rys) = (
+        attribute_values(&svg, "ellipse", "cy"),
+        attribute_values(&svg, "ellipse", "ry"),
+    );
+    let (y1, y2) = (
+        attribute_values(&svg, "line", "y1")[0],
+        attribute_values(&svg, "line", "y2")[0],
+    );
+    assert!(close(cys[0] + rys[0], y1), "{} != {y1}", cys[0] + rys[0]);
+    assert!(close(cys[1] - rys[1], y2), "{} != {y2}", cys[1] - rys[1]);
+}
+
+#[test]
+fn plantuml_drawer_emits_relations() {
+    let tree = more_complex_tree();
+    let plantuml = Layouter::new(&tree)
+        .with_drawer(&PlantUmlDrawer::new())
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let lines = plantuml.lines().collect::<Vec<_>>();
+    assert_eq!(Some(&"@startuml"), lines.first());
+    assert_eq!(Some(&"@enduml"), lines.last());
+    assert!(!lines.contains(&"left to right direction"));
+
+    let nodes = lines.iter().filter(|l| l.starts_with("rectangle ")).count();
+    assert_eq!(5, nodes);
+    // Only the node with the data 2 is emphasized, which has the ord 4
+    assert!(lines.contains(&"rectangle \"<b>2</b>\" as n4"));
+    assert!(lines.contains(&"rectangle \"0\" as n0"));
+
+    let mut edges = lines
+        .iter()
+        .filter_map(|l| l.split_once(" --> "))
+        .collect::<Vec<_>>();
+    edges.sort();
+    assert_eq!(
+        vec![("n0", "n1"), ("n0", "n4"), ("n1", "n2"), ("n1", "n3")],
+        edges
+    );
+}
+
+#[test]
+fn plantuml_drawer_escapes_labels() {
+    let mut tree = Builder::new();
+    tree.open("say \"<hi>\"").unwrap();
+    tree.token("a\nb\\c", 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let plantuml = Layouter::new(&tree)
+        .with_direction(LayoutDirection::LeftToRight)
+        .with_drawer(&PlantUmlDrawer::new())
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(plantuml.starts_with("@startuml\nleft to right direction\n"));
+    assert!(plantuml.contains("rectangle \"say <U+0022><U+003C>hi<U+003E><U+0022>\" as n0"));
+    assert!(plantuml.contains("rectangle \"a\\nb<U+005C>c\" as n1"));
+}
+
+#[test]
+fn svg_drawer_emits_fragments() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let standalone = render(SvgDrawer::new());
+    assert!(standalone.starts_with("<?xml"));
+    assert!(standalone.contains("<svg "));
+
+    let fragment = render(SvgDrawer::new().with_fragment_mode(true));
+    assert!(fragment.starts_with("<g"));
+    assert!(!fragment.contains("<?xml"));
+    assert!(!fragment.contains("<svg"));
+    assert!(!fragment.contains("xmlns"));
+    // The same nodes and edges, including the background spanning the image
+    assert_eq!(
+        attribute_values(&standalone, "rect", "width"),
+        attribute_values(&fragment, "rect", "width")
+    );
+    assert_eq!(
+        standalone.matches("<text ").count(),
+        fragment.matches("<text ").count()
+    );
+
+    // The fragment can be inserted into another document
+    let host = format!("<svg xmlns=\"http://www.w3.org/2000/svg\">{fragment}</svg>");
+    let document = roxmltree::Document::parse(&host).unwrap();
+    let group = document.root_element().first_element_child().unwrap();
+    assert_eq!("g", group.tag_name().name());
+    assert_eq!(None, group.next_sibling_element());
+}
+
+#[test]
+fn svg_drawer_wraps_the_drawing_in_a_symbol() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let sprite = render(
+        SvgDrawer::new()
+            .with_fragment_mode(true)
+            .with_symbol_id("tree"),
+    );
+    assert!(sprite.starts_with("<symbol"));
+    let host = format!("<svg xmlns=\"http://www.w3.org/2000/svg\">{sprite}</svg>");
+    let document = roxmltree::Document::parse(&host).unwrap();
+    let symbol = document.root_element().first_element_child().unwrap();
+    assert_eq!("symbol", symbol.tag_name().name());
+    assert_eq!(Some("tree"), symbol.attribute("id"));
+    assert!(symbol.attribute("viewBox").is_some());
+    // The symbol wraps the group with the nodes
+    let group = symbol.first_element_child().unwrap();
+    assert_eq!("g", group.tag_name().name());
+    assert_eq!(None, group.next_sibling_element());
+    assert_eq!(
+        tree.len(),
+        group
+            .descendants()
+            .filter(|node| node.has_tag_name("text"))
+            .count()
+    );
+
+    // A standalone document shows the symbol
+    let standalone = render(SvgDrawer::new().with_symbol_id("tree"));
+    let document = roxmltree::Document::parse(&standalone).unwrap();
+    let svg = document.root_element();
+    let symbol = svg.first_element_child().unwrap();
+    assert_eq!(Some("tree"), symbol.attribute("id"));
+    assert_eq!(svg.attribute("viewBox"), symbol.attribute("viewBox"));
+    let usage = symbol.next_sibling_element().unwrap();
+    assert_eq!("use", usage.tag_name().name());
+    assert_eq!(Some("#tree"), usage.attribute("href"));
+}
+
+#[test]
+fn svg_drawer_grows_layers_with_their_line_counts() {
+    let layer_ys = |label: &'static str| {
+        let mut tree = Builder::new();
+        tree.open("root").unwrap();
+        tree.open(label).unwrap();
+        tree.token("leaf", 1).unwrap();
+        tree.close().unwrap();
+        tree.close().unwrap();
+        let tree = tree.build().unwrap();
+        let layouter = Layouter::new(&tree).embed().unwrap();
+        assert_eq!(
+            label.lines().count(),
+            layouter.embedding().layer_heights()[1]
+        );
+        let svg = layouter.write_to_string().unwrap();
+        attribute_values(&svg, "text", "y")
+    };
+
+    let flat = layer_ys("inner");
+    let tall = layer_ys("three\nline\nlabel");
+    // The default spacing of two lines plus the single line of the first layer
+    let unit_height = (flat[1] - flat[0]) / 3.0;
+    assert_eq!(flat[1] - flat[0], flat[2] - flat[1]);
+    assert_eq!(flat[1], tall[1]);
+    // Only the layer below the tall label moves, by the two additional lines
+    assert_eq!(flat[2] + 2.0 * unit_height, tall[2]);
+}
+
+#[test]
+fn svg_drawer_draws_radial_layouts() {
+    // A root with three children of two leaves each
+    let mut tree = Builder::new();
+    tree.open("root").unwrap();
+    for child in ["a", "b", "c"] {
+        tree.open(child).unwrap();
+        tree.token("x", 1).unwrap();
+        tree.token("y", 1).unwrap();
+        tree.close().unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let svg = Layouter::new(&tree)
+        .with_layout_style(LayoutStyle::Radial)
+        .with_drawer(
+            &SvgDrawer::new()
+                .with_node_shape(NodeShape::Ellipse)
+                .with_edge_style(EdgeStyle::Orthogonal),
+        )
+        .embed()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let (width, height) = (
+        attribute_values(&svg, "svg", "width")[0],
+        attribute_values(&svg, "svg", "height")[0],
+    );
+    let centers = attribute_values(&svg, "ellipse", "cx")
+        .into_iter()
+        .zip(attribute_values(&svg, "ellipse", "cy"))
+        .collect::<Vec<_>>();
+    assert_eq!(10, centers.len());
+
+    // The root is at the center of the image
+    let (cx, cy) = centers[0];
+    assert!((cx - width / 2.0).abs() < 1e-3, "{cx} {width}");
+    assert!((cy - height / 2.0).abs() < 1e-3, "{cy} {height}");
+
+    // The nodes of each layer are on a ring around it, the leaves evenly spread
+    let distance = |(x, y): (f32, f32)| ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
+    let inner = [1, 4, 7].map(|i| distance(centers[i]));
+    let leaves = [2, 3, 5, 6, 8, 9].map(|i| distance(centers[i]));
+    assert!(inner.iter().all(|r| (r - inner[0]).abs() < 1e-2));
+    assert!(leaves.iter().all(|r| (r - leaves[0]).abs() < 1e-2));
+    assert!(leaves[0] > inner[0] + 1.0);
+    let chord = |i: usize, j: usize| {
+        let ((x1, y1), (x2, y2)) = (centers[i], centers[j]);
+        ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt()
+    };
+    let chords = [(2, 3), (3, 5), (5, 6), (6, 8), (8, 9), (9, 2)].map(|(i, j)| chord(i, j));
+    assert!(
+        chords.iter().all(|c| (c - chords[0]).abs() < 1e-2),
+        "{chords:?}"
+    );
+
+    // The edges are radial segments, which end outside the nodes
+    assert!(!svg.contains("<path "));
+    let starts = attribute_values(&svg, "line", "x1")
+        .into_iter()
+        .zip(attribute_values(&svg, "line", "y1"))
+        .collect::<Vec<_>>();
+    assert_eq!(9, starts.len());
+    assert!(starts.iter().all(|start| distance(*start) > 0.0));
+}
+
+#[test]
+fn svg_drawer_sizes_images_in_physical_units() {
+    let tree = more_complex_tree();
+    let render = |unit| {
+        Layouter::new(&tree)
+            .with_drawer(&SvgDrawer::new().with_units(unit))
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let attribute = |svg: &str, attribute: &str| {
+        let key = format!(" {attribute}=\"");
+        let start = svg.find(&key).unwrap() + key.len();
+        svg[start..start + svg[start..].find('"').unwrap()].to_string()
+    };
+
+    let px = render(Unit::Px);
+    let view_box = attribute(&px, "viewBox");
+    let px_width: f32 = attribute(&px, "width").parse().unwrap();
+    assert!(view_box.ends_with(&format!(" {}", attribute(&px, "height"))));
+
+    let mm = render(Unit::Mm);
+    let width = attribute(&mm, "width");
+    assert!(width.ends_with("mm"), "{width}");
+    let mm_width: f32 = width.trim_end_matches("mm").parse().unwrap();
+    assert!((mm_width - px_width * 25.4 / 96.0).abs() < 1e-3);
+    assert!(attribute(&mm, "height").ends_with("mm"));
+    // The drawing itself is unchanged
+    assert_eq!(view_box, attribute(&mm, "viewBox"));
+    assert_eq!(
+        attribute_values(&px, "text", "x"),
+        attribute_values(&mm, "text", "x")
+    );
+
+    let pt = render(Unit::Pt);
+    let pt_width: f32 = attribute(&pt, "width")
+        .trim_end_matches("pt")
+        .parse()
+        .unwrap();
+    assert!((pt_width - px_width * 0.75).abs() < 1e-3);
+}
+
+#[test]
+fn svg_drawer_rounds_coordinates() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        let drawer = drawer
+            .with_unit_width(7.3)
+            .with_node_shape(NodeShape::Ellipse)
+            .with_arrowheads(true);
+        Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    // All attribute values that consist of numbers, like coordinates, sizes and path data,
+    // except for the versions of XML and SVG
+    let numbers = |svg: &str| {
+        svg[svg.find("<svg").unwrap()..]
+            .replace(" version=\"1.1\"", "")
+            .split('"')
+            .skip(1)
+            .step_by(2)
+            .flat_map(|value| value.split_whitespace())
+            .filter(|token| token.parse::<f32>().is_ok())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    let full = render(SvgDrawer::new().with_edge_style(EdgeStyle::Curved));
+    assert!(numbers(&full).iter().any(|n| n.contains('.')));
+
+    for edge_style in [
+        EdgeStyle::Straight,
+        EdgeStyle::Orthogonal,
+        EdgeStyle::Curved,
+    ] {
+        let svg = render(
+            SvgDrawer::new()
+                .with_edge_style(edge_style)
+                .with_coordinate_precision(0),
+        );
+        let numbers = numbers(&svg);
+        assert!(numbers.len() > 20);
+        assert!(numbers.iter().all(|n| !n.contains('.')), "{numbers:?}");
+    }
+
+    let rounded = render(
+        SvgDrawer::new()
+            .with_edge_style(EdgeStyle::Curved)
+            .with_coordinate_precision(2),
+    );
+    let (full, rounded) = (numbers(&full), numbers(&rounded));
+    assert_eq!(full.len(), rounded.len());
+    for (full, rounded) in full.iter().zip(&rounded) {
+        let decimals = rounded.split('.').nth(1).map_or(0, str::len);
+        assert!(decimals <= 2, "{rounded}");
+        let (full, rounded) = (
+            full.parse::<f32>().unwrap(),
+            rounded.parse::<f32>().unwrap(),
+        );
+        assert!((full - rounded).abs() <= 0.005 + 1e-4, "{full} {rounded}");
+    }
+}
+
+#[test]
+fn svg_drawer_labels_edges_at_their_midpoints() {
+    struct Derivation(&'static str, Option<&'static str>);
+    impl Visualize for Derivation {
+        fn visualize(&self) -> String {
+            self.0.to_string()
+        }
+        fn edge_label(&self) -> Option<String> {
+            self.1.map(str::to_string)
+        }
+    }
+
+    //     expr
+    //     /  \
+    //  term   op
+    let mut tree = Builder::new();
+    tree.open(Derivation("expr", None)).unwrap();
+    tree.token(Derivation("term", Some("alt 2")), 1).unwrap();
+    tree.token(Derivation("op", None), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    let labels = embedding
+        .iter()
+        .map(|e| e.edge_label.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![None, Some("alt 2"), None], labels);
+
+    let coordinate = |node: roxmltree::Node, attr: &str| -> f32 {
+        node.attribute(attr).unwrap().parse().unwrap()
+    };
+    for edge_style in [EdgeStyle::Straight, EdgeStyle::Orthogonal] {
+        let mut svg = Vec::new();
+        SvgDrawer::new()
+            .with_edge_style(edge_style)
+            .draw_to_writer(&mut svg, embedding)
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let label = document
+            .descendants()
+            .filter(|n| n.has_tag_name("text"))
+            .find(|n| n.text() == Some("alt 2"))
+            .unwrap();
+        let (x, y) = (coordinate(label, "x"), coordinate(label, "y"));
+
+        // The label's edge is the first one, the edge to "op" isn't labeled
+        let midpoint = match edge_style {
+            EdgeStyle::Straight => {
+                let line = document
+                    .descendants()
+                    .find(|n| n.has_tag_name("line"))
+                    .unwrap();
+                (
+                    (coordinate(line, "x1") + coordinate(line, "x2")) / 2.0,
+                    (coordinate(line, "y1") + coordinate(line, "y2")) / 2.0,
+                )
+            }
+            _ => {
+                // M x1 y1 V bend H x2 V y2
+                let path = document
+                    .descendants()
+                    .find(|n| n.has_tag_name("path"))
+                    .unwrap();
+                let d = path
+                    .attribute("d")
+                    .unwrap()
+                    .split_whitespace()
+                    .filter_map(|token| token.parse::<f32>().ok())
+                    .collect::<Vec<_>>();
+                ((d[0] + d[3]) / 2.0, d[2])
+            }
+        };
+        assert!(
+            (x - midpoint.0).abs() < 0.01,
+            "{:?}: {} {:?}",
+            edge_style,
+            x,
+            midpoint
+        );
+        assert!(
+            (y - midpoint.1).abs() < 0.01,
+            "{:?}: {} {:?}",
+            edge_style,
+            y,
+            midpoint
+        );
+        assert_eq!(4, svg.matches("<text").count());
+    }
+}
+
+#[test]
+fn layouter_uses_a_boxed_drawer_chosen_at_runtime() {
+    let tree = more_complex_tree();
+    for (file_name, prefix) in [
+        ("boxed_drawer.svg", "<?xml"),
+        ("boxed_drawer.dot", "digraph {"),
+        ("boxed_drawer.mmd", "graph TD"),
+        ("boxed_drawer.txt", "┌"),
+    ] {
+        let path = output_path(file_name);
+        let drawer: Box<dyn Drawer> = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("svg") => Box::new(SvgDrawer::new()),
+            Some("dot") => Box::new(DotDrawer::new()),
+            Some("mmd") => Box::new(MermaidDrawer::new()),
+            _ => Box::new(TextDrawer::new()),
+        };
+        Layouter::new(&tree)
+            .with_boxed_drawer(drawer)
+            .with_file_path(&path)
+            .embed_with_visualize()
+            .unwrap()
+            .write()
+            .unwrap();
+        let output = std::fs::read_to_string(&path).unwrap();
+        assert!(output.trim_start().starts_with(prefix), "{}", output);
+    }
+
+    // A borrowed drawer replaces the owned one
+    let drawer = DotDrawer::new();
+    let output = Layouter::new(&tree)
+        .with_boxed_drawer(Box::new(SvgDrawer::new()))
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(output.starts_with("digraph {"));
+}
+
+#[test]
+fn svg_drawer_places_the_drawing_on_the_canvas() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        let svg = Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let root = document.root_element();
+        let size = |attr: &str| root.attribute(attr).unwrap().parse::<f32>().unwrap();
+        let transform = document
+            .descendants()
+            .find_map(|n| n.attribute("transform"))
+            .map(str::to_string);
+        (
+            (size("width"), size("height")),
+            root.attribute("viewBox").unwrap().to_string(),
+            transform,
+        )
+    };
+    let ((width, height), _, transform) = render(SvgDrawer::new());
+    assert_eq!(None, transform);
+
+    // A small tree is centered on a larger canvas
+    let ((canvas_width, canvas_height), view_box, transform) =
+        render(SvgDrawer::new().with_canvas_size(1000.0, 800.0));
+    assert_eq!((1000.0, 800.0), (canvas_width, canvas_height));
+    assert_eq!("0 0 1000 800", view_box);
+    assert_eq!(
+        Some(format!(
+            "translate({} {}) scale(1)",
+            (1000.0 - width) / 2.0,
+            (800.0 - height) / 2.0
+        )),
+        transform
+    );
+
+    let (_, _, transform) = render(
+        SvgDrawer::new()
+            .with_canvas_size(1000.0, 800.0)
+            .with_alignment(Align::TopLeft),
+    );
+    assert_eq!(Some("translate(0 0) scale(1)".to_string()), transform);
+    let (_, _, transform) = render(
+        SvgDrawer::new()
+            .with_canvas_size(1000.0, 800.0)
+            .with_alignment(Align::BottomRight),
+    );
+    assert_eq!(
+        Some(format!(
+            "translate({} {}) scale(1)",
+            1000.0 - width,
+            800.0 - height
+        )),
+        transform
+    );
+
+    // Too large drawings are scaled down to the canvas or cut off
+    let (_, _, transform) = render(
+        SvgDrawer::new()
+            .with_canvas_size(width / 2.0, height)
+            .with_alignment(Align::TopLeft),
+    );
+    assert_eq!(Some("translate(0 0) scale(0.5)".to_string()), transform);
+    let (_, _, transform) = render(
+        SvgDrawer::new()
+            .with_canvas_size(width / 2.0, height)
+            .with_canvas_overflow(CanvasOverflow::Clip),
+    );
+    assert_eq!(
+        Some(format!("translate({} 0) scale(1)", -width / 4.0)),
+        transform
+    );
+}
+
+#[test]
+fn svg_drawer_draws_wrapped_labels_as_lines() {
+    let mut tree = Builder::new();
+    tree.open(Label("the quick brown fox jumps over the lazy dog"))
+        .unwrap();
+    tree.token(Label("supercalifragilistic"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_label_wrap(10)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(
+        "the quick\nbrown fox\njumps over\nthe lazy\ndog",
+        embedding[0].text
+    );
+    assert_eq!(5, embedding[0].line_count);
+    assert_eq!(10 + 1, embedding[0].x_extent);
+    // Words wider than the limit are broken
+    assert_eq!("supercalif\nragilistic", embedding[1].text);
+
+    let svg = layouter.write_to_string().unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let lines = document
+        .descendants()
+        .filter(|n| n.has_tag_name("tspan"))
+        .map(|n| n.text().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(7, lines.len());
+    assert!(lines.iter().all(|line| line.chars().count() <= 10));
+    assert!(!svg.contains("…"));
+}
+
+#[test]
+fn svg_drawer_widens_boxes_to_the_min_node_width() {
+    let mut tree = Builder::new();
+    tree.open(Label("")).unwrap();
+    tree.token(Label("x"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let drawer = SvgDrawer::new()
+        .with_unit_width(10.0)
+        .with_node_shape(NodeShape::Rect);
+    for direction in [LayoutDirection::TopToBottom, LayoutDirection::LeftToRight] {
+        let svg = Layouter::new(&tree)
+            .with_direction(direction)
+            .with_min_node_width(3)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        // The minimum plus half a character of padding on either side, skipping the background
+        assert_eq!(
+            vec![40.0, 40.0],
+            attribute_values(&svg, "rect", "width")[1..].to_vec()
+        );
+    }
+}
+
+#[test]
+fn svg_drawer_fills_nodes_by_depth() {
+    let tree = more_complex_tree();
+    let drawer = SvgDrawer::new().with_depth_gradient("#000000", "#fff");
+    let svg = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let fills = document
+        .descendants()
+        .filter(|n| n.has_tag_name("rect"))
+        .skip(1)
+        .map(|n| n.attribute("fill").unwrap())
+        .collect::<Vec<_>>();
+    // The nodes 0, 1, 3 and 4 in the layers 0, 1, 2 and 2, the emphasized node 2 isn't filled
+    assert_eq!(vec!["#000000", "#808080", "#ffffff", "#ffffff"], fills);
+
+    let drawer = SvgDrawer::new().with_depth_gradient("black", "#fff");
+    let result = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string();
+    assert!(matches!(result, Err(LayouterError::Description(_))));
+}
+
+#[test]
+fn html_drawer_embeds_the_svg_with_pan_and_zoom() {
+    let tree = more_complex_tree();
+    let path = output_path("html_drawer.html");
+    let drawer = HtmlDrawer::new().with_title("Tree <1>");
+    let layouter = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .with_file_path(&path)
+        .embed_with_visualize()
+        .unwrap();
+    layouter.write().unwrap();
+    let html = std::fs::read_to_string(&path).unwrap();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<meta name=\"viewport\""));
+    assert!(html.contains("<title>Tree &lt;1&gt;</title>"));
+    // The page contains the svg of the layout without its XML prolog
+    let svg = SvgDrawer::new();
+    let mut expected = Vec::new();
+    svg.draw_to_writer(&mut expected, layouter.embedding())
+        .unwrap();
+    let expected = String::from_utf8(expected).unwrap();
+    let expected = &expected[expected.find("<svg").unwrap()..];
+    assert!(html.contains(expected));
+    assert!(!html.contains("<?xml"));
+    // The pan and zoom script, but no external resources
+    let script = &html[html.find("<script>").unwrap()..html.find("</script>").unwrap()];
+    assert!(script.contains("\"wheel\""));
+    assert!(script.contains("\"pointermove\""));
+    assert!(!html.contains("src="));
+    assert!(!html.contains("href="));
+}
+
+#[test]
+fn svg_drawer_adds_accessibility_attributes() {
+    let mut tree = Builder::new();
+    tree.open(Label("expr & <term>")).unwrap();
+    tree.token(Label("two\nlines"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let drawer = SvgDrawer::new().with_accessibility(true);
+    let svg = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let root = document.root_element();
+    assert_eq!(Some("img"), root.attribute("role"));
+    assert_eq!(Some("Tree with 2 nodes"), root.attribute("aria-label"));
+    let labels = document
+        .descendants()
+        .filter(|n| n.has_tag_name("g"))
+        .filter_map(|n| n.attribute("aria-label"))
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["expr & <term>", "two lines"], labels);
+
+    // Without accessibility the output doesn't change
+    let svg = Layouter::new(&tree)
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(!svg.contains("role="));
+    assert!(!svg.contains("aria-label"));
+}
+
+#[test]
+fn layouter_chooses_the_drawer_by_extension() {
+    let tree = more_complex_tree();
+    let write = |file_name: &str, drawer: Option<&dyn Drawer>| {
+        let path = output_path(file_name);
+        let mut layouter = Layouter::new(&tree)
+            .with_file_path(&path)
+            .embed_with_visualize()
+            .unwrap();
+        if let Some(drawer) = drawer {
+            layouter.set_drawer(drawer);
+        }
+        layouter.write().unwrap();
+        std::fs::read_to_string(&path).unwrap()
+    };
+    assert!(write("out.dot", None).starts_with("digraph {"));
+    assert!(write("out.JSON", None).starts_with('['));
+    assert!(write("out.mmd", None).starts_with("graph TD"));
+    assert!(write("out.html", None).starts_with("<!DOCTYPE html>"));
+    assert!(write("out.svg", None).contains("<svg"));
+    // Unknown extensions and files without one are written as SVG
+    assert!(write("out.unknown", None).contains("<svg"));
+    assert!(write("out", None).contains("<svg"));
+    // An explicitly set drawer takes precedence
+    let drawer = MermaidDrawer::new();
+    assert!(write("explicit.dot", Some(&drawer)).starts_with("graph TD"));
+}
+
+#[test]
+fn svg_drawer_combines_embeddings() {
+    let first = more_complex_tree();
+    let mut second = Builder::new();
+    second.open(Label("top")).unwrap();
+    second.token(Label("bottom"), 1).unwrap();
+    second.close().unwrap();
+    let second = second.build().unwrap();
+    let first = Layouter::new(&first).embed_with_visualize().unwrap();
+    let second = Layouter::new(&second).embed_with_visualize().unwrap();
+    let embeddings = [
+        ("First <tree>", first.embedding()),
+        ("Second", second.embedding()),
+    ];
+
+    for (columns, expect_side_by_side) in [(2, true), (1, false)] {
+        let mut buffer = Vec::new();
+        SvgDrawer::new()
+            .with_arrowheads(true)
+            .with_grid(2)
+            .with_layer_bands(true)
+            .with_embedded_font(b"wOF2font")
+            .draw_combined_to_writer(&mut buffer, &embeddings, columns)
+            .unwrap();
+        let svg = String::from_utf8(buffer).unwrap();
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        // The ids are unique and the cells share the font and the marker of the document
+        let ids = document
+            .descendants()
+            .filter_map(|n| n.attribute("id"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids.len(),
+            ids.iter().collect::<HashSet<_>>().len(),
+            "{:?}",
+            ids
+        );
+        assert!(ids.contains(&"cell0-grid") && ids.contains(&"cell1-grid"));
+        assert_eq!(1, svg.matches("@font-face").count());
+        assert_eq!(1, svg.matches("<marker ").count());
+        let marker = document
+            .descendants()
+            .find(|n| n.has_tag_name("marker"))
+            .unwrap();
+        let marker_end = format!("url(#{})", marker.attribute("id").unwrap());
+        let marker_ends = document
+            .descendants()
+            .filter_map(|n| n.attribute("marker-end"))
+            .collect::<Vec<_>>();
+        // The four edges of the first tree and the one of the second
+        assert_eq!(vec![marker_end.as_str(); 5], marker_ends);
+        let texts = document
+            .descendants()
+            .filter(|n| n.has_tag_name("text"))
+            .filter_map(|n| n.text())
+            .collect::<Vec<_>>();
+        for text in ["First <tree>", "Second", "0", "4", "top", "bottom"] {
+            assert!(texts.contains(&text), "{} is missing", text);
+        }
+        // Each embedding is translated into its own cell
+        let translations = document
+            .root_element()
+            .children()
+            .filter_map(|n| n.attribute("transform"))
+            .map(|transform| {
+                let numbers = transform
+                    .trim_start_matches("translate(")
+                    .trim_end_matches(')')
+                    .split(' ')
+                    .map(|n| n.parse::<f32>().unwrap())
+                    .collect::<Vec<_>>();
+                (numbers[0], numbers[1])
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(2, translations.len());
+        let (first, second) = (translations[0], translations[1]);
+        if expect_side_by_side {
+            assert!(first.0 < second.0);
+            assert_eq!(first.1, second.1);
+        } else {
+            assert_eq!(first.0, second.0);
+            assert!(first.1 < second.1);
+        }
+    }
+}
+
+#[test]
+fn svg_drawer_centers_padded_labels() {
+    let mut tree = Builder::new();
+    tree.open(Label("a")).unwrap();
+    tree.token(Label("bb"), 1).unwrap();
+    tree.token(Label("ccc"), 1).unwrap();
+    tree.token(Label("dddd"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    for direction in [LayoutDirection::TopToBottom, LayoutDirection::LeftToRight] {
+        for padding in [0, 1, 2] {
+            let svg = Layouter::new(&tree)
+                .with_direction(direction)
+                .with_horizontal_padding(padding)
+                .with_drawer(&SvgDrawer::new().with_node_shape(NodeShape::Rect))
+                .embed_with_visualize()
+                .unwrap()
+                .write_to_string()
+                .unwrap();
+            let document = roxmltree::Document::parse(&svg).unwrap();
+            let number = |node: roxmltree::Node, attr: &str| -> f32 {
+                node.attribute(attr).unwrap().parse().unwrap()
+            };
+            // The background is the first rectangle
+            let boxes = document
+                .descendants()
+                .filter(|n| n.has_tag_name("rect"))
+                .skip(1)
+                .collect::<Vec<_>>();
+            let texts = document
+                .descendants()
+                .filter(|n| n.has_tag_name("text"))
+                .collect::<Vec<_>>();
+            assert_eq!(4, boxes.len());
+            for (rect, text) in boxes.into_iter().zip(texts) {
+                let label_width = text.text().unwrap().chars().count() as f32 * 10.0;
+                let label_center = number(text, "x") + label_width / 2.0;
+                let box_width = number(rect, "width");
+                assert_eq!(label_center, number(rect, "x") + box_width / 2.0);
+                assert_eq!(label_width + (2 * padding + 1) as f32 * 10.0, box_width);
+            }
+        }
+    }
+}
+
+#[test]
+fn svg_drawer_draws_layer_bands() {
+    let tree = more_complex_tree();
+    for direction in [LayoutDirection::TopToBottom, LayoutDirection::LeftToRight] {
+        let draw = |drawer: SvgDrawer| {
+            Layouter::new(&tree)
+                .with_direction(direction)
+                .with_drawer(&drawer)
+                .embed_with_visualize()
+                .unwrap()
+                .write_to_string()
+                .unwrap()
+        };
+        assert!(!draw(SvgDrawer::new()).contains("layer-bands"));
+
+        let svg = draw(SvgDrawer::new().with_layer_bands(true));
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let root = document.root_element();
+        let bands = root
+            .children()
+            .find(|n| n.attribute("id") == Some("layer-bands"))
+            .unwrap();
+        let number = |node: roxmltree::Node, attr: &str| -> f32 {
+            node.attribute(attr).unwrap().parse().unwrap()
+        };
+        let (position, size, image_size) = match direction {
+            LayoutDirection::TopToBottom => ("y", "height", number(root, "height")),
+            LayoutDirection::LeftToRight => ("x", "width", number(root, "width")),
+        };
+        // One band per layer, which together cover the image without gaps
+        let spans = bands
+            .children()
+            .filter(|n| n.has_tag_name("rect"))
+            .map(|n| (number(n, position), number(n, position) + number(n, size)))
+            .collect::<Vec<_>>();
+        assert_eq!(3, spans.len());
+        assert_eq!(0.0, spans[0].0);
+        assert_eq!(image_size, spans[2].1);
+        assert!(spans
+            .windows(2)
+            .all(|w| w[0].1 == w[1].0 && w[0].0 < w[0].1));
+        // The bands are drawn before, i.e. behind, the nodes and edges
+        let edges = root
+            .children()
+            .position(|n| n.attribute("class") == Some("edges"))
+            .unwrap();
+        let bands_position = root.children().position(|n| n == bands).unwrap();
+        assert!(bands_position < edges);
+    }
+}
+
+#[test]
+fn svg_drawer_groups_edges_beneath_nodes() {
+    let tree = more_complex_tree();
+    let svg = Layouter::new(&tree)
+        .with_drawer(&SvgDrawer::new().with_arrowheads(true))
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let group = |class: &str| {
+        document
+            .root_element()
+            .children()
+            .find(|n| n.has_tag_name("g") && n.attribute("class") == Some(class))
+            .unwrap()
+    };
+    let (edges, nodes) = (group("edges"), group("nodes"));
+    // The edges group precedes the nodes group, so the edges are drawn beneath the nodes
+    assert!(edges.range().end <= nodes.range().start);
+    let count = |group: roxmltree::Node, tag: &str| {
+        group.descendants().filter(|n| n.has_tag_name(tag)).count()
+    };
+    assert_eq!(tree.len() - 1, count(edges, "line"));
+    assert_eq!(0, count(edges, "text"));
+    assert_eq!(tree.len(), count(nodes, "text"));
+    assert_eq!(0, count(nodes, "line"));
+}
+
+#[test]
+fn typed_drawers_get_the_original_values() {
+    enum Expr {
+        Operator(char),
+        Number(i32),
+    }
+
+    /// Draws operators as circles and numbers as rectangles
+    struct ExprDrawer;
+
+    impl TypedDrawer<Expr> for ExprDrawer {
+        fn draw_typed_to_writer(
+            &self,
+            writer: &mut dyn std::io::Write,
+            embedding: &Embedding,
+            nodes: &[(&Expr, &EmbeddedNode)],
+        ) -> Result<()> {
+            assert_eq!(embedding.len(), nodes.len());
+            for (value, node) in nodes {
+                let shape = match value {
+                    Expr::Operator(_) => "circle",
+                    Expr::Number(_) => "rect",
+                };
+                writeln!(writer, "{} {}", shape, node.text)?;
+            }
+            Ok(())
+        }
+    }
+
+    //      *
+    //     / \
+    //    +   3
+    //   / \
+    //  1   2
+    let mut tree = Builder::new();
+    tree.open(Expr::Operator('*')).unwrap();
+    tree.open(Expr::Operator('+')).unwrap();
+    tree.token(Expr::Number(1), 1).unwrap();
+    tree.token(Expr::Number(2), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Expr::Number(3), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let path = output_path("typed_drawer.txt");
+    let layouter = Layouter::new(&tree)
+        .with_file_path(&path)
+        .embed_with(
+            |value| match value {
+                Expr::Operator(c) => c.to_string(),
+                Expr::Number(n) => n.to_string(),
+            },
+            |_| false,
+        )
+        .unwrap();
+    layouter.write_typed(&ExprDrawer).unwrap();
+    let expected = "circle *\ncircle +\nrect 1\nrect 2\nrect 3\n";
+    assert_eq!(expected, std::fs::read_to_string(&path).unwrap());
+
+    let mut buffer = Vec::new();
+    ExprDrawer
+        .draw_typed_to_writer(&mut buffer, layouter.embedding(), &layouter.typed_nodes())
+        .unwrap();
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+}
+
+#[test]
+fn svg_drawer_dims_nodes_outside_of_the_focus() {
+    //      root
+    //     /    \
+    //  focus   other
+    //   / \
+    //  a   b
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    let focus = tree.open(Label("focus")).unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    tree.token(Label("b"), 1).unwrap();
+    tree.close().unwrap();
+    let other = tree.token(Label("other"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_focus(focus)
+        .embed_with_visualize()
+        .unwrap();
+    let opacities = layouter
+        .embedding()
+        .iter()
+        .map(|e| (e.text.as_str(), e.opacity.is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("root", true),
+            ("focus", false),
+            ("a", false),
+            ("b", false),
+            ("other", true)
+        ],
+        opacities
+    );
+
+    let svg = layouter.write_to_string().unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let opacity = |node: roxmltree::Node| {
+        node.ancestors()
+            .find_map(|n| n.attribute("opacity"))
+            .map(|opacity| opacity.parse::<f32>().unwrap())
+    };
+    for text in document.descendants().filter(|n| n.has_tag_name("text")) {
+        match text.text().unwrap() {
+            "root" | "other" => assert!(opacity(text).unwrap() < 1.0),
+            _ => assert_eq!(None, opacity(text)),
+        }
+    }
+    // Only the edge to the unrelated node is dimmed
+    let edges = document
+        .descendants()
+        .filter(|n| n.has_tag_name("line"))
+        .map(|n| opacity(n).is_some())
+        .collect::<Vec<_>>();
+    assert_eq!(4, edges.len());
+    assert_eq!(1, edges.iter().filter(|dimmed| **dimmed).count());
+
+    let missing = Layouter::new(&tree)
+        .with_focus(focus)
+        .with_max_depth(0)
+        .embed_with_visualize();
+    assert!(matches!(missing, Err(LayouterError::UnknownFocus)));
+
+    // The focus is outside of the embedded subtree
+    let unrelated = Layouter::new(&tree)
+        .with_focus(focus)
+        .with_subtree_root(other)
+        .embed_with_visualize();
+    assert!(matches!(unrelated, Err(LayouterError::UnknownFocus)));
+}
+
+#[test]
+fn svg_drawer_annotates_nodes_with_their_ords() {
+    let tree = more_complex_tree();
+    let render = |drawer: SvgDrawer| {
+        Layouter::new(&tree)
+            .with_drawer(&drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+    let annotations = |svg: &str| {
+        let document = roxmltree::Document::parse(svg).unwrap();
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name("text"))
+            .filter(|node| node.attribute("class") == Some("annotation"))
+            .map(|node| node.text().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(annotations(&render(SvgDrawer::new())).is_empty());
+
+    let svg = render(SvgDrawer::new().with_node_annotations(AnnotationKind::Ord));
+    let expected = (0..tree.len())
+        .map(|ord| ord.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(expected, annotations(&svg));
+    // The labels are still drawn as the main texts
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let labels = document
+        .descendants()
+        .filter(|node| node.has_tag_name("text") && node.attribute("class").is_none())
+        .count();
+    assert_eq!(tree.len(), labels);
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let svg = render(SvgDrawer::new().with_node_annotations(AnnotationKind::NodeId));
+    let expected = layouter
+        .embedding()
+        .iter()
+        .map(|data| data.node_id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(expected, annotations(&svg));
+}
+
+#[test]
+fn svg_drawer_draws_internal_nodes_as_dots() {
+    let tree = more_complex_tree();
+    let drawer = SvgDrawer::new().with_internal_node_dots(true);
+    let layouter = Layouter::new(&tree)
+        .with_drawer(&drawer)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    let internal = embedding.iter().filter(|e| e.child_count > 0).count();
+    let leaves = embedding.len() - internal;
+    assert!(internal > 0 && leaves > 0);
+    // The child counts match the parent links
+    for e in embedding.iter() {
+        let children = embedding.iter().filter(|c| c.parent == Some(e.ord)).count();
+        assert_eq!(children, e.child_count);
+    }
+
+    let svg = layouter.write_to_string().unwrap();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let count = |tag: &str| {
+        document
+            .descendants()
+            .filter(|n| n.has_tag_name(tag))
+            .count()
+    };
+    assert_eq!(internal, count("circle"));
+    // The background and one box per leaf
+    assert_eq!(leaves + 1, count("rect"));
+    // All labels are still drawn
+    assert_eq!(embedding.len(), count("text"));
+
+    // By default neither dots nor boxes are drawn
+    let svg = Layouter::new(&tree)
+        .with_drawer(&SvgDrawer::new())
+        .embed_with_visualize()
+        .unwrap()
+        .write_to_string()
+        .unwrap();
+    assert!(!svg.contains("<circle"));
+    assert_eq!(1, svg.matches("<rect").count());
+}
+
+#[test]
+fn post_embed_hook_adjusts_the_rendered_coordinates() {
+    let tree = more_complex_tree();
+    let render = |shift: usize| {
+        let layouter = Layouter::new(&tree)
+            .with_post_embed(move |nodes: &mut [EmbeddedNode]| {
+                nodes.iter_mut().for_each(|node| node.x_center += shift)
+            })
+            .embed_with_visualize()
+            .unwrap();
+        let centers = layouter
+            .embedding()
+            .iter()
+            .map(|node| node.x_center)
+            .collect::<Vec<_>>();
+        let svg = layouter
+            .with_drawer(&SvgDrawer::new())
+            .write_to_string()
+            .unwrap();
+        (centers, attribute_values(&svg, "text", "x"))
+    };
+
+    let (centers, xs) = render(0);
+    let (shifted_centers, shifted_xs) = render(3);
+    for (center, shifted) in centers.iter().zip(&shifted_centers) {
+        assert_eq!(center + 3, *shifted);
+    }
+    // All texts are drawn shifted by the same distance
+    assert_eq!(xs.len(), shifted_xs.len());
+    let distance = shifted_xs[0] - xs[0];
+    assert!(distance > 0.0);
+    for (x, shifted) in xs.iter().zip(&shifted_xs) {
+        assert!((shifted - x - distance).abs() < 0.01, "{} {}", x, shifted);
+    }
+}
+
+#[test]
+fn drawers_fit_wide_characters_into_their_boxes() {
+    // Six wide characters occupy as many columns as twelve narrow ones
+    let draw = |label: &'static str, drawer: &dyn Drawer| {
+        let mut tree = Builder::new();
+        tree.open(Label(label)).unwrap();
+        tree.token(Label("ab"), 1).unwrap();
+        tree.close().unwrap();
+        let tree = tree.build().unwrap();
+        Layouter::new(&tree)
+            .with_char_width(east_asian_char_width)
+            .with_drawer(drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap()
+    };
+
+    let svg_drawer = SvgDrawer::new().with_node_shape(NodeShape::Rect);
+    let wide = draw("日本語日本語", &svg_drawer);
+    let narrow = draw("abcdefghijkl", &svg_drawer);
+    for (element, attribute) in [("text", "x"), ("rect", "x"), ("rect", "width")] {
+        assert_eq!(
+            attribute_values(&narrow, element, attribute),
+            attribute_values(&wide, element, attribute),
+            "{} {}",
+            element,
+            attribute
+        );
+    }
+
+    // The terminal columns of each line match, since wide characters occupy two of them
+    let columns = |text: &str| {
+        text.lines()
+            .map(|line| line.chars().map(east_asian_char_width).sum::<usize>())
+            .collect::<Vec<_>>()
+    };
+    let wide = draw("日本語日本語", &TextDrawer::new());
+    let narrow = draw("abcdefghijkl", &TextDrawer::new());
+    assert!(wide.contains("│日本語日本語│"));
+    assert_eq!(columns(&narrow), columns(&wide));
+}
+
+#[test]
+fn drawers_size_the_nodes_with_the_text_measure() {
+    // A measure of two columns per character draws like a character width of two
+    let tree = more_complex_tree();
+    let svg_drawer = SvgDrawer::new().with_node_shape(NodeShape::Rect);
+    let text_drawer = TextDrawer::new();
+    for drawer in [&svg_drawer as &dyn Drawer, &text_drawer] {
+        let measured = Layouter::new(&tree)
+            .with_text_measure(|line| 2.0 * line.chars().count() as f32)
+            .with_drawer(drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        let doubled = Layouter::new(&tree)
+            .with_char_width(|_| 2)
+            .with_drawer(drawer)
+            .embed_with_visualize()
+            .unwrap()
+            .write_to_string()
+            .unwrap();
+        assert_eq!(doubled, measured);
+    }
+}
@@ -1,5 +1,8 @@
 use syntree::{Builder, Tree};
-use syntree_layout::{Layouter, Visualize};
+use syntree_layout::{
+    east_asian_char_width, layout, EmbeddedNode, Embedding, LayoutDirection, LayoutStyle, Layouter,
+    LayouterError, ParentAlignment, Rounding, Visualize,
+};
 
 #[derive(Debug)]
 struct MyNodeData(i32);
@@ -10,6 +13,15 @@ impl Visualize for MyNodeData {
     }
 }
 
+#[derive(Debug)]
+struct Label(&'static str);
+
+impl Visualize for Label {
+    fn visualize(&self) -> std::string::String {
+        self.0.to_string()
+    }
+}
+
 #[test]
 fn empty_tree() {
     let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
@@ -112,3 +124,1936 @@ fn more_complex_tree() {
         assert_eq!(2, e.x_extent_children);
     }
 }
+
+#[test]
+fn forest_with_two_roots() {
+    //  0   2
+    //  |
+    //  1
+    let mut tree = Builder::new();
+
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(MyNodeData(2)).unwrap();
+    tree.close().unwrap();
+
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(3, embedding.len());
+
+    {
+        let e = &embedding.iter().find(|e| e.text == "0").unwrap();
+        assert_eq!(0, e.y_order);
+        assert_eq!(1, e.x_center);
+        assert_eq!(None, e.parent);
+    }
+    {
+        let e = &embedding.iter().find(|e| e.text == "1").unwrap();
+        assert_eq!(1, e.y_order);
+        assert_eq!(1, e.x_center);
+        assert_eq!(Some(0), e.parent);
+    }
+    {
+        // Default gap between roots is 2 units
+        let e = &embedding.iter().find(|e| e.text == "2").unwrap();
+        assert_eq!(0, e.y_order);
+        assert_eq!(5, e.x_center);
+        assert_eq!(None, e.parent);
+    }
+
+    let layouter = Layouter::new(&tree)
+        .with_root_gap(0)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    let e = &embedding.iter().find(|e| e.text == "2").unwrap();
+    assert_eq!(3, e.x_center);
+}
+
+#[test]
+fn single_root_rejects_forests() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).with_single_root(true);
+    assert_eq!(2, layouter.root_count());
+    assert!(matches!(
+        layouter.embed_with_visualize(),
+        Err(LayouterError::MultipleRoots { found: 2 })
+    ));
+
+    // A tree with a single root and the subtree of a forest are embedded
+    let root = tree.first().unwrap().id();
+    let layouter = Layouter::new(&tree)
+        .with_single_root(true)
+        .with_subtree_root(root)
+        .embed_with_visualize()
+        .unwrap();
+    assert_eq!(2, layouter.embedding().len());
+    let empty: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    assert_eq!(0, Layouter::new(&empty).root_count());
+}
+
+#[test]
+fn x_extent_counts_characters_not_bytes() {
+    let mut tree = Builder::new();
+    tree.open(Label("日本語")).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let e = &layouter.embedding()[0];
+    // Three characters plus one unit of padding, although the text is nine bytes long
+    assert_eq!(4, e.x_extent);
+
+    let layouter = Layouter::new(&tree)
+        .with_char_width(east_asian_char_width)
+        .embed_with_visualize()
+        .unwrap();
+    let e = &layouter.embedding()[0];
+    // Three full-width characters of two columns each plus one unit of padding
+    assert_eq!(7, e.x_extent);
+}
+
+#[test]
+fn left_to_right_stacks_siblings() {
+    //      0
+    //     / \
+    //    1   2
+    let mut tree = Builder::new();
+
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.close().unwrap();
+    tree.open(MyNodeData(222)).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_direction(LayoutDirection::LeftToRight)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(LayoutDirection::LeftToRight, embedding.direction());
+
+    // The extent along the sibling axis is independent of the text length
+    assert!(embedding.iter().all(|e| e.x_extent == 2));
+    {
+        let e = &embedding.iter().find(|e| e.text == "0").unwrap();
+        assert_eq!(0, e.y_order);
+        assert_eq!(2, e.x_center);
+        assert_eq!(4, e.x_extent_children);
+    }
+    {
+        let e = &embedding.iter().find(|e| e.text == "1").unwrap();
+        assert_eq!(1, e.y_order);
+        assert_eq!(1, e.x_center);
+    }
+    {
+        let e = &embedding.iter().find(|e| e.text == "222").unwrap();
+        assert_eq!(1, e.y_order);
+        assert_eq!(3, e.x_center);
+    }
+}
+
+#[test]
+fn inverted_layers() {
+    //  3   4
+    //   \ /
+    //    1   2
+    //     \ /
+    //      0
+    let mut tree = Builder::new();
+
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.open(MyNodeData(3)).unwrap();
+    tree.close().unwrap();
+    tree.open(MyNodeData(4)).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(MyNodeData(2)).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_inverted(true)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+
+    let y_order = |text: &str| embedding.iter().find(|e| e.text == text).unwrap().y_order;
+    assert_eq!(2, y_order("0"));
+    assert_eq!(1, y_order("1"));
+    assert_eq!(1, y_order("2"));
+    assert_eq!(0, y_order("3"));
+    assert_eq!(0, y_order("4"));
+
+    // The horizontal placement is not affected
+    let x_center = |text: &str| embedding.iter().find(|e| e.text == text).unwrap().x_center;
+    assert_eq!(3, x_center("0"));
+    assert_eq!(2, x_center("1"));
+    assert_eq!(5, x_center("2"));
+}
+
+#[test]
+fn embed_with_closures() {
+    let mut tree = Builder::new();
+    tree.open(1_i32).unwrap();
+    tree.token(20_i32, 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    // The closures may borrow local state
+    let threshold = 10;
+    let prefix = String::from("n");
+    let layouter = Layouter::new(&tree)
+        .embed_with(|v| format!("{prefix}{v}"), |v| *v > threshold)
+        .unwrap();
+    let embedding = layouter.embedding();
+
+    assert_eq!("n1", embedding[0].text);
+    assert!(!embedding[0].is_emphasized);
+    assert_eq!("n20", embedding[1].text);
+    assert!(embedding[1].is_emphasized);
+}
+
+#[test]
+fn embed_with_context_resolves_labels() {
+    // The nodes refer to the entries of a symbol table
+    struct SymbolTable {
+        names: Vec<&'static str>,
+        keywords: Vec<usize>,
+    }
+    let symbols = SymbolTable {
+        names: vec!["fn", "main", "x"],
+        keywords: vec![0],
+    };
+
+    let mut tree = Builder::new();
+    tree.open(0_usize).unwrap();
+    tree.token(1_usize, 1).unwrap();
+    tree.token(2_usize, 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .embed_with_context(
+            &symbols,
+            |symbols, id| symbols.names[*id].to_string(),
+            |symbols, id| symbols.keywords.contains(id),
+        )
+        .unwrap();
+    let labels = layouter
+        .embedding()
+        .iter()
+        .map(|e| (e.text.as_str(), e.is_emphasized))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![("fn", true), ("main", false), ("x", false)], labels);
+}
+
+#[test]
+fn sibling_spacing_widens_nodes() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.close().unwrap();
+    tree.open(MyNodeData(2)).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_sibling_spacing(3)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+
+    assert!(embedding.iter().all(|e| e.x_extent == 4));
+    let x_center = |text: &str| embedding.iter().find(|e| e.text == text).unwrap().x_center;
+    assert_eq!(2, x_center("1"));
+    assert_eq!(6, x_center("2"));
+    assert_eq!(4, x_center("0"));
+}
+
+#[test]
+fn deep_left_leaning_tree_stays_in_bounds() {
+    //          0
+    //         / \
+    //        1   x
+    //       / \
+    //      2   x
+    //     ...
+    let mut tree = Builder::new();
+    for depth in 0..8 {
+        tree.open(Label(["node", "a", "rather_long_label"][depth % 3]))
+            .unwrap();
+    }
+    for _ in 0..8 {
+        tree.token(Label("x"), 1).unwrap();
+        tree.close().unwrap();
+    }
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(16, embedding.len());
+
+    for e in embedding {
+        // No node or subtree extends left of the origin
+        assert!(e.x_center >= e.x_extent / 2, "{e:?}");
+        assert!(e.x_center >= e.x_extent_children / 2, "{e:?}");
+        // Each node lies within the subtree span of its parent
+        if let Some(parent) = e.parent {
+            let p = embedding.iter().find(|p| p.ord == parent).unwrap();
+            assert!(p.x_center - p.x_extent_children / 2 <= e.x_center - e.x_extent / 2);
+            assert!(e.x_center + e.x_extent / 2 <= p.x_center + p.x_extent_children / 2 + 1);
+        }
+    }
+}
+
+#[test]
+fn multi_line_labels_use_widest_line() {
+    let mut tree = Builder::new();
+    tree.open(Label("fn\nmain_function")).unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(14, embedding[0].x_extent);
+    assert_eq!(2, embedding[0].line_count);
+    assert_eq!(2, embedding[1].x_extent);
+    assert_eq!(1, embedding[1].line_count);
+}
+
+#[test]
+fn large_tree_is_embedded_in_linear_time() {
+    // A root with 100 children, each of them having 100 leaves
+    let mut tree = Builder::new();
+    tree.open(Label("r")).unwrap();
+    for _ in 0..100 {
+        tree.open(Label("n")).unwrap();
+        for _ in 0..100 {
+            tree.token(Label("x"), 1).unwrap();
+        }
+        tree.close().unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(10_101, embedding.len());
+
+    // The leaves are placed side by side without any overlap
+    let mut leaves = embedding
+        .iter()
+        .filter(|e| e.y_order == 2)
+        .map(|e| e.x_center)
+        .collect::<Vec<_>>();
+    assert_eq!(10_000, leaves.len());
+    assert!(leaves.windows(2).all(|w| w[1] == w[0] + 2));
+    leaves.dedup();
+    assert_eq!(10_000, leaves.len());
+
+    // The root is centered above all leaves
+    assert_eq!(20_000, embedding[0].x_extent_children);
+    assert_eq!(10_000, embedding[0].x_center);
+}
+
+#[test]
+fn parent_ords_match_linear_lookup() {
+    // A mixed tree of nodes and tokens with varying depths and a second root
+    let mut tree = Builder::new();
+    tree.open(Label("fn")).unwrap();
+    tree.token(Label("name"), 4).unwrap();
+    tree.open(Label("args")).unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    tree.open(Label("pair")).unwrap();
+    tree.token(Label("b"), 1).unwrap();
+    tree.token(Label("c"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.token(Label("body"), 4).unwrap();
+    tree.close().unwrap();
+    tree.open(Label("eof")).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+
+    // Find each node's parent by scanning all nodes in walk order
+    let nodes = tree.walk().collect::<Vec<_>>();
+    assert_eq!(nodes.len(), embedding.len());
+    for (ord, node) in nodes.iter().enumerate() {
+        let expected = node
+            .parent()
+            .map(|p| nodes.iter().position(|n| n.id() == p.id()).unwrap());
+        assert_eq!(ord, embedding[ord].ord);
+        assert_eq!(node.value().0, embedding[ord].text);
+        assert_eq!(expected, embedding[ord].parent, "{:?}", embedding[ord]);
+
+        // The children's extents were found by their ids as well
+        let x_extent_of_children = node
+            .children()
+            .map(|c| {
+                let ord = nodes.iter().position(|n| n.id() == c.id()).unwrap();
+                embedding[ord].x_extent_children
+            })
+            .sum::<usize>();
+        assert_eq!(
+            embedding[ord].x_extent.max(x_extent_of_children),
+            embedding[ord].x_extent_children
+        );
+    }
+}
+
+#[test]
+fn dimensions_span_all_nodes() {
+    let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!((0, 0), layouter.embedding().dimensions());
+
+    //  0   3
+    //  |
+    //  1
+    //  |
+    //  2
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(3), 1).unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    // Both roots plus the gap between them
+    assert_eq!((6, 3), embedding.dimensions());
+    for e in embedding {
+        assert!(e.x_center + e.x_extent / 2 <= 6);
+    }
+}
+
+#[test]
+fn max_depth_collapses_deeper_subtrees() {
+    //      0
+    //     / \
+    //    1   2
+    //   / \
+    //  3   4
+    //  |
+    //  5
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.open(MyNodeData(3)).unwrap();
+    tree.token(MyNodeData(5), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_max_depth(1)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(3, embedding.len());
+    assert_eq!(
+        (0..3).collect::<Vec<_>>(),
+        embedding.iter().map(|e| e.ord).collect::<Vec<_>>()
+    );
+
+    // Only the node with hidden children is marked
+    let texts = embedding
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["0", "1 …", "2"], texts);
+
+    // The collapsed node is laid out like a leaf
+    let e = &embedding[1];
+    assert_eq!(Some(0), e.parent);
+    assert_eq!(4, e.x_extent);
+    assert_eq!(4, e.x_extent_children);
+    assert_eq!(2, e.x_center);
+    assert_eq!(6, embedding[0].x_extent_children);
+    assert_eq!(2, layouter.embedding().dimensions().1);
+
+    // A depth beyond the tree's height changes nothing
+    let layouter = Layouter::new(&tree)
+        .with_max_depth(3)
+        .embed_with_visualize()
+        .unwrap();
+    assert_eq!(6, layouter.embedding().len());
+    assert!(layouter.embedding().iter().all(|e| !e.text.contains('…')));
+}
+
+#[test]
+fn long_labels_are_truncated() {
+    let mut tree = Builder::new();
+    tree.open(Label("äöüßéèêë")).unwrap();
+    tree.token(Label("short"), 1).unwrap();
+    tree.token(Label("日本語のテキスト"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_max_label_width(5)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!("äöüß…", embedding[0].text);
+    assert_eq!(Some("äöüßéèêë".to_string()), embedding[0].full_text);
+    // The extent is based on the truncated text
+    assert_eq!(6, embedding[0].x_extent);
+    assert_eq!("short", embedding[1].text);
+    assert_eq!(None, embedding[1].full_text);
+
+    // Full-width characters are never split
+    let layouter = Layouter::new(&tree)
+        .with_char_width(east_asian_char_width)
+        .with_max_label_width(7)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!("日本語…", embedding[2].text);
+    assert_eq!(8, embedding[2].x_extent);
+}
+
+#[test]
+fn display_types_need_no_visualize_impl() {
+    let mut tree = Builder::new();
+    tree.open(String::from("root")).unwrap();
+    tree.token(String::from("leaf"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed().unwrap();
+    let texts = layouter
+        .embedding()
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["root", "leaf"], texts);
+    assert!(layouter.embedding().iter().all(|e| !e.is_emphasized));
+
+    let mut tree = Builder::new();
+    tree.open(-42i32).unwrap();
+    tree.token(7i32, 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed().unwrap();
+    assert_eq!("-42", layouter.embedding()[0].text);
+    assert_eq!(4, layouter.embedding()[0].x_extent);
+}
+
+#[test]
+fn right_to_left_mirrors_siblings() {
+    //      0
+    //    / | \
+    //   1  2  3
+    //   |
+    //   4
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.token(MyNodeData(3), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let x_centers = |right_to_left| {
+        let layouter = Layouter::new(&tree)
+            .with_right_to_left(right_to_left)
+            .embed_with_visualize()
+            .unwrap();
+        let embedding = layouter.embedding();
+        let x_center = |text: &str| embedding.iter().find(|e| e.text == text).unwrap().x_center;
+        let x_centers = ["0", "1", "2", "3", "4"].map(x_center);
+        // The layout starts at the origin in both modes
+        assert_eq!(
+            0,
+            embedding
+                .iter()
+                .map(|e| e.x_center - e.x_extent / 2)
+                .min()
+                .unwrap()
+        );
+        (x_centers, embedding.dimensions())
+    };
+
+    let ([_, l1, l2, l3, l4], ltr_dimensions) = x_centers(false);
+    assert!(l1 < l2 && l2 < l3);
+    assert_eq!(l1, l4);
+
+    let ([r0, r1, r2, r3, r4], rtl_dimensions) = x_centers(true);
+    assert!(r1 > r2 && r2 > r3);
+    assert_eq!(r1, r4);
+    assert_eq!(3, r0);
+    assert_eq!(ltr_dimensions, rtl_dimensions);
+}
+
+#[test]
+fn right_to_left_reverses_the_children_but_not_their_ords() {
+    let mut tree = Builder::new();
+    tree.open(Label("p")).unwrap();
+    for child in ["a", "b", "c"] {
+        tree.token(Label(child), 1).unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_right_to_left(true)
+        .embed_with_visualize()
+        .unwrap();
+    let mut children = layouter.embedding()[1..].iter().collect::<Vec<_>>();
+    // The ords follow the tree
+    assert_eq!(
+        vec!["a", "b", "c"],
+        children.iter().map(|e| e.text.as_str()).collect::<Vec<_>>()
+    );
+    children.sort_by_key(|e| e.x_center);
+    assert_eq!(
+        vec!["c", "b", "a"],
+        children.iter().map(|e| e.text.as_str()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reversed_children_places_the_children_from_right_to_left() {
+    let mut tree = Builder::new();
+    tree.open(Label("p")).unwrap();
+    for child in ["a", "b", "c"] {
+        tree.token(Label(child), 1).unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_reversed_children(true)
+        .embed_with_visualize()
+        .unwrap();
+    let mut children = layouter.embedding()[1..].iter().collect::<Vec<_>>();
+    children.sort_by_key(|e| e.x_center);
+    assert_eq!(
+        vec!["c", "b", "a"],
+        children.iter().map(|e| e.text.as_str()).collect::<Vec<_>>()
+    );
+
+    let right_to_left = Layouter::new(&tree)
+        .with_right_to_left(true)
+        .embed_with_visualize()
+        .unwrap();
+    let x_centers =
+        |embedding: &Embedding| embedding.iter().map(|e| e.x_center).collect::<Vec<_>>();
+    assert_eq!(
+        x_centers(right_to_left.embedding()),
+        x_centers(layouter.embedding())
+    );
+}
+
+#[test]
+fn edges_never_cross() {
+    // Parents on the same layer with differently sized subtrees, which interleave in a layered
+    // drawing that doesn't keep subtrees together
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    for (i, children) in [3, 0, 5, 1, 2].iter().enumerate() {
+        tree.open(Label(["a", "bb", "ccc", "d", "eeeee"][i]))
+            .unwrap();
+        for c in 0..*children {
+            tree.open(Label(["x", "yyyy", "zz"][c % 3])).unwrap();
+            for _ in 0..c % 2 {
+                tree.token(Label("leaf"), 1).unwrap();
+            }
+            tree.close().unwrap();
+        }
+        tree.close().unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+
+    // Edges between two adjacent layers, as (x of parent, x of child)
+    let edges = embedding
+        .iter()
+        .filter_map(|e| {
+            let p = &embedding[e.parent?];
+            Some((p.y_order, p.x_center, e.x_center))
+        })
+        .collect::<Vec<_>>();
+    let crossings = edges
+        .iter()
+        .flat_map(|a| edges.iter().map(move |b| (a, b)))
+        .filter(|((la, pa, ca), (lb, pb, cb))| la == lb && pa < pb && ca > cb)
+        .count();
+    assert_eq!(0, crossings);
+}
+
+#[test]
+fn compaction_reduces_width() {
+    //        root
+    //      /   |   \
+    //     a    c    e
+    //     |    |    |
+    //     b    d    f
+    //     |
+    //  wide_label
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("wide_label"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    for (parent, child) in [("c", "d"), ("e", "f")] {
+        tree.open(Label(parent)).unwrap();
+        tree.token(Label(child), 1).unwrap();
+        tree.close().unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let embed = |compact| {
+        Layouter::new(&tree)
+            .with_compaction(compact)
+            .embed_with_visualize()
+            .unwrap()
+            .embedding()
+            .clone()
+    };
+    let loose = embed(false);
+    let compact = embed(true);
+    assert_eq!((15, 4), loose.dimensions());
+    assert_eq!((11, 4), compact.dimensions());
+
+    for embedding in [&loose, &compact] {
+        // The layout starts at the origin
+        assert_eq!(
+            0,
+            embedding
+                .iter()
+                .map(|e| e.x_center - e.x_extent / 2)
+                .min()
+                .unwrap()
+        );
+        // Nodes don't overlap and keep the order of the tree on each layer
+        for layer in 0..4 {
+            let nodes = embedding
+                .iter()
+                .filter(|e| e.y_order == layer)
+                .collect::<Vec<_>>();
+            for pair in nodes.windows(2) {
+                assert!(
+                    pair[0].x_center + pair[0].x_extent / 2
+                        <= pair[1].x_center - pair[1].x_extent / 2
+                );
+            }
+        }
+        // Each parent is centered above its children
+        let x_center = |text: &str| embedding.iter().find(|e| e.text == text).unwrap().x_center;
+        assert_eq!(x_center("d"), x_center("c"));
+    }
+
+    // The subtree of the first child reserves the width of its widest layer, except in the
+    // compact layout where the second subtree is moved below its narrow upper part
+    let x_center = |embedding: &Embedding, text: &str| {
+        embedding.iter().find(|e| e.text == text).unwrap().x_center
+    };
+    assert_eq!(2, x_center(&compact, "c") - x_center(&compact, "a"));
+    assert_eq!(7, x_center(&loose, "c") - x_center(&loose, "a"));
+    assert_eq!(x_center(&compact, "c"), x_center(&compact, "root"));
+    assert_eq!(
+        11,
+        compact
+            .iter()
+            .find(|e| e.text == "root")
+            .unwrap()
+            .x_extent_children
+    );
+}
+
+#[test]
+fn leaves_are_moved_to_the_last_layer() {
+    //        root
+    //      /   |   \
+    //     a    c    e
+    //     |    |
+    //     b    d
+    //     |
+    //   leaf
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("leaf"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(Label("c")).unwrap();
+    tree.token(Label("d"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("e"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let embed = |compact, leaves_at_bottom| {
+        Layouter::new(&tree)
+            .with_compaction(compact)
+            .with_leaves_at_bottom(leaves_at_bottom)
+            .embed_with_visualize()
+            .unwrap()
+            .embedding()
+            .clone()
+    };
+    for compact in [false, true] {
+        let embedding = embed(compact, true);
+        let is_leaf = |ord| embedding.iter().all(|e| e.parent != Some(ord));
+        let leaves = embedding
+            .iter()
+            .filter(|e| is_leaf(e.ord))
+            .map(|e| (e.text.as_str(), e.y_order))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("leaf", 3), ("d", 3), ("e", 3)], leaves);
+
+        // The inner nodes keep their layers, in the compact layout the leaves' contours span the
+        // elongated edges which may move the nodes apart
+        let original = embed(compact, false);
+        for (moved, kept) in embedding.iter().zip(original.iter()) {
+            if !compact {
+                assert_eq!(moved.x_center, kept.x_center);
+            }
+            if !is_leaf(moved.ord) {
+                assert_eq!(moved.y_order, kept.y_order);
+            }
+        }
+
+        // The leaves don't overlap
+        let mut borders = embedding
+            .iter()
+            .filter(|e| e.y_order == 3)
+            .map(|e| {
+                (
+                    e.x_center - e.x_extent / 2,
+                    e.x_center + e.x_extent - e.x_extent / 2,
+                )
+            })
+            .collect::<Vec<_>>();
+        borders.sort();
+        assert!(borders.windows(2).all(|w| w[0].1 <= w[1].0), "{borders:?}");
+    }
+}
+
+#[test]
+fn too_wide_layouts_are_rejected() {
+    // Three leaves of width 3 below a narrow root, i.e. a width of 9
+    let mut tree = Builder::new();
+    tree.open(Label("r")).unwrap();
+    for label in ["ab", "cd", "ef"] {
+        tree.token(Label(label), 1).unwrap();
+    }
+    tree.close().unwrap();
+    // A forest adds the root gap of 2 between the trees
+    tree.token(Label("gh"), 1).unwrap();
+    let tree = tree.build().unwrap();
+
+    let embed = |max_layout_width| {
+        Layouter::new(&tree)
+            .with_max_layout_width(max_layout_width)
+            .embed_with_visualize()
+            .map(|layouter| layouter.embedding().dimensions())
+    };
+    assert_eq!((14, 2), embed(14).unwrap());
+    assert!(matches!(embed(13), Err(LayouterError::LayoutTooLarge(13))));
+    assert!(matches!(embed(8), Err(LayouterError::LayoutTooLarge(8))));
+}
+
+#[test]
+fn parents_are_aligned_over_their_first_or_last_child() {
+    //         wide_parent
+    //        /     |     \
+    //       a      b      c
+    let mut tree = Builder::new();
+    tree.open(Label("wide_parent")).unwrap();
+    for label in ["a", "b", "c"] {
+        tree.token(Label(label), 1).unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    for compact in [false, true] {
+        let embed = |alignment| {
+            Layouter::new(&tree)
+                .with_compaction(compact)
+                .with_parent_alignment(alignment)
+                .embed_with_visualize()
+                .unwrap()
+                .embedding()
+                .clone()
+        };
+        let first = embed(ParentAlignment::OverFirstChild);
+        assert_eq!(first[0].x_center, first[1].x_center);
+        let last = embed(ParentAlignment::OverLastChild);
+        assert_eq!(last[0].x_center, last[3].x_center);
+        let centered = embed(ParentAlignment::Center);
+        assert_eq!(centered[0].x_center, centered[2].x_center);
+
+        for embedding in [&first, &last, &centered] {
+            // The layout spans the overhanging parent and still starts at the origin
+            let left = |e: &syntree_layout::EmbeddedNode| e.x_center - e.x_extent / 2;
+            assert_eq!(0, embedding.iter().map(left).min().unwrap());
+            let children = &embedding[1..];
+            assert!(children
+                .windows(2)
+                .all(|w| left(&w[0]) + w[0].x_extent <= left(&w[1])));
+            assert_eq!(
+                embedding.dimensions().0,
+                embedding[0].x_extent_children,
+                "{embedding:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn layout_statistics() {
+    //        root
+    //      /   |   \
+    //     a    c    e
+    //     |    |
+    //     b    d
+    //     |
+    //   leaf
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("leaf"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(Label("c")).unwrap();
+    tree.token(Label("d"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("e"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(3, embedding.height());
+    assert_eq!(vec![1, 3, 2, 1], embedding.layer_widths());
+
+    let layouter = Layouter::new(&tree)
+        .with_max_depth(1)
+        .embed_with_visualize()
+        .unwrap();
+    assert_eq!(1, layouter.embedding().height());
+    assert_eq!(vec![1, 3], layouter.embedding().layer_widths());
+
+    let empty: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    let layouter = Layouter::new(&empty).embed_with_visualize().unwrap();
+    assert_eq!(0, layouter.embedding().height());
+    assert!(layouter.embedding().layer_widths().is_empty());
+}
+
+#[test]
+fn embedded_nodes_never_overlap() {
+    //        root
+    //      /   |   \
+    //     a    c    e
+    //     |    |
+    //     b    d
+    //     |
+    //   wide_leaf
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("wide_leaf"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(Label("c")).unwrap();
+    tree.token(Label("d"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("e"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("second_root"), 1).unwrap();
+    let tree = tree.build().unwrap();
+
+    for compact in [false, true] {
+        for leaves_at_bottom in [false, true] {
+            for alignment in [
+                ParentAlignment::Center,
+                ParentAlignment::OverFirstChild,
+                ParentAlignment::OverLastChild,
+            ] {
+                let layouter = Layouter::new(&tree)
+                    .with_compaction(compact)
+                    .with_leaves_at_bottom(leaves_at_bottom)
+                    .with_parent_alignment(alignment)
+                    .with_sibling_spacing(0)
+                    .embed_with_visualize()
+                    .unwrap();
+                assert_eq!(None, layouter.embedding().find_overlap());
+            }
+        }
+    }
+}
+
+#[test]
+fn sibling_subtrees_are_separated_at_every_depth() {
+    //          root
+    //        /      \
+    //       a        wide_shallow_sibling
+    //       |
+    //       b
+    //       |
+    //  very_wide_deep_leaf
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("very_wide_deep_leaf"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.token(Label("wide_shallow_sibling"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    for right_to_left in [false, true] {
+        let layouter = Layouter::new(&tree)
+            .with_right_to_left(right_to_left)
+            .with_sibling_spacing(0)
+            .embed_with_visualize()
+            .unwrap();
+        let embedding = layouter.embedding();
+        // The child of the root whose subtree contains the node
+        let branch = |ord: usize| {
+            let mut ord = ord;
+            while let Some(parent) = embedding[ord].parent.filter(|p| *p != 0) {
+                ord = parent;
+            }
+            ord
+        };
+        // The left and right border of each branch over all of its layers
+        let span = |branch_ord: usize| {
+            embedding
+                .iter()
+                .filter(|e| e.ord != 0 && branch(e.ord) == branch_ord)
+                .fold((usize::MAX, 0), |(l, r), e| {
+                    let left = e.x_center - e.x_extent / 2;
+                    (l.min(left), r.max(left + e.x_extent))
+                })
+        };
+        // The deep branch spans more layers than its sibling, but they're apart at every depth
+        let ((deep_left, deep_right), (shallow_left, shallow_right)) = (span(1), span(4));
+        assert!(deep_right <= shallow_left || shallow_right <= deep_left);
+        assert_eq!(None, embedding.find_overlap());
+    }
+}
+
+#[test]
+fn ord_is_the_pre_order_index() {
+    //    a       h
+    //   / \      |
+    //  b   e     i
+    //  |  / \
+    //  c f   g
+    //  |
+    //  d
+    let mut tree = Builder::new();
+    tree.open(Label("a")).unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.open(Label("c")).unwrap();
+    tree.token(Label("d"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(Label("e")).unwrap();
+    tree.token(Label("f"), 1).unwrap();
+    tree.token(Label("g"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.open(Label("h")).unwrap();
+    tree.token(Label("i"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    let texts = embedding
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["a", "b", "c", "d", "e", "f", "g", "h", "i"], texts);
+    for (i, e) in embedding.iter().enumerate() {
+        assert_eq!(i, e.ord);
+    }
+    let parents = embedding.iter().map(|e| e.parent).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            None,
+            Some(0),
+            Some(1),
+            Some(2),
+            Some(0),
+            Some(4),
+            Some(4),
+            None,
+            Some(7)
+        ],
+        parents
+    );
+
+    // The maximum depth omits nodes without leaving gaps
+    let layouter = Layouter::new(&tree)
+        .with_max_depth(1)
+        .embed_with_visualize()
+        .unwrap();
+    let ords = layouter
+        .embedding()
+        .iter()
+        .map(|e| e.ord)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![0, 1, 2, 3, 4], ords);
+}
+
+#[test]
+fn parents_are_resolved_for_all_non_root_nodes() {
+    // A malformed tree whose parent links contradict the pre-order can't be built with syntree's
+    // builder, such trees fail with `LayouterError::MissingParent` instead of gaining roots
+    let mut tree = Builder::new();
+    for root in ["a", "b"] {
+        tree.open(Label(root)).unwrap();
+        tree.open(Label("x")).unwrap();
+        tree.token(Label("y"), 1).unwrap();
+        tree.close().unwrap();
+        tree.token(Label("z"), 1).unwrap();
+        tree.close().unwrap();
+    }
+    let tree = tree.build().unwrap();
+
+    let expected = tree
+        .walk()
+        .map(|node| node.parent().map(|p| p.value().0))
+        .collect::<Vec<_>>();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    let parents = embedding
+        .iter()
+        .map(|e| e.parent.map(|p| embedding[p].text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, parents);
+
+    assert_eq!(
+        "The parent of the node 3 is missing in the embedding",
+        LayouterError::MissingParent(3).to_string()
+    );
+}
+
+#[test]
+fn forest_wraps_into_bands() {
+    // Five trees of a root and a child, each two units wide, the root gap is 2
+    let mut tree = Builder::new();
+    for root in ["a", "b", "c", "d", "e"] {
+        tree.open(Label(root)).unwrap();
+        tree.token(Label("x"), 1).unwrap();
+        tree.close().unwrap();
+    }
+    let tree = tree.build().unwrap();
+
+    let positions = |max_width| {
+        let layouter = Layouter::new(&tree)
+            .with_max_width(max_width)
+            .embed_with_visualize()
+            .unwrap();
+        layouter
+            .embedding()
+            .iter()
+            .filter(|e| e.parent.is_none())
+            .map(|e| (e.text.clone(), e.x_center, e.y_order))
+            .collect::<Vec<_>>()
+    };
+    let expected = |tuples: &[(&str, usize, usize)]| {
+        tuples
+            .iter()
+            .map(|(text, x, y)| (text.to_string(), *x, *y))
+            .collect::<Vec<_>>()
+    };
+
+    // Three trees and two gaps fit into the width
+    assert_eq!(
+        expected(&[
+            ("a", 1, 0),
+            ("b", 5, 0),
+            ("c", 9, 0),
+            ("d", 1, 2),
+            ("e", 5, 2)
+        ]),
+        positions(10)
+    );
+    let layouter = Layouter::new(&tree)
+        .with_max_width(10)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!((10, 4), embedding.dimensions());
+    assert_eq!(vec![3, 3, 2, 2], embedding.layer_widths());
+    // The children of the second band are placed below their roots
+    assert_eq!((1, 3), (embedding[7].x_center, embedding[7].y_order));
+    assert_eq!(None, embedding.find_overlap());
+
+    // A width that fits all trees keeps them in a single band
+    let single_band = positions(100);
+    assert!(single_band.iter().all(|(_, _, y)| *y == 0));
+    assert_eq!(17, single_band[4].1);
+
+    // Each tree wider than the width gets a band of its own
+    let bands = positions(1);
+    assert!(bands.iter().all(|(_, x, _)| *x == 1));
+    assert_eq!(
+        vec![0, 2, 4, 6, 8],
+        bands.iter().map(|(_, _, y)| *y).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn embedded_node_by_node_id() {
+    let mut tree = Builder::new();
+    let root = tree.open(Label("root")).unwrap();
+    tree.open(Label("inner")).unwrap();
+    let deep = tree.token(Label("deep"), 1).unwrap();
+    tree.close().unwrap();
+    let leaf = tree.token(Label("leaf"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    for (id, text) in [(root, "root"), (deep, "deep"), (leaf, "leaf")] {
+        let node = embedding.by_node_id(id).unwrap();
+        assert_eq!(text, node.text);
+        assert_eq!(id.get(), node.node_id);
+    }
+    assert_eq!(2, embedding.by_node_id(deep).unwrap().y_order);
+
+    // Omitted nodes aren't found
+    let layouter = Layouter::new(&tree)
+        .with_max_depth(1)
+        .embed_with_visualize()
+        .unwrap();
+    assert!(layouter.embedding().by_node_id(deep).is_none());
+    assert_eq!("leaf", layouter.embedding().by_node_id(leaf).unwrap().text);
+
+    // The ids that the post embed hook swaps are found
+    let layouter = Layouter::new(&tree)
+        .with_post_embed(move |nodes: &mut [EmbeddedNode]| {
+            nodes[0].node_id = leaf.get();
+            nodes[3].node_id = root.get();
+        })
+        .embed_with_visualize()
+        .unwrap();
+    assert_eq!("root", layouter.embedding().by_node_id(leaf).unwrap().text);
+    assert_eq!("leaf", layouter.embedding().by_node_id(root).unwrap().text);
+}
+
+#[test]
+fn overlaps_of_nodes_moved_beyond_the_origin_are_found() {
+    //   root
+    //   /  \
+    // abc  def
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.token(Label("abc"), 1).unwrap();
+    tree.token(Label("def"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let overlap = |shift: fn(&mut [EmbeddedNode])| {
+        Layouter::new(&tree)
+            .with_post_embed(shift)
+            .embed_with_visualize()
+            .unwrap()
+            .embedding()
+            .find_overlap()
+    };
+    // The root extends to the left of the origin, but stays alone on its layer
+    assert_eq!(None, overlap(|nodes| nodes[0].x_center = 0));
+    // The first child extends to the left of the origin, the second one overlaps it
+    assert_eq!(
+        Some((1, 2)),
+        overlap(|nodes| {
+            nodes[1].x_center = 0;
+            nodes[2].x_center = 1;
+        })
+    );
+    // Both children extend to the left of the origin, the second one is left of the first one
+    assert_eq!(
+        Some((2, 1)),
+        overlap(|nodes| {
+            nodes[1].x_center = 1;
+            nodes[2].x_center = 0;
+        })
+    );
+}
+
+#[test]
+fn siblings_are_sorted_for_display() {
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.token(Label("c"), 1).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.token(Label("z"), 1).unwrap();
+    tree.token(Label("y"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("b"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_sibling_sort(|a: &Label, b: &Label| a.0.cmp(b.0))
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    let x_order = |layer: usize| {
+        let mut nodes = embedding
+            .iter()
+            .filter(|e| e.y_order == layer)
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|e| e.x_center);
+        nodes.iter().map(|e| e.text.as_str()).collect::<Vec<_>>()
+    };
+    assert_eq!(vec!["a", "b", "c"], x_order(1));
+    assert_eq!(vec!["y", "z"], x_order(2));
+    let texts = embedding
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["root", "a", "y", "z", "b", "c"], texts);
+
+    // The tree keeps its order and without a comparator so does the layout
+    let children = tree
+        .first()
+        .unwrap()
+        .children()
+        .map(|n| n.value().0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["c", "a", "b"], children);
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let texts = layouter
+        .embedding()
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["root", "c", "a", "z", "y", "b"], texts);
+}
+
+#[test]
+fn roots_are_sorted_by_the_root_order() {
+    //  b     c   a
+    //  |\
+    //  z y
+    let mut tree = Builder::new();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("z"), 1).unwrap();
+    tree.token(Label("y"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("c"), 1).unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_root_order(|a: &Label, b: &Label| a.0.cmp(b.0))
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    let x_order = |layer: usize| {
+        let mut nodes = embedding
+            .iter()
+            .filter(|e| e.y_order == layer)
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|e| e.x_center);
+        nodes.iter().map(|e| e.text.as_str()).collect::<Vec<_>>()
+    };
+    assert_eq!(vec!["a", "b", "c"], x_order(0));
+    // Only the roots are sorted
+    assert_eq!(vec!["z", "y"], x_order(1));
+    assert!(embedding.find_overlap().is_none());
+
+    // The root order takes precedence over the sibling order
+    let layouter = Layouter::new(&tree)
+        .with_sibling_sort(|a: &Label, b: &Label| a.0.cmp(b.0))
+        .with_root_order(|a: &Label, b: &Label| b.0.cmp(a.0))
+        .embed_with_visualize()
+        .unwrap();
+    let texts = layouter
+        .embedding()
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["c", "b", "y", "z", "a"], texts);
+}
+
+#[test]
+fn radial_layout_spans_subtrees_by_their_leaves() {
+    //        root
+    //       /    \
+    //      a      b
+    //     /|\     |
+    //    c d e    f
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("a")).unwrap();
+    tree.token(Label("c"), 1).unwrap();
+    tree.token(Label("d"), 1).unwrap();
+    tree.token(Label("e"), 1).unwrap();
+    tree.close().unwrap();
+    tree.open(Label("b")).unwrap();
+    tree.token(Label("f"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_layout_style(LayoutStyle::Radial)
+        .with_direction(LayoutDirection::LeftToRight)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(LayoutStyle::Radial, embedding.layout_style());
+    // The direction is ignored
+    assert_eq!(LayoutDirection::TopToBottom, embedding.direction());
+
+    // Each of the four leaves gets a slot as wide as the widest node "root"
+    let slot = embedding[0].x_extent;
+    assert_eq!(5, slot);
+    let leaves = [2, 3, 4, 6].map(|ord| embedding[ord].x_center);
+    assert_eq!([2, 7, 12, 17], leaves);
+    let spans = [1, 5].map(|ord| embedding[ord].x_extent_children);
+    assert_eq!([3 * slot, slot], spans);
+    assert_eq!(10, embedding[0].x_center);
+    assert_eq!(4 * slot, embedding[0].x_extent_children);
+    assert_eq!(None, embedding.find_overlap());
+}
+
+#[test]
+fn subtree_of_a_chosen_root() {
+    //      0
+    //     / \
+    //    1   5
+    //   / \
+    //  2   3
+    //      |
+    //      4
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    let inner = tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.open(MyNodeData(3)).unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    let leaf = tree.token(MyNodeData(5), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_subtree_root(inner)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    let nodes = embedding
+        .iter()
+        .map(|e| (e.text.as_str(), e.y_order, e.parent))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("1", 0, None),
+            ("2", 1, Some(0)),
+            ("3", 1, Some(0)),
+            ("4", 2, Some(2))
+        ],
+        nodes
+    );
+    assert_eq!((4, 3), embedding.dimensions());
+    assert_eq!(0, embedding[1].x_center - embedding[1].x_extent / 2);
+
+    // A leaf as subtree root gives a single node
+    let layouter = Layouter::new(&tree)
+        .with_subtree_root(leaf)
+        .with_max_depth(0)
+        .embed_with_visualize()
+        .unwrap();
+    assert_eq!(1, layouter.embedding().len());
+    assert_eq!("5", layouter.embedding()[0].text);
+}
+
+#[test]
+fn unknown_subtree_root_is_rejected() {
+    let mut other = Builder::new();
+    other.token(MyNodeData(0), 1).unwrap();
+    let unknown = other.token(MyNodeData(1), 1).unwrap();
+    let mut tree = Builder::new();
+    tree.token(MyNodeData(0), 1).unwrap();
+    let tree = tree.build().unwrap();
+
+    assert!(matches!(
+        Layouter::new(&tree)
+            .with_subtree_root(unknown)
+            .embed_with_visualize(),
+        Err(LayouterError::UnknownSubtreeRoot)
+    ));
+}
+
+#[test]
+fn edges_connect_parents_with_their_children() {
+    //    0      5
+    //   / \     |
+    //  1   4    6
+    //  |\
+    //  2 3
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.token(MyNodeData(3), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.open(MyNodeData(5)).unwrap();
+    tree.token(MyNodeData(6), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let embedding = layouter.embedding();
+    let root_count = embedding.iter().filter(|e| e.parent.is_none()).count();
+    assert_eq!(2, root_count);
+    assert_eq!(embedding.len() - root_count, embedding.edges().count());
+
+    let edges = embedding
+        .edges()
+        .map(|(parent, child)| (parent.text.as_str(), child.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![("0", "1"), ("1", "2"), ("1", "3"), ("0", "4"), ("5", "6")],
+        edges
+    );
+    assert!(embedding
+        .edges()
+        .all(|(parent, child)| child.parent == Some(parent.ord)));
+
+    let tree: Tree<MyNodeData, _, _> = Builder::new().build().unwrap();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!(0, layouter.embedding().edges().count());
+}
+
+#[test]
+fn min_node_width_widens_empty_labels() {
+    //     ""
+    //    /  \
+    //  ""   "wide label"
+    let mut tree = Builder::new();
+    tree.open(Label("")).unwrap();
+    tree.token(Label(""), 1).unwrap();
+    tree.token(Label("wide label"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!(1, layouter.embedding()[0].x_extent);
+
+    let layouter = Layouter::new(&tree)
+        .with_min_node_width(4)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(4, embedding.min_node_width());
+    let extents = embedding.iter().map(|e| e.x_extent).collect::<Vec<_>>();
+    // The sibling spacing is added to the minimum
+    assert_eq!(vec![5, 5, 11], extents);
+    assert!(embedding.find_overlap().is_none());
+}
+
+#[test]
+fn free_layout_functions_match_the_layouter() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(3), 1).unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let embedding = layout::layout(&tree).unwrap();
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!(
+        format!("{:?}", layouter.embedding()),
+        format!("{:?}", embedding)
+    );
+
+    let stringify = |value: &MyNodeData| format!("<{}>", value.0);
+    let emphasize = |value: &MyNodeData| value.0 == 1;
+    let embedding = layout::layout_with(&tree, stringify, emphasize).unwrap();
+    let layouter = Layouter::new(&tree)
+        .embed_with(stringify, emphasize)
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", layouter.embedding()),
+        format!("{:?}", embedding)
+    );
+    assert!(embedding[1].is_emphasized);
+}
+
+#[test]
+fn horizontal_padding_widens_the_nodes_on_both_sides() {
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_horizontal_padding(2)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(2, embedding.horizontal_padding());
+    // The label's width, the padding on both sides and the sibling spacing
+    assert_eq!(4 + 4 + 1, embedding[0].x_extent);
+    assert_eq!(1 + 4 + 1, embedding[1].x_extent);
+    assert_eq!(None, embedding.find_overlap());
+}
+
+#[test]
+fn single_children_are_aligned_with_their_parents() {
+    // Chains of single children with even and odd widths, where the narrower nodes are
+    // centered below and above the wider ones
+    //      ab
+    //       |
+    //   abcdefg
+    //       |
+    //      abc
+    //     /   \
+    //  abcd    a
+    //    |
+    //  abcdefghij
+    let mut tree = Builder::new();
+    tree.open(Label("ab")).unwrap();
+    tree.open(Label("abcdefg")).unwrap();
+    tree.open(Label("abc")).unwrap();
+    tree.open(Label("abcd")).unwrap();
+    tree.token(Label("abcdefghij"), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    for compact in [false, true] {
+        for sibling_spacing in [0, 1, 2] {
+            let layouter = Layouter::new(&tree)
+                .with_compaction(compact)
+                .with_sibling_spacing(sibling_spacing)
+                .embed_with_visualize()
+                .unwrap();
+            let embedding = layouter.embedding();
+            for (parent, child) in [(0, 1), (1, 2), (3, 4)] {
+                assert_eq!(
+                    embedding[parent].x_center, embedding[child].x_center,
+                    "{} and {} aren't aligned",
+                    embedding[parent].text, embedding[child].text
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn streaming_embedding_yields_the_embedded_nodes() {
+    //      0
+    //     / \
+    //    1   2
+    //   / \
+    //  3   4
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.open(MyNodeData(1)).unwrap();
+    tree.token(MyNodeData(3), 1).unwrap();
+    tree.token(MyNodeData(4), 1).unwrap();
+    tree.close().unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    for inverted in [false, true] {
+        let layouter = Layouter::new(&tree)
+            .with_inverted(inverted)
+            .with_right_to_left(true);
+        let nodes = layouter.embed_streaming_with_visualize().unwrap();
+        assert_eq!(5, nodes.len());
+        let streamed = nodes.map(|e| format!("{:?}", e)).collect::<Vec<_>>();
+        let layouter = layouter.embed_with_visualize().unwrap();
+        let embedded = layouter
+            .embedding()
+            .iter()
+            .map(|e| format!("{:?}", e))
+            .collect::<Vec<_>>();
+        assert_eq!(embedded, streamed);
+    }
+}
+
+#[test]
+fn span_layout_sizes_nodes_by_their_spans() {
+    //      0      spans 0..8
+    //     / \
+    //    1   2    spans 0..2 and 2..8
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 2).unwrap();
+    tree.token(MyNodeData(2), 6).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_sibling_spacing(0)
+        .with_span_layout(true)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(8, embedding[0].x_extent);
+    assert_eq!(2, embedding[1].x_extent);
+    assert_eq!(6, embedding[2].x_extent);
+    assert_eq!(3 * embedding[1].x_extent, embedding[2].x_extent);
+    // The siblings follow their spans' starts and cover the parent's span side by side
+    assert!(embedding[1].x_center < embedding[2].x_center);
+    assert_eq!(1, embedding[1].x_center);
+    assert_eq!(5, embedding[2].x_center);
+
+    // Without the span layout the labels determine the extents
+    let layouter = Layouter::new(&tree)
+        .with_sibling_spacing(0)
+        .embed_with_visualize()
+        .unwrap();
+    assert_eq!(1, layouter.embedding()[1].x_extent);
+    assert_eq!(1, layouter.embedding()[2].x_extent);
+}
+
+#[test]
+fn span_layout_pads_the_spans_in_both_directions() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    tree.token(MyNodeData(2), 6).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    for direction in [LayoutDirection::TopToBottom, LayoutDirection::LeftToRight] {
+        let layouter = Layouter::new(&tree)
+            .with_direction(direction)
+            .with_sibling_spacing(0)
+            .with_span_layout(true)
+            .with_min_node_width(3)
+            .with_horizontal_padding(1)
+            .embed_with_visualize()
+            .unwrap();
+        let embedding = layouter.embedding();
+        // The short span is widened to the minimum, both are padded
+        assert_eq!(3 + 2, embedding[1].x_extent, "{:?}", direction);
+        assert_eq!(6 + 2, embedding[2].x_extent, "{:?}", direction);
+    }
+}
+
+#[test]
+fn rounding_centers_parents_over_their_children() {
+    //         r
+    //      /     \
+    //     x       y
+    //    / \     / \
+    //  ab   cd ef   gh
+    let mut tree = Builder::new();
+    tree.open(Label("r")).unwrap();
+    for (inner, leaves) in [("x", ["ab", "cd"]), ("y", ["ef", "gh"])] {
+        tree.open(Label(inner)).unwrap();
+        for leaf in leaves {
+            tree.token(Label(leaf), 1).unwrap();
+        }
+        tree.close().unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree)
+        .with_rounding(Rounding::Nearest)
+        .embed_with_visualize()
+        .unwrap();
+    let embedding = layouter.embedding();
+    assert_eq!(
+        2 * embedding[0].x_center,
+        embedding[1].x_center + embedding[4].x_center
+    );
+
+    // Parents over two children of odd width, which can't be centered exactly
+    for (right, expected) in [
+        (
+            "a",
+            [
+                (Rounding::Floor, 2),
+                (Rounding::Ceil, 3),
+                (Rounding::Nearest, 2),
+            ],
+        ),
+        (
+            "abc",
+            [
+                (Rounding::Floor, 3),
+                (Rounding::Ceil, 4),
+                (Rounding::Nearest, 4),
+            ],
+        ),
+    ] {
+        let mut tree = Builder::new();
+        tree.open(Label("r")).unwrap();
+        tree.token(Label("ab"), 1).unwrap();
+        tree.token(Label(right), 1).unwrap();
+        tree.close().unwrap();
+        let tree = tree.build().unwrap();
+
+        for (rounding, x_center) in expected {
+            let layouter = Layouter::new(&tree)
+                .with_rounding(rounding)
+                .embed_with_visualize()
+                .unwrap();
+            assert_eq!(x_center, layouter.embedding()[0].x_center, "{:?}", rounding);
+        }
+    }
+}
+
+#[test]
+fn subtree_gutter_separates_sibling_subtrees() {
+    //        0
+    //      /   \
+    //     1     2
+    //    / \   / \
+    //   3   4 5   6
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    for (inner, leaves) in [(1, [3, 4]), (2, [5, 6])] {
+        tree.open(MyNodeData(inner)).unwrap();
+        for leaf in leaves {
+            tree.token(MyNodeData(leaf), 1).unwrap();
+        }
+        tree.close().unwrap();
+    }
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let left = |e: &syntree_layout::EmbeddedNode| e.x_center - e.x_extent / 2;
+    let right = |e: &syntree_layout::EmbeddedNode| e.x_center + (e.x_extent - e.x_extent / 2);
+    for compact in [false, true] {
+        let layouter = Layouter::new(&tree)
+            .with_subtree_gutter(3)
+            .with_compaction(compact)
+            .embed_with_visualize()
+            .unwrap();
+        let embedding = layouter.embedding();
+        let node = |value: &str| embedding.iter().find(|e| e.text == value).unwrap();
+        // The gap between the nearest edges of the sibling subtrees 1 and 2 is the gutter
+        assert_eq!(
+            3,
+            left(node("5")) - right(node("4")),
+            "compact: {}",
+            compact
+        );
+        // Also between the leaves within each subtree
+        assert_eq!(
+            3,
+            left(node("4")) - right(node("3")),
+            "compact: {}",
+            compact
+        );
+        assert_eq!(
+            3,
+            left(node("6")) - right(node("5")),
+            "compact: {}",
+            compact
+        );
+    }
+
+    // Without a gutter the subtrees touch
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let node = |value: &str| {
+        layouter
+            .embedding()
+            .iter()
+            .find(|e| e.text == value)
+            .unwrap()
+    };
+    assert_eq!(right(node("4")), left(node("5")));
+}
+
+#[test]
+fn text_measure_replaces_the_character_count() {
+    let mut tree = Builder::new();
+    tree.open(Label("root")).unwrap();
+    tree.open(Label("expression")).unwrap();
+    tree.token(Label("a"), 1).unwrap();
+    tree.token(Label("+"), 1).unwrap();
+    tree.token(Label("bc"), 2).unwrap();
+    tree.close().unwrap();
+    tree.token(Label(";"), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let extents = |layouter: Layouter<'_, '_, '_, Label, u32, usize>| {
+        layouter
+            .with_sibling_spacing(0)
+            .embed_with_visualize()
+            .unwrap()
+            .embedding()
+            .iter()
+            .map(|e| e.x_extent)
+            .collect::<Vec<_>>()
+    };
+
+    let counted = extents(Layouter::new(&tree));
+    let doubled = extents(
+        Layouter::new(&tree).with_text_measure(|line: &str| 2.0 * line.chars().count() as f32),
+    );
+    assert!(counted.iter().all(|extent| *extent > 0));
+    for (counted, doubled) in counted.iter().zip(&doubled) {
+        assert_eq!(2 * counted, *doubled);
+    }
+
+    // Fractions are rounded up to whole columns
+    let rounded = extents(Layouter::new(&tree).with_text_measure(|_| 1.2));
+    assert!(rounded.iter().all(|extent| *extent == 2));
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "png")]
+
+use std::path::PathBuf;
+
+use syntree::Builder;
+use syntree_layout::{Layouter, PngDrawer, Visualize};
+
+#[derive(Debug)]
+struct MyNodeData(i32);
+
+impl Visualize for MyNodeData {
+    fn visualize(&self) -> std::string::String {
+        self.0.to_string()
+    }
+}
+
+/// Reads the width and height from the IHDR chunk of a PNG image
+fn png_size(png: &[u8]) -> (u32, u32) {
+    assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+    assert_eq!(b"IHDR", &png[12..16]);
+    let be = |bytes: &[u8]| u32::from_be_bytes(bytes.try_into().unwrap());
+    (be(&png[16..20]), be(&png[20..24]))
+}
+
+/// Reads the value of the given attribute of the svg element
+fn svg_attribute(svg: &str, attribute: &str) -> f32 {
+    let key = format!(" {attribute}=\"");
+    let start = svg.find(&key).unwrap() + key.len();
+    let len = svg[start..].find('"').unwrap();
+    svg[start..start + len].parse().unwrap()
+}
+
+#[test]
+fn png_drawer_scales_the_svg_size() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let svg = layouter.write_to_string().unwrap();
+    let width = svg_attribute(&svg, "width");
+    let height = svg_attribute(&svg, "height");
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("png_drawer.png");
+    let drawer = PngDrawer::new().with_scale(2.0);
+    let layouter = layouter.with_drawer(&drawer).with_file_path(&path);
+    layouter.write().unwrap();
+
+    let png = std::fs::read(&path).unwrap();
+    assert_eq!(
+        ((2.0 * width).ceil() as u32, (2.0 * height).ceil() as u32),
+        png_size(&png)
+    );
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "rayon")]
+
+use syntree::{Builder, Tree};
+use syntree_layout::{Layouter, ParentAlignment, Visualize};
+
+#[derive(Debug)]
+struct MyNodeData(u32);
+
+impl Visualize for MyNodeData {
+    fn visualize(&self) -> std::string::String {
+        self.0.to_string()
+    }
+}
+
+/// A large tree with a varying number of children per node, driven by a simple linear
+/// congruential generator so that it's the same in each run
+fn large_tree(node_count: u32) -> Tree<MyNodeData, u32, usize> {
+    let mut state = 12345_u32;
+    let mut next = move |range: u32| {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (state >> 16) % range
+    };
+    let mut tree = Builder::new();
+    let mut depth = 0;
+    for i in 0..node_count {
+        if depth < 12 && next(3) > 0 {
+            tree.open(MyNodeData(i)).unwrap();
+            depth += 1;
+        } else {
+            tree.token(MyNodeData(i), 1).unwrap();
+        }
+        while depth > 0 && next(4) == 0 {
+            tree.close().unwrap();
+            depth -= 1;
+        }
+    }
+    while depth > 0 {
+        tree.close().unwrap();
+        depth -= 1;
+    }
+    tree.build().unwrap()
+}
+
+#[test]
+fn parallel_centering_matches_sequential_centering() {
+    let tree = large_tree(200_000);
+    let (_, depth) = Layouter::new(&tree)
+        .embed_with_visualize()
+        .unwrap()
+        .embedding()
+        .dimensions();
+    assert!(depth > 10);
+    for (parent_alignment, right_to_left) in [
+        (ParentAlignment::Center, false),
+        (ParentAlignment::OverFirstChild, true),
+        (ParentAlignment::OverLastChild, false),
+    ] {
+        let embed = |parallel: bool| {
+            let layouter = Layouter::new(&tree)
+                .with_parent_alignment(parent_alignment)
+                .with_right_to_left(right_to_left)
+                .with_parallel_centering(parallel)
+                .embed_with_visualize()
+                .unwrap();
+            format!("{:?}", layouter.embedding())
+        };
+        let sequential = embed(false);
+        assert_eq!(sequential, embed(true));
+    }
+}
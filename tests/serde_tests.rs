@@ -0,0 +1,102 @@
+#![cfg(feature = "serde")]
+
+use syntree::Builder;
+use syntree_layout::{Embedding, Layouter, Visualize};
+
+#[derive(Debug)]
+struct MyNodeData(i32);
+
+impl Visualize for MyNodeData {
+    fn visualize(&self) -> std::string::String {
+        self.0.to_string()
+    }
+    fn emphasize(&self) -> bool {
+        self.0 == 1
+    }
+}
+
+#[test]
+fn embedding_json_round_trip() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let json = serde_json::to_string(layouter.embedding()).unwrap();
+    let embedding: Embedding = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(layouter.embedding().len(), embedding.len());
+    assert_eq!(layouter.embedding().direction(), embedding.direction());
+    for (expected, e) in layouter.embedding().iter().zip(embedding.iter()) {
+        assert_eq!(expected.text, e.text);
+        assert_eq!(expected.x_center, e.x_center);
+        assert_eq!(expected.y_order, e.y_order);
+        assert_eq!(expected.is_emphasized, e.is_emphasized);
+        assert_eq!(expected.parent, e.parent);
+        assert_eq!(expected.ord, e.ord);
+    }
+}
+
+#[test]
+fn overlaps_of_deserialized_embeddings_are_found() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    assert_eq!(None, layouter.embedding().find_overlap());
+
+    // Move the second child onto the first one
+    let mut json = serde_json::to_value(layouter.embedding()).unwrap();
+    json["nodes"][2]["x_center"] = json["nodes"][1]["x_center"].clone();
+    let embedding: Embedding = serde_json::from_value(json).unwrap();
+    assert_eq!(Some((1, 2)), embedding.find_overlap());
+}
+
+#[test]
+fn deserialized_embeddings_find_nodes_by_id() {
+    let mut tree = Builder::new();
+    tree.open(MyNodeData(0)).unwrap();
+    tree.token(MyNodeData(1), 1).unwrap();
+    let second = tree.token(MyNodeData(2), 1).unwrap();
+    tree.close().unwrap();
+    let tree = tree.build().unwrap();
+
+    let layouter = Layouter::new(&tree).embed_with_visualize().unwrap();
+    let json = serde_json::to_string(layouter.embedding()).unwrap();
+    let embedding: Embedding = serde_json::from_str(&json).unwrap();
+    assert_eq!("2", embedding.by_node_id(second).unwrap().text);
+}
+
+#[test]
+fn nodes_without_the_later_fields_are_deserialized() {
+    let json = serde_json::json!({
+        "nodes": [{
+            "y_order": 0,
+            "x_center": 1,
+            "x_extent": 3,
+            "x_extent_children": 3,
+            "text": "a",
+            "is_emphasized": false,
+            "background_color": null,
+            "parent": null,
+            "ord": 0
+        }],
+        "direction": "TopToBottom",
+        "layer_spacing": 2
+    });
+    let embedding: Embedding = serde_json::from_value(json).unwrap();
+    let node = &embedding[0];
+    assert_eq!("a", node.text);
+    assert_eq!(1, node.line_count);
+    assert_eq!(None, node.full_text);
+    assert_eq!(None, node.opacity);
+    assert_eq!(0, node.child_count);
+    assert_eq!(0, node.node_id);
+}